@@ -77,7 +77,8 @@ async fn main() {
                 tracing::info!("手机打开url，处理完成后重启程序");
                 std::process::exit(0);
                 //也可以走短信验证
-                // resp = client.request_sms().await.expect("failed to request sms");
+                // client.request_sms().await.expect("failed to request sms");
+                // resp = client.submit_sms_code(&code).await.expect("failed to submit sms code");
             }
             LoginResponse::NeedCaptcha(LoginNeedCaptcha {
                 ref verify_url,
@@ -105,8 +106,11 @@ async fn main() {
                     .await
                     .expect("failed to login with device lock");
             }
-            LoginResponse::AccountFrozen => {
-                panic!("account frozen");
+            LoginResponse::Failed(ref failed) => {
+                panic!(
+                    "login failed: {:?}, {} {}",
+                    failed.kind, failed.title, failed.message
+                );
             }
             LoginResponse::TooManySMSRequest => {
                 panic!("too many sms request");
@@ -115,6 +119,7 @@ async fn main() {
                 ref status,
                 ref tlv_map,
                 ref message,
+                ..
             }) => {
                 panic!(
                     "unknown login status: {:?}, {:?}, {:?}",
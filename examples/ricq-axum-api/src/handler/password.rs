@@ -8,7 +8,10 @@ use serde::{Deserialize, Serialize};
 use ricq::client::{Connector as _, DefaultConnector, NetworkStatus};
 use ricq::ext::reconnect::{Credential, Password};
 use ricq::version::get_version;
-use ricq::{Client, Device, LoginDeviceLocked, LoginNeedCaptcha, LoginResponse, Protocol};
+use ricq::{
+    Client, Device, LoginDeviceLocked, LoginFailedKind, LoginNeedCaptcha, LoginResponse, Protocol,
+    RQError,
+};
 
 use crate::processor::Processor;
 use crate::u8_protocol::U8Protocol;
@@ -62,8 +65,16 @@ impl From<LoginResponse> for PasswordLoginResp {
                 resp.state = "need_captcha".into();
                 resp.captcha_url = verify_url.clone();
             }
-            LoginResponse::AccountFrozen => {
-                resp.state = "account_frozen".into();
+            LoginResponse::Failed(failed) => {
+                resp.state = match failed.kind {
+                    LoginFailedKind::WrongPassword => "wrong_password",
+                    LoginFailedKind::AccountFrozen => "account_frozen",
+                    LoginFailedKind::ProtocolBanned => "protocol_banned",
+                    LoginFailedKind::VersionTooLow => "version_too_low",
+                    LoginFailedKind::AbnormalEnvironment => "abnormal_environment",
+                }
+                .into();
+                resp.message = Some(failed.message);
             }
             LoginResponse::DeviceLocked(LoginDeviceLocked {
                 ref verify_url,
@@ -105,7 +116,7 @@ pub async fn login<P: Processor>(
     let device = Device::random_with_rng(&mut StdRng::seed_from_u64(rand_seed));
     let protocol = Protocol::from_u8(req.protocol);
     let (sender, receiver) = tokio::sync::broadcast::channel(10);
-    let cli = Arc::new(Client::new(device, get_version(protocol.clone()), sender));
+    let cli = Arc::new(Client::new(device, get_version(protocol), sender));
     let connector = DefaultConnector;
     let stream = connector
         .connect(&cli)
@@ -203,20 +214,26 @@ pub async fn request_sms<P: Processor>(
     Json(req): Json<RequestSmsReq>,
     ricq_axum_api: Extension<Arc<RicqAxumApi<P>>>,
 ) -> Result<Json<PasswordLoginResp>, StatusCode> {
-    let resp = ricq_axum_api
+    let result = ricq_axum_api
         .password_clients
         .get(&(req.uin, req.protocol))
         .ok_or(StatusCode::BAD_REQUEST)?
         .client
         .request_sms()
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    ricq_axum_api
-        .password_clients
-        .get_mut(&(req.uin, req.protocol))
-        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?
-        .login_response = resp.clone();
-    Ok(Json(PasswordLoginResp::from(resp)))
+        .await;
+    match result {
+        Ok(sms) => Ok(Json(PasswordLoginResp {
+            state: "sms_sent".into(),
+            sms_phone: sms.phone,
+            message: Some(format!("resend after {}s", sms.resend_interval)),
+            ..Default::default()
+        })),
+        Err(RQError::SmsTooFrequent) => Ok(Json(PasswordLoginResp {
+            state: "too_many_sms_request".into(),
+            ..Default::default()
+        })),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
 }
 
 pub async fn submit_sms<P: Processor>(
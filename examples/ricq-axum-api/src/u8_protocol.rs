@@ -13,6 +13,7 @@ impl U8Protocol for Protocol {
             Protocol::MacOS => 3,
             Protocol::QiDian => 4,
             Protocol::IPad => 5,
+            Protocol::AndroidPad => 6,
         }
     }
 
@@ -23,6 +24,7 @@ impl U8Protocol for Protocol {
             3 => Protocol::MacOS,
             4 => Protocol::QiDian,
             5 => Protocol::IPad,
+            6 => Protocol::AndroidPad,
             _ => Protocol::IPad,
         }
     }
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use jcers::JcePut;
+
+use crate::command::common::pack_uni_request_data;
+use crate::jce;
+use crate::protocol::packet::Packet;
+
+impl super::super::super::Engine {
+    fn build_accost_packet(&self, command: &str, func_name: &str, body: bytes::Bytes) -> Packet {
+        let buf = jce::RequestDataVersion3 {
+            map: HashMap::from([(func_name.to_string(), pack_uni_request_data(&body))]),
+        };
+        let pkt = jce::RequestPacket {
+            i_version: 3,
+            i_request_id: self.next_packet_seq(),
+            s_servant_name: "AccostObj".to_string(),
+            s_func_name: func_name.to_string(),
+            s_buffer: buf.freeze(),
+            ..Default::default()
+        };
+        self.uni_packet(command, pkt.freeze())
+    }
+
+    fn accost_req_header(&self) -> jce::AccostReqHeader {
+        jce::AccostReqHeader {
+            sh_version: 0,
+            l_mid: self.uin(),
+            i_app_id: self.transport.version.app_id as i32,
+        }
+    }
+
+    // AccostSvc.ReqInsertBlackList
+    pub fn build_insert_black_list_packet(&self, uin: i64) -> Packet {
+        let req = jce::ReqInsertBlackList {
+            header: self.accost_req_header(),
+            black_uin: uin,
+        };
+        self.build_accost_packet(
+            "AccostSvc.ReqInsertBlackList",
+            "ReqInsertBlackList",
+            req.freeze(),
+        )
+    }
+
+    // AccostSvc.ReqDeleteBlackList
+    pub fn build_delete_black_list_packet(&self, uin: i64) -> Packet {
+        let req = jce::ReqDeleteBlackList {
+            header: self.accost_req_header(),
+            black_uin: uin,
+        };
+        self.build_accost_packet(
+            "AccostSvc.ReqDeleteBlackList",
+            "ReqDeleteBlackList",
+            req.freeze(),
+        )
+    }
+
+    // AccostSvc.ReqGetBlackList
+    pub fn build_get_black_list_packet(&self, start_index: i64, count: i64) -> Packet {
+        let req = jce::ReqGetBlackList {
+            header: self.accost_req_header(),
+            next_uin: 0,
+            start_index,
+            req_num: count,
+        };
+        self.build_accost_packet("AccostSvc.ReqGetBlackList", "ReqGetBlackList", req.freeze())
+    }
+}
@@ -0,0 +1,45 @@
+use bytes::{Buf, Bytes};
+
+use crate::{jce, RQError, RQResult};
+
+impl super::super::super::Engine {
+    fn decode_accost_response(&self, mut payload: Bytes, func_name: &str) -> RQResult<Bytes> {
+        let mut request: jce::RequestPacket = jcers::from_buf(&mut payload)?;
+        let mut data: jce::RequestDataVersion3 = jcers::from_buf(&mut request.s_buffer)?;
+        let mut rsp = data
+            .map
+            .remove(func_name)
+            .ok_or_else(|| RQError::Decode(format!("missing {func_name}")))?;
+        rsp.advance(1);
+        Ok(rsp)
+    }
+
+    // AccostSvc.ReqInsertBlackList / AccostSvc.ReqDeleteBlackList
+    pub fn decode_black_list_op_response(&self, payload: Bytes, func_name: &str) -> RQResult<()> {
+        let mut rsp = self.decode_accost_response(payload, func_name)?;
+        let rsp: jce::RespBlackListOp = jcers::from_buf(&mut rsp)?;
+        if rsp.header.reply_code != 0 {
+            return Err(RQError::Other(format!(
+                "{func_name} failed: {} {}",
+                rsp.header.reply_code, rsp.header.result
+            )));
+        }
+        Ok(())
+    }
+
+    // AccostSvc.ReqGetBlackList
+    pub fn decode_get_black_list_response(
+        &self,
+        payload: Bytes,
+    ) -> RQResult<jce::RespGetBlackList> {
+        let mut rsp = self.decode_accost_response(payload, "RespGetBlackList")?;
+        let rsp: jce::RespGetBlackList = jcers::from_buf(&mut rsp)?;
+        if rsp.header.reply_code != 0 {
+            return Err(RQError::Other(format!(
+                "get black list failed: {} {}",
+                rsp.header.reply_code, rsp.header.result
+            )));
+        }
+        Ok(rsp)
+    }
+}
@@ -248,4 +248,29 @@ impl super::super::super::Engine {
         body.put_u8(group_id);
         self.build_friend_list_set_group_req_packet(2, body.freeze())
     }
+
+    // friendlist.GetSimpleOnlineFriendInfoReq
+    pub fn build_online_friend_info_request_packet(&self) -> Packet {
+        let payload = jce::GetSimpleOnlineFriendInfoReq {
+            uin: self.uin(),
+            if_show_term_type: 1,
+            version: 1,
+            src_type: 1,
+        };
+        let buf = jce::RequestDataVersion3 {
+            map: HashMap::from([(
+                "FSOLREQ".to_string(),
+                pack_uni_request_data(&payload.freeze()),
+            )]),
+        };
+        let pkt = jce::RequestPacket {
+            i_version: 3,
+            i_request_id: self.next_packet_seq(),
+            s_servant_name: "mqq.IMService.FriendListServiceServantObj".to_string(),
+            s_func_name: "GetSimpleOnlineFriendInfoReq".to_string(),
+            s_buffer: buf.freeze(),
+            ..Default::default()
+        };
+        self.uni_packet("friendlist.GetSimpleOnlineFriendInfoReq", pkt.freeze())
+    }
 }
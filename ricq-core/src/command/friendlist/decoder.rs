@@ -2,7 +2,10 @@ use bytes::{Buf, Bytes};
 use jcers::Jce;
 
 use crate::command::friendlist::*;
-use crate::structs::{FriendInfo, GroupInfo, GroupMemberInfo, GroupMemberPermission};
+use crate::structs::{
+    FriendClientKind, FriendInfo, FriendOnlineStatus, GroupInfo, GroupMemberInfo,
+    GroupMemberPermission, OnlineStatus,
+};
 use crate::{jce, RQError, RQResult};
 
 impl super::super::super::Engine {
@@ -32,6 +35,7 @@ impl super::super::super::Engine {
                     remark: f.remark,
                     face_id: f.face_id,
                     group_id: f.group_id,
+                    status: OnlineStatus::try_from(f.detail_status_flag as i32).ok(),
                 })
                 .collect(),
             friend_groups: resp
@@ -143,4 +147,56 @@ impl super::super::super::Engine {
             .ok_or_else(|| RQError::Decode("decode_remove_friend `DFRESP` not found".into()))?;
         jcers::from_buf(&mut r).map_err(Into::into)
     }
+
+    // friendlist.GetSimpleOnlineFriendInfoReq
+    /// 返回在线好友的状态，不在列表中的好友离线
+    pub fn decode_online_friend_info_response(
+        &self,
+        mut payload: Bytes,
+    ) -> RQResult<Vec<FriendOnlineStatus>> {
+        let mut request: jce::RequestPacket = jcers::from_buf(&mut payload)?;
+        let mut data: jce::RequestDataVersion3 = jcers::from_buf(&mut request.s_buffer)?;
+        let mut resp = data.map.remove("FSOLRESP").ok_or_else(|| {
+            RQError::Decode("decode_online_friend_info_response FSOLRESP not found".into())
+        })?;
+        resp.advance(1);
+        let resp: jce::GetSimpleOnlineFriendInfoResp = jcers::from_buf(&mut resp)?;
+        if resp.result != 0 {
+            return Err(RQError::server_error(
+                "friendlist.GetSimpleOnlineFriendInfoReq",
+                resp.error_code as i32,
+                "",
+            ));
+        }
+        Ok(resp
+            .friend_info_list
+            .into_iter()
+            .map(|f| FriendOnlineStatus {
+                uin: f.friend_uin,
+                online: true,
+                status: OnlineStatus::try_from(f.detail_status_flag as i32).ok(),
+                client: client_kind(&f),
+                battery: (1..=100)
+                    .contains(&f.battery_status)
+                    .then_some(f.battery_status as u8),
+                term_desc: f.term_desc,
+            })
+            .collect())
+    }
+}
+
+/// 按 term_type（与登录设备列表中的 client type 相同）判断客户端类型，未知类型时按手机在线标记判断
+fn client_kind(f: &jce::FriendInfo) -> FriendClientKind {
+    match f.term_type {
+        // Windows QQ、TIM、macOS QQ
+        65793 | 77313 | 65805 => FriendClientKind::Pc,
+        // iPad、Android 平板
+        68361 | 68104 => FriendClientKind::Pad,
+        // Android 手表
+        75023 => FriendClientKind::Watch,
+        // iPhone、Android
+        65799 | 72837 => FriendClientKind::Mobile,
+        _ if f.is_mqq_online != 0 || f.is_iphone_online != 0 => FriendClientKind::Mobile,
+        _ => FriendClientKind::Unknown,
+    }
 }
@@ -1,3 +1,5 @@
+use bytes::Bytes;
+
 use crate::protocol::packet::*;
 
 impl super::super::super::Engine {
@@ -13,4 +15,13 @@ impl super::super::super::Engine {
             ..Default::default()
         }
     }
+
+    // trpc.qq_new_tech.status_svc.StatusService.SsoHeartBeat
+    pub fn build_sso_heartbeat_packet(&self) -> Packet {
+        // type: 1
+        self.uni_packet(
+            "trpc.qq_new_tech.status_svc.StatusService.SsoHeartBeat",
+            Bytes::from_static(&[0x08, 0x01]),
+        )
+    }
 }
@@ -41,4 +41,30 @@ impl super::super::super::Engine {
             }
         })
     }
+
+    pub fn decode_group_image_url_response(&self, payload: Bytes) -> RQResult<String> {
+        let mut rsp = pb::cmd0x388::D388RspBody::decode(&*payload)?;
+        let rsp = rsp
+            .getimg_url_rsp
+            .pop()
+            .ok_or(EmptyField("getimg_url_rsp"))?;
+        if rsp.result() != 0 {
+            return Err(RQError::Other(
+                String::from_utf8_lossy(&rsp.fail_msg.unwrap_or_default()).into_owned(),
+            ));
+        }
+        let para = rsp
+            .original_down_para
+            .or(rsp.big_down_para)
+            .ok_or(EmptyField("original_down_para"))?;
+        let domain = rsp
+            .down_domain
+            .map(|d| String::from_utf8_lossy(&d).into_owned())
+            .unwrap_or_else(|| "gchat.qpic.cn".into());
+        Ok(format!(
+            "https://{}{}",
+            domain,
+            String::from_utf8_lossy(&para)
+        ))
+    }
 }
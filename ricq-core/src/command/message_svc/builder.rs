@@ -1,3 +1,5 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use prost::encoding::{encode_key, encode_varint, WireType};
 use prost::Message;
 
 use crate::command::common::PbToBytes;
@@ -136,6 +138,64 @@ impl super::super::super::Engine {
         self.uni_packet("MessageSvc.PbSendMsg", req.to_bytes())
     }
 
+    /// 编码 MessageBody，用于向多个目标发送同一条消息时复用
+    pub fn encode_message_body(
+        &self,
+        elems: Vec<pb::msg::Elem>,
+        ptt: Option<pb::msg::Ptt>,
+    ) -> Bytes {
+        pb::msg::MessageBody {
+            rich_text: Some(pb::msg::RichText {
+                elems,
+                ptt,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .to_bytes()
+    }
+
+    // MessageSvc.PbSendMsg
+    // 与 build_send_message_packet 相同，但 msg_body 使用 encode_message_body 编码好的数据
+    pub fn build_send_message_packet_with_body(
+        &self,
+        routing_head: pb::msg::routing_head::RoutingHead,
+        body: &[u8],
+        seq: i32,
+        ran: i32,
+        time: i64,
+    ) -> Packet {
+        let sync_cookie = self.sync_cookie(time);
+        // 按字段顺序拼接: 1-2 | 3 (msg_body) | 4-
+        let head = pb::msg::SendMessageRequest {
+            routing_head: Some(pb::msg::RoutingHead {
+                routing_head: Some(routing_head),
+            }),
+            content_head: Some(pb::msg::ContentHead {
+                pkg_num: Some(1),
+                pkg_index: Some(0),
+                div_seq: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let tail = pb::msg::SendMessageRequest {
+            msg_seq: Some(seq),
+            msg_rand: Some(ran),
+            sync_cookie: Some(sync_cookie),
+            msg_via: Some(1),
+            ..Default::default()
+        };
+        let mut buf =
+            BytesMut::with_capacity(head.encoded_len() + body.len() + tail.encoded_len() + 8);
+        head.encode(&mut buf).unwrap();
+        encode_key(3, WireType::LengthDelimited, &mut buf);
+        encode_varint(body.len() as u64, &mut buf);
+        buf.put_slice(body);
+        tail.encode(&mut buf).unwrap();
+        self.uni_packet("MessageSvc.PbSendMsg", buf.freeze())
+    }
+
     // MessageSvc.PbGetGroupMsg
     pub fn build_get_group_msg_request(
         &self,
@@ -153,6 +213,23 @@ impl super::super::super::Engine {
         self.uni_packet("MessageSvc.PbGetGroupMsg", req.to_bytes())
     }
 
+    // MessageSvc.PbGetOneDayRoamMsg
+    pub fn build_get_friend_roam_msg_request(
+        &self,
+        peer_uin: i64,
+        last_msg_time: i64,
+        random: i64,
+        count: u32,
+    ) -> Packet {
+        let req = pb::msg::PbGetOneDayRoamMsgReq {
+            peer_uin: Some(peer_uin as u64),
+            last_msg_time: Some(last_msg_time as u64),
+            random: Some(random as u64),
+            read_cnt: Some(count),
+        };
+        self.uni_packet("MessageSvc.PbGetOneDayRoamMsg", req.to_bytes())
+    }
+
     pub fn build_friend_recall_packet(
         &self,
         uin: i64,
@@ -1,6 +1,9 @@
 use bytes::{Buf, Bytes};
 
-use crate::pb::msg::GetMessageResponse;
+use crate::command::online_push::GroupMessagePart;
+use crate::pb::msg::{
+    GetGroupMsgResp, GetMessageResponse, PbGetOneDayRoamMsgResp, SendMessageResponse,
+};
 use crate::{jce, RQError, RQResult};
 use prost::Message;
 
@@ -57,4 +60,60 @@ impl crate::Engine {
                 .collect(),
         })
     }
+
+    // MessageSvc.PbSendMsg
+    pub fn decode_send_message_response(&self, payload: Bytes) -> RQResult<()> {
+        let resp = SendMessageResponse::decode(&*payload)?;
+        match resp.result() {
+            0 => Ok(()),
+            code => Err(RQError::server_error(
+                "MessageSvc.PbSendMsg",
+                code,
+                resp.err_msg(),
+            )),
+        }
+    }
+
+    // MessageSvc.PbGetGroupMsg
+    pub fn decode_get_group_msg_response(&self, payload: Bytes) -> RQResult<Vec<GroupMessagePart>> {
+        let resp = GetGroupMsgResp::decode(&*payload)?;
+        if resp.result() != 0 {
+            return Err(RQError::server_error(
+                "MessageSvc.PbGetGroupMsg",
+                resp.result() as i32,
+                resp.errmsg(),
+            ));
+        }
+        // 已撤回等消息没有正文，跳过
+        Ok(resp
+            .msg
+            .into_iter()
+            .filter_map(|msg| GroupMessagePart::from_message(msg).ok())
+            .collect())
+    }
+
+    // MessageSvc.PbGetOneDayRoamMsg
+    pub fn decode_get_friend_roam_msg_response(
+        &self,
+        payload: Bytes,
+    ) -> RQResult<super::FriendRoamMessages> {
+        let resp = PbGetOneDayRoamMsgResp::decode(&*payload)?;
+        if resp.result() != 0 {
+            return Err(RQError::server_error(
+                "MessageSvc.PbGetOneDayRoamMsg",
+                resp.result() as i32,
+                resp.err_msg(),
+            ));
+        }
+        Ok(super::FriendRoamMessages {
+            last_msg_time: resp.last_msg_time() as i64,
+            random: resp.random() as i64,
+            complete: resp.is_complete() == 1,
+            messages: resp
+                .msg
+                .into_iter()
+                .filter_map(|msg| crate::msg::parse_friend_message(msg).ok())
+                .collect(),
+        })
+    }
 }
@@ -1,8 +1,14 @@
+use bytes::Bytes;
+
+use crate::common::group_code2uin;
 use crate::pb;
+use crate::pb::msg::routing_head::RoutingHead;
+use crate::structs::FriendMessage;
 
 pub mod builder;
 pub mod decoder;
 
+#[derive(Debug)]
 pub struct MessageSyncResponse {
     pub msg_rsp_type: i32,
     pub sync_flag: i32,
@@ -10,3 +16,59 @@ pub struct MessageSyncResponse {
     pub pub_account_cookie: Option<Vec<u8>>,
     pub msgs: Vec<pb::msg::Message>,
 }
+
+/// 好友漫游消息
+#[derive(Debug, Default, Clone)]
+pub struct FriendRoamMessages {
+    pub messages: Vec<FriendMessage>,
+    /// 继续向前拉取时使用的 last_msg_time
+    pub last_msg_time: i64,
+    /// 继续向前拉取时使用的 random
+    pub random: i64,
+    /// 是否已经拉取完当天的消息
+    pub complete: bool,
+}
+
+/// 好友消息
+pub fn c2c_routing_head(uin: i64) -> RoutingHead {
+    RoutingHead::C2c(pb::msg::C2c { to_uin: Some(uin) })
+}
+
+/// 群消息
+pub fn grp_routing_head(group_code: i64) -> RoutingHead {
+    RoutingHead::Grp(pb::msg::Grp {
+        group_code: Some(group_code),
+    })
+}
+
+/// 群成员临时会话消息
+pub fn grp_tmp_routing_head(group_code: i64, uin: i64) -> RoutingHead {
+    RoutingHead::GrpTmp(pb::msg::GrpTmp {
+        group_uin: Some(group_code2uin(group_code)),
+        to_uin: Some(uin),
+    })
+}
+
+/// 非群来源（如 QQ 咨询）的临时会话消息，sig 取自收到的消息
+pub fn wpa_tmp_routing_head(uin: i64, sig: Bytes) -> RoutingHead {
+    RoutingHead::WpaTmp(pb::msg::WpaTmp {
+        to_uin: Some(uin as u64),
+        sig: Some(sig.to_vec()),
+    })
+}
+
+/// 通讯录来源的临时会话消息
+pub fn address_list_tmp_routing_head(
+    uin: i64,
+    sig: Bytes,
+    from_phone: String,
+    to_phone: String,
+) -> RoutingHead {
+    RoutingHead::AddressList(pb::msg::AddressListTmp {
+        from_phone: Some(from_phone),
+        to_phone: Some(to_phone),
+        to_uin: Some(uin as u64),
+        sig: Some(sig.to_vec()),
+        ..Default::default()
+    })
+}
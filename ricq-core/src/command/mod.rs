@@ -1,3 +1,4 @@
+pub mod accost_svc;
 pub mod common;
 pub mod config_push_svc;
 pub mod friendlist;
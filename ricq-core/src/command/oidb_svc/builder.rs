@@ -38,6 +38,7 @@ impl super::super::super::Engine {
                         group_member_num: Some(0),
                         group_option: Some(0),
                         group_level: Some(0),
+                        level_name_seq: Some(0),
                         group_face: Some(0),
                         group_name: Some(vec![]),
                         group_memo: Some(vec![]),
@@ -422,6 +423,63 @@ impl super::super::super::Engine {
         let payload = self.transport.encode_oidb_packet(0xeb7, 1, body.to_bytes());
         self.uni_packet("OidbSvc.0xeb7", payload)
     }
+    // OidbSvc.0x6c3_1
+    pub fn build_group_gift_stock_request_packet(&self, group_code: i64) -> Packet {
+        let body = pb::oidb::D6c3ReqBody {
+            group_code: Some(group_code as u64),
+            uin: Some(self.uin() as u64),
+        };
+        let payload = self.transport.encode_oidb_packet(0x6c3, 1, body.to_bytes());
+        self.uni_packet("OidbSvc.0x6c3_1", payload)
+    }
+
+    // OidbSvc.0x962_1
+    pub fn build_group_gift_rank_request_packet(
+        &self,
+        group_code: i64,
+        rank_type: GroupGiftRankType,
+        start: u32,
+        count: u32,
+    ) -> Packet {
+        let body = pb::oidb::D962ReqBody {
+            group_code: Some(group_code as u64),
+            rank_type: Some(rank_type as u32),
+            start: Some(start),
+            count: Some(count),
+        };
+        let payload = self.transport.encode_oidb_packet(0x962, 1, body.to_bytes());
+        self.uni_packet("OidbSvc.0x962_1", payload)
+    }
+
+    // OidbSvc.0x6b6_2
+    pub fn build_group_send_gift_packet(
+        &self,
+        group_code: i64,
+        uin: i64,
+        product_id: u32,
+        count: u32,
+    ) -> Packet {
+        let body = pb::oidb::D6b6ReqBody {
+            group_code: Some(group_code as u64),
+            to_uin: Some(uin as u64),
+            product_id: Some(product_id),
+            count: Some(count),
+        };
+        let payload = self.transport.encode_oidb_packet(0x6b6, 2, body.to_bytes());
+        self.uni_packet("OidbSvc.0x6b6_2", payload)
+    }
+
+    // OidbSvc.0x888_1
+    pub fn build_create_group_packet(&self, name: String, members: Vec<i64>) -> Packet {
+        let body = pb::oidb::D888ReqBody {
+            group_name: Some(name.into_bytes()),
+            member_uins: members.into_iter().map(|uin| uin as u64).collect(),
+            group_class: Some(0),
+        };
+        let payload = self.transport.encode_oidb_packet(0x888, 1, body.to_bytes());
+        self.uni_packet("OidbSvc.0x888_1", payload)
+    }
+
     // OidbSvc.0x6d8_1
     pub fn build_group_file_list_request_packet(
         &self,
@@ -2,12 +2,14 @@ use std::collections::HashMap;
 
 use bytes::{Bytes, BytesMut};
 
-use crate::command::oidb_svc::GroupAtAllRemainInfo;
+use crate::command::oidb_svc::{
+    GroupAtAllRemainInfo, GroupGiftRank, GroupGiftRankItem, GroupGiftStock, GroupSignInResult,
+};
 use crate::structs::{
     GroupFileCount, GroupFileInfo, GroupFileItem, GroupFileList, GroupFolderInfo, GroupInfo,
     GroupMemberPermission,
 };
-use crate::{pb, RQResult};
+use crate::{pb, RQError, RQResult};
 use prost::Message;
 
 use super::OcrResponse;
@@ -35,11 +37,25 @@ impl super::super::super::Engine {
                     shut_up_timestamp: info.shutup_timestamp.unwrap_or_default() as i64,
                     my_shut_up_timestamp: info.shutup_timestamp_me.unwrap_or_default() as i64,
                     last_msg_seq: info.group_cur_msg_seq.unwrap_or_default() as i64,
+                    level_name_seq: info.level_name_seq.unwrap_or_default(),
                 })
             })
             .collect())
     }
 
+    /// 只检查返回码的 oidb 请求
+    pub fn decode_oidb_result(&self, payload: Bytes) -> RQResult<()> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        if pkg.result != 0 {
+            return Err(RQError::server_error(
+                format!("OidbSvc.0x{:x}_{}", pkg.command, pkg.service_type),
+                pkg.result,
+                pkg.error_msg,
+            ));
+        }
+        Ok(())
+    }
+
     // // OidbSvc.0x8a7_0
     pub fn decode_group_at_all_remain_response(
         &self,
@@ -54,6 +70,102 @@ impl super::super::super::Engine {
         })
     }
 
+    // OidbSvc.0x6c3_1
+    pub fn decode_group_gift_stock_response(&self, payload: Bytes) -> RQResult<GroupGiftStock> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        if pkg.result != 0 {
+            return Err(RQError::server_error(
+                "OidbSvc.0x6c3_1",
+                pkg.result,
+                pkg.error_msg,
+            ));
+        }
+        let rsp = pb::oidb::D6c3RspBody::decode(&*pkg.bodybuffer)?;
+        Ok(GroupGiftStock {
+            free_gift_count: rsp.free_gift_count(),
+            flower_count: rsp.flower_count(),
+        })
+    }
+
+    // OidbSvc.0x962_1
+    pub fn decode_group_gift_rank_response(&self, payload: Bytes) -> RQResult<GroupGiftRank> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        if pkg.result != 0 {
+            return Err(RQError::server_error(
+                "OidbSvc.0x962_1",
+                pkg.result,
+                pkg.error_msg,
+            ));
+        }
+        let rsp = pb::oidb::D962RspBody::decode(&*pkg.bodybuffer)?;
+        Ok(GroupGiftRank {
+            is_end: rsp.is_end(),
+            items: rsp
+                .rank_items
+                .into_iter()
+                .map(|item| GroupGiftRankItem {
+                    uin: item.uin() as i64,
+                    nick: String::from_utf8_lossy(item.nick()).into_owned(),
+                    score: item.score(),
+                    rank: item.rank(),
+                })
+                .collect(),
+        })
+    }
+
+    // OidbSvc.0x6b6_2
+    /// 返回今日剩余的免费礼物数量
+    pub fn decode_group_send_gift_response(&self, payload: Bytes) -> RQResult<u32> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        if pkg.result != 0 {
+            return Err(RQError::server_error(
+                "OidbSvc.0x6b6_2",
+                pkg.result,
+                pkg.error_msg,
+            ));
+        }
+        let rsp = pb::oidb::D6b6RspBody::decode(&*pkg.bodybuffer)?;
+        if rsp.result() != 0 {
+            return Err(RQError::server_error(
+                "OidbSvc.0x6b6_2",
+                rsp.result(),
+                String::from_utf8_lossy(rsp.err_msg()),
+            ));
+        }
+        Ok(rsp.remain_free_gift_count())
+    }
+
+    // OidbSvc.0x888_1
+    /// 返回新群的群号
+    pub fn decode_create_group_response(&self, payload: Bytes) -> RQResult<i64> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        if pkg.result != 0 {
+            return Err(RQError::server_error(
+                "OidbSvc.0x888_1",
+                pkg.result,
+                pkg.error_msg,
+            ));
+        }
+        let rsp = pb::oidb::D888RspBody::decode(&*pkg.bodybuffer)?;
+        match rsp.group_code() {
+            0 => Err(RQError::EmptyField("group_code")),
+            code => Ok(code as i64),
+        }
+    }
+
+    // OidbSvc.0x8a0_0
+    /// 返回踢出失败的成员 uin
+    pub fn decode_group_kick_response(&self, payload: Bytes) -> RQResult<Vec<i64>> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        let rsp = pb::oidb::D8a0RspBody::decode(&*pkg.bodybuffer)?;
+        Ok(rsp
+            .msg_kick_result
+            .into_iter()
+            .filter(|r| r.opt_uint32_result != 0)
+            .map(|r| r.opt_uint64_member_uin)
+            .collect())
+    }
+
     // OidbSvc.0x990
     pub fn decode_translate_response(&self, payload: Bytes) -> RQResult<Vec<String>> {
         let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
@@ -68,17 +180,48 @@ impl super::super::super::Engine {
         Ok(resp)
     }
 
+    // OidbSvc.0xeb7
+    pub fn decode_group_sign_in_response(&self, payload: Bytes) -> RQResult<GroupSignInResult> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        if pkg.result != 0 {
+            return Err(RQError::server_error(
+                "OidbSvc.0xeb7",
+                pkg.result,
+                pkg.error_msg,
+            ));
+        }
+        let rsp = pb::oidb::Deb7RspBody::decode(&*pkg.bodybuffer)?
+            .sign_in_write_rsp
+            .unwrap_or_default();
+        let ret = rsp.ret.unwrap_or_default();
+        if ret.code() != 0 {
+            return Err(RQError::server_error(
+                "OidbSvc.0xeb7",
+                ret.code() as i32,
+                ret.msg.unwrap_or_default(),
+            ));
+        }
+        let done = rsp.done_info.unwrap_or_default();
+        Ok(GroupSignInResult {
+            title: done.left_title_wrod.unwrap_or_default(),
+            desc: done.right_desc_word.unwrap_or_default(),
+            record_url: done.record_url.unwrap_or_default(),
+            score: rsp
+                .group_score
+                .and_then(|s| s.group_score_word)
+                .unwrap_or_default(),
+        })
+    }
+
     // OidbSvc.0xe07_0
     pub fn decode_image_ocr_response(&self, payload: Bytes) -> RQResult<OcrResponse> {
         let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
-        let resp = pb::oidb::De07RspBody::decode(&*pkg.bodybuffer)?;
+        let resp = pb::oidb::De07RspBody::decode(&*pkg.bodybuffer)?
+            .ocr_rsp_body
+            .unwrap_or_default();
         Ok(OcrResponse {
-            texts: resp
-                .ocr_rsp_body
-                .clone()
-                .unwrap_or_default()
-                .text_detections,
-            language: resp.ocr_rsp_body.unwrap_or_default().language,
+            texts: resp.text_detections.into_iter().map(Into::into).collect(),
+            language: resp.language,
         })
     }
 
@@ -193,3 +336,126 @@ impl super::super::super::Engine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::common::PbToBytes;
+    use crate::protocol::device::Device;
+    use crate::protocol::version::IPAD;
+    use crate::Engine;
+
+    fn oidb_payload(result: i32, error_msg: &str, body: Bytes) -> Bytes {
+        pb::oidb::OidbssoPkg {
+            result,
+            error_msg: error_msg.into(),
+            bodybuffer: body.to_vec(),
+            ..Default::default()
+        }
+        .to_bytes()
+    }
+
+    #[test]
+    fn test_decode_group_gift_stock() {
+        let engine = Engine::new(Device::random(), IPAD);
+        let body = pb::oidb::D6c3RspBody {
+            free_gift_count: Some(3),
+            flower_count: Some(12),
+            err_msg: None,
+        };
+        let stock = engine
+            .decode_group_gift_stock_response(oidb_payload(0, "", body.to_bytes()))
+            .unwrap();
+        assert_eq!((stock.free_gift_count, stock.flower_count), (3, 12));
+
+        let err = engine
+            .decode_group_gift_stock_response(oidb_payload(1, "not member", Bytes::new()))
+            .unwrap_err();
+        assert_eq!(err.command(), Some("OidbSvc.0x6c3_1"));
+    }
+
+    #[test]
+    fn test_decode_group_gift_rank() {
+        let engine = Engine::new(Device::random(), IPAD);
+        let body = pb::oidb::D962RspBody {
+            rank_items: vec![
+                pb::oidb::D962RankItem {
+                    uin: Some(10001),
+                    nick: Some("a".into()),
+                    score: Some(520),
+                    rank: Some(1),
+                },
+                pb::oidb::D962RankItem {
+                    uin: Some(10002),
+                    nick: None,
+                    score: Some(66),
+                    rank: Some(2),
+                },
+            ],
+            is_end: Some(true),
+        };
+        let rank = engine
+            .decode_group_gift_rank_response(oidb_payload(0, "", body.to_bytes()))
+            .unwrap();
+        assert!(rank.is_end);
+        assert_eq!(rank.items.len(), 2);
+        assert_eq!(rank.items[0].uin, 10001);
+        assert_eq!(rank.items[0].nick, "a");
+        assert_eq!((rank.items[0].score, rank.items[0].rank), (520, 1));
+        assert_eq!(rank.items[1].nick, "");
+    }
+
+    #[test]
+    fn test_decode_group_send_gift() {
+        let engine = Engine::new(Device::random(), IPAD);
+        let ok = pb::oidb::D6b6RspBody {
+            result: Some(0),
+            err_msg: None,
+            remain_free_gift_count: Some(4),
+        };
+        assert_eq!(
+            engine
+                .decode_group_send_gift_response(oidb_payload(0, "", ok.to_bytes()))
+                .unwrap(),
+            4
+        );
+
+        let failed = pb::oidb::D6b6RspBody {
+            result: Some(2),
+            err_msg: Some("礼物不足".as_bytes().to_vec()),
+            remain_free_gift_count: Some(0),
+        };
+        let err = engine
+            .decode_group_send_gift_response(oidb_payload(0, "", failed.to_bytes()))
+            .unwrap_err();
+        assert_eq!(err.command(), Some("OidbSvc.0x6b6_2"));
+    }
+
+    #[test]
+    fn test_decode_create_group() {
+        let engine = Engine::new(Device::random(), IPAD);
+        let body = pb::oidb::D888RspBody {
+            group_code: Some(20002),
+            group_uin: Some(20002),
+            err_msg: None,
+        };
+        assert_eq!(
+            engine
+                .decode_create_group_response(oidb_payload(0, "", body.to_bytes()))
+                .unwrap(),
+            20002
+        );
+        assert!(matches!(
+            engine.decode_create_group_response(oidb_payload(
+                0,
+                "",
+                pb::oidb::D888RspBody::default().to_bytes()
+            )),
+            Err(RQError::EmptyField("group_code"))
+        ));
+        assert!(matches!(
+            engine.decode_create_group_response(oidb_payload(1202, "等级不足", Bytes::new())),
+            Err(RQError::AccountLevelTooLow(_))
+        ));
+    }
+}
@@ -13,11 +13,84 @@ pub struct GroupAtAllRemainInfo {
     pub remain_at_all_count_for_uin: u32,
 }
 
+// 群打卡结果
+#[derive(Default, Debug, Clone)]
+pub struct GroupSignInResult {
+    /// 如 "今日已打卡"
+    pub title: String,
+    /// 如 "已打卡 N 天"
+    pub desc: String,
+    pub record_url: String,
+    /// 群积分提示
+    pub score: String,
+}
+
+// 群礼物库存
+#[derive(Default, Debug, Clone)]
+pub struct GroupGiftStock {
+    /// 今日剩余的免费礼物数量
+    pub free_gift_count: u32,
+    /// 剩余的鲜花数量
+    pub flower_count: u32,
+}
+
+// 群礼物排行
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupGiftRankType {
+    /// 收到礼物
+    #[default]
+    Received = 1,
+    /// 送出礼物
+    Sent = 2,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct GroupGiftRankItem {
+    pub uin: i64,
+    pub nick: String,
+    /// 礼物积分
+    pub score: u32,
+    /// 名次，从 1 开始
+    pub rank: u32,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct GroupGiftRank {
+    pub items: Vec<GroupGiftRankItem>,
+    /// 是否已经是最后一页
+    pub is_end: bool,
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct OcrResponse {
-    pub texts: Vec<pb::oidb::TextDetection>,
+    pub texts: Vec<OcrText>,
     pub language: String,
 }
 
+/// OCR 识别出的文本块
+#[derive(Default, Debug, Clone)]
+pub struct OcrText {
+    pub text: String,
+    pub confidence: i32,
+    /// 文本块的多边形顶点 (x, y)
+    pub polygon: Vec<(i32, i32)>,
+    pub advanced_info: String,
+}
+
+impl From<pb::oidb::TextDetection> for OcrText {
+    fn from(detection: pb::oidb::TextDetection) -> Self {
+        Self {
+            text: detection.detected_text,
+            confidence: detection.confidence,
+            polygon: detection
+                .polygon
+                .map(|p| p.coordinates.into_iter().map(|c| (c.x, c.y)).collect())
+                .unwrap_or_default(),
+            advanced_info: detection.advanced_info,
+        }
+    }
+}
+
 // 编辑个人资料
 #[derive(Default, Debug)]
 pub struct ProfileDetailUpdate(pub HashMap<u16, Vec<u8>>);
@@ -3,7 +3,9 @@ use jcers::Jce;
 
 use super::*;
 use crate::common::group_uin2code;
-use crate::structs::{GroupDisband, GroupLeave, GroupMemberPermission, MemberPermissionChange};
+use crate::structs::{
+    GroupDisband, GroupLeave, GroupLeaveKind, GroupMemberPermission, MemberPermissionChange,
+};
 use crate::{jce, pb, RQError, RQResult};
 use prost::Message;
 
@@ -12,35 +14,7 @@ impl super::super::super::Engine {
     // OnlinePush.PbPushGroupMsg
     pub fn decode_group_message_packet(&self, payload: Bytes) -> RQResult<GroupMessagePart> {
         let message = pb::msg::PushMessagePacket::decode(&*payload)?;
-        (|| {
-            let msg = message.message.ok_or("message")?;
-            let head = msg.head.ok_or("head")?;
-            let body = msg.body.ok_or("body")?;
-            let content = msg.content.ok_or("content")?;
-            let rich_text = body.rich_text.ok_or("rich_text")?;
-            let group_info = head.group_info.ok_or("group_info")?;
-            Ok(GroupMessagePart {
-                seq: head.msg_seq.ok_or("msg_seq")?,
-                rand: rich_text.attr.ok_or("attr")?.random.ok_or("attr.random")?,
-                group_code: group_info.group_code.ok_or("group_info.group_code")?,
-                group_name: String::from_utf8_lossy(
-                    &group_info.group_name.ok_or("group_info.group_name")?,
-                )
-                .into_owned(),
-                group_card: String::from_utf8_lossy(
-                    &group_info.group_card.ok_or("group_info.group_card")?,
-                )
-                .into_owned(),
-                from_uin: head.from_uin.ok_or("from_uin")?,
-                elems: rich_text.elems,
-                time: head.msg_time.ok_or("msg_time")?,
-                pkg_num: content.pkg_num.ok_or("pkg_num")?,
-                pkg_index: content.pkg_index.ok_or("pkg_index")?,
-                div_seq: content.div_seq.ok_or("div_seq")?,
-                ptt: rich_text.ptt,
-            })
-        })()
-        .map_err(|e: &'static str| RQError::Decode(format!("{e} is none")))
+        GroupMessagePart::from_message(message.message.ok_or(RQError::EmptyField("message"))?)
     }
 
     // OnlinePush.ReqPush
@@ -103,6 +77,7 @@ impl super::super::super::Engine {
                                 group_code: group_uin2code(group_uin),
                                 member_uin: target,
                                 operator_uin: None,
+                                kind: GroupLeaveKind::Leave,
                             }),
                         });
                     }
@@ -115,6 +90,7 @@ impl super::super::super::Engine {
                                 group_code: group_uin2code(group_uin),
                                 member_uin: target,
                                 operator_uin: Some(operator),
+                                kind: GroupLeaveKind::Kick,
                             }),
                         });
                     }
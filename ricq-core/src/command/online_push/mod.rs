@@ -1,5 +1,5 @@
 use crate::structs::{GroupDisband, GroupLeave, MemberPermissionChange};
-use crate::{jce, pb};
+use crate::{jce, pb, RQError, RQResult};
 
 pub mod builder;
 pub mod decoder;
@@ -45,3 +45,38 @@ pub struct GroupMessagePart {
     // 分片id，相同id的应该合并，且根据pkg_index排序
     pub div_seq: i32,
 }
+
+impl GroupMessagePart {
+    /// 从 pb 消息中取出群消息分片，推送和历史消息通用
+    pub fn from_message(msg: pb::msg::Message) -> RQResult<Self> {
+        (|| {
+            let head = msg.head.ok_or("head")?;
+            let body = msg.body.ok_or("body")?;
+            let rich_text = body.rich_text.ok_or("rich_text")?;
+            let group_info = head.group_info.ok_or("group_info")?;
+            // 历史消息可能没有 content，视为单分片
+            let content = msg.content.unwrap_or_default();
+            Ok(GroupMessagePart {
+                seq: head.msg_seq.ok_or("msg_seq")?,
+                rand: rich_text.attr.ok_or("attr")?.random.ok_or("attr.random")?,
+                group_code: group_info.group_code.ok_or("group_info.group_code")?,
+                group_name: String::from_utf8_lossy(
+                    &group_info.group_name.ok_or("group_info.group_name")?,
+                )
+                .into_owned(),
+                group_card: String::from_utf8_lossy(
+                    &group_info.group_card.ok_or("group_info.group_card")?,
+                )
+                .into_owned(),
+                from_uin: head.from_uin.ok_or("from_uin")?,
+                elems: rich_text.elems,
+                time: head.msg_time.ok_or("msg_time")?,
+                pkg_num: content.pkg_num.unwrap_or(1),
+                pkg_index: content.pkg_index.unwrap_or_default(),
+                div_seq: content.div_seq.unwrap_or_default(),
+                ptt: rich_text.ptt,
+            })
+        })()
+        .map_err(|e: &'static str| RQError::Decode(format!("{e} is none")))
+    }
+}
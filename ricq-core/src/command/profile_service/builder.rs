@@ -100,6 +100,8 @@ impl super::super::super::Engine {
         req_id: i64,
         req_uin: i64,
         accept: bool,
+        block: bool,
+        msg: &str,
     ) -> Packet {
         let req = pb::structmsg::ReqSystemMsgAction {
             msg_type: 1,
@@ -110,7 +112,8 @@ impl super::super::super::Engine {
             sub_src_id: 7,
             action_info: Some(pb::structmsg::SystemMsgActionInfo {
                 r#type: if accept { 2 } else { 3 },
-                blacklist: false,
+                msg: msg.into(),
+                blacklist: block,
                 add_frd_sn_info: Some(pb::structmsg::AddFrdSnInfo::default()),
                 ..Default::default()
             }),
@@ -24,9 +24,10 @@ impl super::super::super::Engine {
                     let msg_time = st.1;
                     let req_uin = st.2;
                     let msg = st.3;
+                    let handled = msg.sub_type == 2;
                     match msg.sub_type {
-                        // 1 进群申请
-                        1 => match msg.group_msg_type {
+                        // 1 未处理 2 已处理
+                        1 | 2 => match msg.group_msg_type {
                             1 => join_group_requests.push(JoinGroupRequest {
                                 msg_seq,
                                 msg_time,
@@ -37,6 +38,8 @@ impl super::super::super::Engine {
                                 group_name: msg.group_name,
                                 actor_uin: msg.actor_uin,
                                 suspicious: !msg.warning_tips.is_empty(),
+                                handled,
+                                decided: msg.msg_decided,
                                 ..Default::default()
                             }),
                             2 => self_invited.push(SelfInvited {
@@ -48,6 +51,9 @@ impl super::super::super::Engine {
                                 group_name: msg.group_name,
                                 actor_uin: msg.actor_uin,
                                 actor_nick: msg.actor_uin_nick,
+                                handled,
+                                decided: msg.msg_decided,
+                                decision: Default::default(),
                             }),
                             22 => join_group_requests.push(JoinGroupRequest {
                                 msg_seq,
@@ -61,11 +67,11 @@ impl super::super::super::Engine {
                                 suspicious: !msg.warning_tips.is_empty(),
                                 invitor_uin: Some(msg.action_uin),
                                 invitor_nick: Some(msg.action_uin_qq_nick),
+                                handled,
+                                decided: msg.msg_decided,
                             }),
                             _ => {}
                         },
-                        // 2 被邀请，不需要处理
-                        2 => {}
                         // ?
                         3 => {}
                         // 自身状态变更(管理员/加群退群)
@@ -106,12 +112,55 @@ impl super::super::super::Engine {
                         req_nick: msg
                             .map(|msg| msg.req_uin_nick.to_owned())
                             .unwrap_or_default(),
+                        source: msg.map(|msg| msg.msg_source.to_owned()).unwrap_or_default(),
+                        source_group_code: msg.map(|msg| msg.group_code).filter(|&code| code != 0),
+                        handled: msg.is_some_and(|msg| msg.sub_type == 2),
+                        decided: msg
+                            .map(|msg| msg.msg_decided.to_owned())
+                            .unwrap_or_default(),
+                        decision: Default::default(),
                     }
                 })
                 .collect(),
         })
     }
 
+    // ProfileService.Pb.ReqSystemMsgAction.Group
+    // ProfileService.Pb.ReqSystemMsgAction.Friend
+    pub fn decode_system_msg_action_response(&self, payload: Bytes, command: &str) -> RQResult<()> {
+        let rsp = pb::structmsg::RspSystemMsgAction::decode(&*payload)?;
+        let head = rsp.head.unwrap_or_default();
+        if head.result != 0 {
+            return Err(RQError::server_error(command, head.result, head.msg_fail));
+        }
+        Ok(())
+    }
+
+    // ProfileService.GroupMngReq
+    pub fn decode_group_mng_response(&self, mut payload: Bytes) -> RQResult<()> {
+        let mut request: jce::RequestPacket = jcers::from_buf(&mut payload)?;
+        let mut data: jce::RequestDataVersion3 = jcers::from_buf(&mut request.s_buffer)?;
+        let mut rsp = data
+            .map
+            .remove("GroupMngRes")
+            .ok_or_else(|| RQError::Decode("missing GroupMngRes".into()))?;
+        rsp.advance(1);
+        let rsp: jce::GroupMngRes = jcers::from_buf(&mut rsp)?;
+        if rsp.result != 0 || rsp.error_code != 0 {
+            let code = if rsp.error_code != 0 {
+                rsp.error_code as i32
+            } else {
+                rsp.result as i32
+            };
+            return Err(RQError::server_error(
+                "ProfileService.GroupMngReq",
+                code,
+                rsp.error_string,
+            ));
+        }
+        Ok(())
+    }
+
     pub fn decode_get_rich_sig_response_packet(
         &self,
         mut payload: Bytes,
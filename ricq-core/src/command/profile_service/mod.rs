@@ -22,6 +22,12 @@ pub struct SelfInvited {
     pub group_name: String,
     pub actor_uin: i64,
     pub actor_nick: String,
+    /// 是否已被处理（包括在其他客户端处理）
+    pub handled: bool,
+    /// 处理结果描述，如 "已同意"
+    pub decided: String,
+    /// 自动处理策略的处理结果
+    pub decision: RequestDecision,
 }
 
 // 用户申请进群
@@ -38,6 +44,10 @@ pub struct JoinGroupRequest {
     pub suspicious: bool,
     pub invitor_uin: Option<i64>,
     pub invitor_nick: Option<String>,
+    /// 是否已被处理（包括被其他管理员处理）
+    pub handled: bool,
+    /// 处理结果描述，如 "已同意"
+    pub decided: String,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -51,6 +61,27 @@ pub struct NewFriendRequest {
     pub message: String,
     pub req_uin: i64,
     pub req_nick: String,
+    /// 申请来源，如 "QQ群"
+    pub source: String,
+    /// 通过群添加时的来源群号
+    pub source_group_code: Option<i64>,
+    /// 是否已被处理（包括在其他客户端处理）
+    pub handled: bool,
+    /// 处理结果描述，如 "已同意"
+    pub decided: String,
+    /// 自动处理策略的处理结果
+    pub decision: RequestDecision,
+}
+
+/// 申请的自动处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derivative::Derivative)]
+#[derivative(Default)]
+pub enum RequestDecision {
+    /// 未自动处理，需要 handler 处理
+    #[derivative(Default)]
+    Pending,
+    Accepted,
+    Rejected,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -8,7 +8,7 @@ use crate::command::common::pack_uni_request_data;
 use crate::command::common::PbToBytes;
 use crate::jce;
 use crate::protocol::packet::*;
-use crate::structs::CustomOnlineStatus;
+use crate::structs::{CustomOnlineStatus, OnlineStatus};
 
 impl super::super::super::Engine {
     // StatSvc.SetStatusFromClient
@@ -59,6 +59,18 @@ impl super::super::super::Engine {
 
     // StatSvc.register
     pub fn build_client_register_packet(&self) -> Packet {
+        self.build_client_register_packet_with_status(OnlineStatus::Online)
+    }
+
+    // StatSvc.register
+    /// 注销客户端，下线前发送
+    pub fn build_client_unregister_packet(&self) -> Packet {
+        self.build_client_register_packet_with_status(OnlineStatus::Offline)
+    }
+
+    // StatSvc.register
+    /// 以指定在线状态注册，如 `OnlineStatus::Invisible` 隐身登录
+    pub fn build_client_register_packet_with_status(&self, status: OnlineStatus) -> Packet {
         let seq = self.next_seq();
         let transport = &self.transport;
 
@@ -66,7 +78,7 @@ impl super::super::super::Engine {
             uin: self.uin(),
             bid: 1 | 2 | 4,
             conn_type: 0,
-            status: 11,
+            status: status as i32,
             kick_pc: 0,
             kick_weak: 0,
             ios_version: transport.device.version.sdk as i64,
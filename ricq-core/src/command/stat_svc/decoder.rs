@@ -74,4 +74,23 @@ impl super::super::super::Engine {
             .ok_or_else(|| RQError::Decode("missing QQService.RequestMSFForceOffline".into()))?;
         jcers::from_buf(&mut data).map_err(RQError::from)
     }
+
+    // StatSvc.SvcReqMSFLoginNotify
+    pub fn decode_msf_login_notify(
+        &self,
+        mut payload: Bytes,
+    ) -> RQResult<jce::SvcReqMSFLoginNotify> {
+        let mut request: jce::RequestPacket =
+            jcers::from_buf(&mut payload).map_err(RQError::from)?;
+        let mut data: jce::RequestDataVersion2 =
+            jcers::from_buf(&mut request.s_buffer).map_err(RQError::from)?;
+        let mut data = data
+            .map
+            .remove("SvcReqMSFLoginNotify")
+            .ok_or_else(|| RQError::Decode("missing SvcReqMSFLoginNotify".into()))?
+            .remove("QQService.SvcReqMSFLoginNotify")
+            .ok_or_else(|| RQError::Decode("missing QQService.SvcReqMSFLoginNotify".into()))?;
+        data.advance(1);
+        jcers::from_buf(&mut data).map_err(RQError::from)
+    }
 }
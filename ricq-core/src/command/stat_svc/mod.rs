@@ -8,7 +8,7 @@ pub struct Status {
     pub custom_status: Option<CustomOnlineStatus>,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum OnlineStatus {
     Online = 11,    // 在线
     Offline = 21,   // 离线
@@ -19,6 +19,23 @@ pub enum OnlineStatus {
     Dnd = 70,       // 请勿打扰
 }
 
+impl TryFrom<i32> for OnlineStatus {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            11 => Self::Online,
+            21 => Self::Offline,
+            31 => Self::Away,
+            41 => Self::Invisible,
+            50 => Self::Busy,
+            60 => Self::Qme,
+            70 => Self::Dnd,
+            _ => return Err(value),
+        })
+    }
+}
+
 impl From<OnlineStatus> for Status {
     fn from(s: OnlineStatus) -> Self {
         Self {
@@ -1,8 +1,10 @@
 use bytes::{Buf, Bytes};
 
+use prost::Message;
+
 use crate::jce::{RespSummaryCard, RespSummaryCardHead};
-use crate::structs::SummaryCardInfo;
-use crate::{jce, RQError, RQResult};
+use crate::structs::{SummaryCardInfo, VipPrivilege};
+use crate::{jce, pb, RQError, RQResult};
 
 impl super::super::super::Engine {
     // SummaryCard.ReqSummaryCard
@@ -27,6 +29,13 @@ impl super::super::super::Engine {
             .ok_or_else(|| RQError::Decode("missing SummaryCard.RespSummaryCard".into()))?;
         rsp.advance(1);
         let rsp: RespSummaryCard = jcers::from_buf(&mut rsp)?;
+        let gate = rsp
+            .services
+            .into_iter()
+            .filter_map(read_service)
+            .find(|(comm, _)| comm.service == Some(16))
+            .and_then(|(_, data)| pb::profilecard::GateVaProfileGateRsp::decode(data).ok())
+            .unwrap_or_default();
         let info = SummaryCardInfo {
             sex: rsp.sex,
             age: rsp.age,
@@ -37,9 +46,40 @@ impl super::super::super::Engine {
             mobile: rsp.mobile,
             uin: rsp.uin,
             login_days: rsp.login_days,
+            province: rsp.province,
+            country: rsp.country,
+            qzone_feeds_desc: rsp.qzone_feeds_desc,
+            like_count: rsp.vote_count,
+            rich_sign: rsp.rich_sign,
+            qid: gate.qid_info.and_then(|q| q.qid).unwrap_or_default(),
+            privileges: gate
+                .st_privilege_rsp
+                .map(|p| p.v_open_priv)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|p| VipPrivilege {
+                    kind: p.i_type(),
+                    level: p.i_level(),
+                    fee_type: p.i_fee_type(),
+                })
+                .collect(),
             cookie: head.cookie,
         };
-        // TODO more info
         Ok(info)
     }
 }
+
+/// 解析 req_services 对应的返回，格式与请求相同：0x28 | comm_len | data_len | comm | data | 0x29
+fn read_service(mut buf: Bytes) -> Option<(pb::profilecard::BusiComm, Bytes)> {
+    if buf.remaining() < 9 {
+        return None;
+    }
+    buf.advance(1);
+    let comm_len = buf.get_u32() as usize;
+    let data_len = buf.get_u32() as usize;
+    if buf.remaining() < comm_len + data_len {
+        return None;
+    }
+    let comm = pb::profilecard::BusiComm::decode(buf.split_to(comm_len)).ok()?;
+    Some((comm, buf.split_to(data_len)))
+}
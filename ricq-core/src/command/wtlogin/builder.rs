@@ -231,6 +231,7 @@ impl super::super::super::Engine {
             command_name: "wtlogin.login".to_string(),
             uin: self.uin(),
             message: "".to_string(),
+            sign: None,
         }
     }
 
@@ -494,6 +495,7 @@ impl super::super::super::Engine {
             command_name: "wtlogin.exchange_emp".into(),
             uin: self.uin(),
             message: "".to_string(),
+            sign: None,
         }
     }
 
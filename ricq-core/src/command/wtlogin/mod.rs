@@ -49,7 +49,8 @@ pub enum LoginResponse {
     Success(LoginSuccess),
     // slider or image captcha
     NeedCaptcha(LoginNeedCaptcha),
-    AccountFrozen,
+    // 冻结、协议被限制、版本过低等，原因见 kind
+    Failed(LoginFailed),
     // sms or qrcode
     DeviceLocked(LoginDeviceLocked),
     TooManySMSRequest,
@@ -94,10 +95,25 @@ pub struct LoginDeviceLocked {
     pub t104: Option<Bytes>,
     pub t174: Option<Bytes>,
     pub t402: Option<Bytes>,
+    /// 服务端返回的已打码手机号
     pub sms_phone: Option<String>,
     pub verify_url: Option<String>,
     pub message: Option<String>,
     pub rand_seed: Option<Bytes>,
+    /// 请求短信后返回：剩余可发送次数
+    pub sms_available_count: Option<u16>,
+    /// 请求短信后返回：多少秒后可以重新发送
+    pub sms_resend_interval: Option<u16>,
+}
+
+/// 请求短信验证码的结果
+#[derive(Debug, Clone)]
+pub struct SmsRequestResult {
+    /// 已打码手机号
+    pub phone: Option<String>,
+    pub available_count: Option<u16>,
+    /// 多少秒后可以重新发送
+    pub resend_interval: u16,
 }
 
 #[derive(Debug, Clone)]
@@ -107,13 +123,64 @@ pub struct LoginDeviceLockLogin {
     pub rand_seed: Option<Bytes>,
 }
 
+/// 登录失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginFailedKind {
+    /// 密码错误
+    WrongPassword,
+    /// 账号被冻结
+    AccountFrozen,
+    /// 协议被限制，需要更换协议或使用签名服务
+    ProtocolBanned,
+    /// 客户端版本过低
+    VersionTooLow,
+    /// 登录环境异常（异地登录、网络环境异常等）
+    AbnormalEnvironment,
+}
+
+impl LoginFailedKind {
+    pub fn from_status(status: u8) -> Option<Self> {
+        Some(match status {
+            1 => Self::WrongPassword,
+            40 => Self::AccountFrozen,
+            45 => Self::ProtocolBanned,
+            235 => Self::VersionTooLow,
+            237 => Self::AbnormalEnvironment,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoginFailed {
+    pub kind: LoginFailedKind,
+    pub status: u8,
+    /// 服务端返回的标题和提示，可以直接展示给用户
+    pub title: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct LoginUnknownStatus {
     pub status: u8,
     pub tlv_map: HashMap<u16, Bytes>,
+    pub title: String,
     pub message: String,
 }
 
+/// 读取 t146/t149 中的错误标题和信息
+fn read_error_tlv(tlv_map: &mut HashMap<u16, Bytes>) -> (String, String) {
+    if let Some(mut v) = tlv_map.remove(&0x146) {
+        v.advance(4); // ver, code
+        return (v.read_string_short(), v.read_string_short());
+    }
+    if let Some(mut v) = tlv_map.remove(&0x149) {
+        v.advance(2);
+        return (v.read_string_short(), v.read_string_short());
+    }
+    Default::default()
+}
+
 impl LoginResponse {
     pub fn decode(
         status: u8,
@@ -163,21 +230,34 @@ impl LoginResponse {
                     }
                 }),
             }),
-            40 => LoginResponse::AccountFrozen,
-            160 | 239 => LoginResponse::DeviceLocked(LoginDeviceLocked {
-                // TODO?
-                sms_phone: tlv_map.remove(&0x178).map(|_| "todo".into()),
-                verify_url: tlv_map
-                    .remove(&0x204)
-                    .map(|v| String::from_utf8_lossy(&v).into_owned()),
-                message: tlv_map
-                    .remove(&0x17e)
-                    .map(|v| String::from_utf8_lossy(&v).into_owned()),
-                rand_seed: tlv_map.remove(&0x403),
-                t104: tlv_map.remove(&0x104),
-                t174: tlv_map.remove(&0x174),
-                t402: tlv_map.remove(&0x402),
-            }),
+            160 | 239 => {
+                let t17b = tlv_map.remove(&0x17b);
+                LoginResponse::DeviceLocked(LoginDeviceLocked {
+                    sms_phone: tlv_map.remove(&0x178).map(|mut v| {
+                        let _country_code = v.read_string_short();
+                        v.read_string_short()
+                    }),
+                    verify_url: tlv_map
+                        .remove(&0x204)
+                        .map(|v| String::from_utf8_lossy(&v).into_owned()),
+                    message: tlv_map
+                        .remove(&0x17e)
+                        .map(|v| String::from_utf8_lossy(&v).into_owned()),
+                    rand_seed: tlv_map.remove(&0x403),
+                    t104: tlv_map.remove(&0x104),
+                    t174: tlv_map.remove(&0x174),
+                    t402: tlv_map.remove(&0x402),
+                    // t17b: 剩余短信次数 u16，重发间隔 u16，长度不足时忽略
+                    sms_available_count: t17b
+                        .clone()
+                        .filter(|v| v.remaining() >= 2)
+                        .map(|mut v| v.get_u16()),
+                    sms_resend_interval: t17b.filter(|v| v.remaining() >= 4).map(|mut v| {
+                        v.advance(2);
+                        v.get_u16()
+                    }),
+                })
+            }
             162 => LoginResponse::TooManySMSRequest,
             204 => LoginResponse::DeviceLockLogin(LoginDeviceLockLogin {
                 t104: tlv_map.remove(&0x104),
@@ -185,21 +265,51 @@ impl LoginResponse {
                 rand_seed: tlv_map.remove(&0x403),
             }),
             _ => {
-                // status=1 可能是密码错误
-                let mut _title = "".into();
-                let mut message = "".into();
-                if let Some(mut v) = tlv_map.remove(&0x146) {
-                    v.advance(4);
-                    _title = v.read_string_short();
-                    message = v.read_string_short();
+                let (title, message) = read_error_tlv(&mut tlv_map);
+                match LoginFailedKind::from_status(status) {
+                    Some(kind) => LoginResponse::Failed(LoginFailed {
+                        kind,
+                        status,
+                        title,
+                        message,
+                    }),
+                    None => LoginResponse::UnknownStatus(LoginUnknownStatus {
+                        status,
+                        tlv_map,
+                        title,
+                        message,
+                    }),
                 }
-                LoginResponse::UnknownStatus(LoginUnknownStatus {
-                    status,
-                    tlv_map,
-                    message,
-                })
             }
         };
         Ok(resp)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_t17b(t17b: &'static [u8]) -> LoginDeviceLocked {
+        let tlv_map = HashMap::from([(0x17b, Bytes::from_static(t17b))]);
+        match LoginResponse::decode(160, tlv_map, &[]).unwrap() {
+            LoginResponse::DeviceLocked(resp) => resp,
+            resp => panic!("unexpected response {resp:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_t17b() {
+        let resp = decode_t17b(&[0, 5, 0, 60]);
+        assert_eq!(resp.sms_available_count, Some(5));
+        assert_eq!(resp.sms_resend_interval, Some(60));
+
+        let resp = decode_t17b(&[0, 5, 0]);
+        assert_eq!(resp.sms_available_count, Some(5));
+        assert_eq!(resp.sms_resend_interval, None);
+
+        let resp = decode_t17b(&[0]);
+        assert_eq!(resp.sms_available_count, None);
+        assert_eq!(resp.sms_resend_interval, None);
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::binary::BinaryReader;
-use crate::crypto::qqtea_decrypt;
+use crate::crypto::qqtea_decrypt_bytes;
 
 #[derive(Debug, Clone)]
 pub struct T161 {
@@ -58,7 +58,7 @@ pub fn decode_t161(mut data: Bytes) -> T161 {
 }
 
 pub fn decode_t119(data: &[u8], ek: &[u8]) -> HashMap<u16, Bytes> {
-    let mut reader = Bytes::from(qqtea_decrypt(data, ek));
+    let mut reader = qqtea_decrypt_bytes(data, ek);
     reader.advance(2);
     reader.read_tlv_map(2)
 }
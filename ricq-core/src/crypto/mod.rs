@@ -2,4 +2,4 @@ mod encrypt;
 mod qqtea;
 
 pub use self::encrypt::{EncryptECDH, EncryptSession, IEncryptMethod};
-pub use self::qqtea::{qqtea_decrypt, qqtea_encrypt};
+pub use self::qqtea::{qqtea_decrypt, qqtea_decrypt_bytes, qqtea_encrypt};
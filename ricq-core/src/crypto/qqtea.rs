@@ -1,5 +1,6 @@
 // copy from https://github.com/zkonge/rtea
 use byteorder::{BigEndian, ByteOrder};
+use bytes::Bytes;
 use rand::{thread_rng, RngCore};
 
 use tea::{GenericArray, Tea16};
@@ -47,9 +48,12 @@ pub fn qqtea_encrypt(text: &[u8], key: &[u8]) -> Vec<u8> {
 }
 
 pub fn qqtea_decrypt(text: &[u8], key: &[u8]) -> Vec<u8> {
-    let mut work_block: Vec<u64> = vec![0; text.len() / 8];
+    qqtea_decrypt_bytes(text, key).to_vec()
+}
 
-    BigEndian::read_u64_into(text, &mut work_block);
+/// 原地解密，只分配一次，返回去掉填充后的切片
+pub fn qqtea_decrypt_bytes(text: &[u8], key: &[u8]) -> Bytes {
+    let mut result = text[..text.len() / 8 * 8].to_vec();
 
     let mut iv1 = 0u64;
     let mut iv2 = 0u64;
@@ -58,8 +62,10 @@ pub fn qqtea_decrypt(text: &[u8], key: &[u8]) -> Vec<u8> {
 
     let cipher = Tea16::new(GenericArray::from_slice(key));
 
-    for block in work_block.iter_mut() {
-        tmp_block = *block ^ iv2;
+    for chunk in result.chunks_exact_mut(8) {
+        let block = BigEndian::read_u64(chunk);
+
+        tmp_block = block ^ iv2;
 
         tmp_block = cipher.decrypt(tmp_block);
 
@@ -67,19 +73,15 @@ pub fn qqtea_decrypt(text: &[u8], key: &[u8]) -> Vec<u8> {
 
         holder = tmp_block ^ iv1;
 
-        iv1 = *block;
+        iv1 = block;
 
-        *block = holder;
+        BigEndian::write_u64(chunk, holder);
     }
 
-    let mut result = vec![0u8; text.len()];
-
-    BigEndian::write_u64_into(&work_block, &mut result);
-
     let begin_pos = ((result[0] as usize) & 7) + 3;
     let end_pos = result.len() - 7;
 
-    result[begin_pos..end_pos].to_owned()
+    Bytes::from(result).slice(begin_pos..end_pos)
 }
 
 mod tea {
@@ -175,3 +177,18 @@ mod tea {
         BigEndian::write_u64(text, n);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decrypt_roundtrip() {
+        let key = [7u8; 16];
+        for len in [0, 1, 7, 8, 100] {
+            let text: Vec<u8> = (0..len as u8).collect();
+            let encrypted = qqtea_encrypt(&text, &key);
+            assert_eq!(qqtea_decrypt_bytes(&encrypted, &key), text);
+        }
+    }
+}
@@ -2,8 +2,30 @@ use std::io;
 
 use thiserror::Error;
 
+use crate::structs::AccountRestriction;
+
 pub type RQResult<T> = Result<T, RQError>;
 
+/// 表示账号限制的返回码 (命令, 返回码, 限制类型)
+const RESTRICTION_CODES: &[(&str, i32, AccountRestriction)] = &[
+    // 群消息被限制，未实名的账号发送群消息时返回
+    ("MessageSvc.PbSendMsg", 46, AccountRestriction::RealName),
+    // 账号等级不足，不能创建群
+    ("OidbSvc.0x888_1", 1202, AccountRestriction::Level),
+    // 账号等级不足，不能同意好友申请
+    (
+        "ProfileService.Pb.ReqSystemMsgAction.Friend",
+        1202,
+        AccountRestriction::Level,
+    ),
+    // 账号等级不足，不能同意入群邀请
+    (
+        "ProfileService.Pb.ReqSystemMsgAction.Group",
+        1202,
+        AccountRestriction::Level,
+    ),
+];
+
 #[derive(Error, Debug)]
 pub enum RQError {
     #[error("other error {0}")]
@@ -24,8 +46,8 @@ pub enum RQError {
     #[error("command_name mismatch, expected {0} get {1}")]
     CommandNameMismatch(String, String),
 
-    #[error("timeout error")]
-    Timeout,
+    #[error("timeout waiting for {command} (seq {seq})")]
+    Timeout { command: String, seq: i32 },
 
     #[error("network error")]
     Network,
@@ -58,4 +80,117 @@ pub enum RQError {
     GetFileCountFailed,
     #[error("failed to get file list: {0}")]
     GetFileListFailed(String),
+    #[error("daily quota exceeded: {0}")]
+    QuotaExceeded(&'static str),
+    #[error("captcha ticket rejected after {0} attempts")]
+    CaptchaTicketRejected(usize),
+    #[error("sms requested too frequently")]
+    SmsTooFrequent,
+    #[error("sms code rejected: {0}")]
+    SmsCodeRejected(String),
+    #[error("client stopped")]
+    ClientStopped,
+    #[error("group owner must transfer ownership or disband the group before quitting")]
+    OwnerCannotQuit,
+    #[error("rate limited: {0}")]
+    RateLimited(&'static str),
+    #[error("realname verification required: {0}")]
+    RealNameRequired(String),
+    #[error("account level too low: {0}")]
+    AccountLevelTooLow(String),
+    #[error("server error {command}: {code} {message}")]
+    ServerError {
+        command: String,
+        code: i32,
+        message: String,
+    },
+    #[error("not logged in")]
+    NeedLogin,
+    #[error("bot is muted in group {0}")]
+    BotMuted(i64),
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
+}
+
+impl RQError {
+    /// 根据命令和返回码识别实名、账号等级限制，message 只用于展示
+    pub fn from_restriction_code(command: &str, code: i32, message: &str) -> Option<Self> {
+        let (_, _, restriction) = RESTRICTION_CODES
+            .iter()
+            .find(|(c, r, _)| *c == command && *r == code)?;
+        Some(match restriction {
+            AccountRestriction::RealName => RQError::RealNameRequired(message.into()),
+            AccountRestriction::Level => RQError::AccountLevelTooLow(message.into()),
+        })
+    }
+
+    /// 服务端返回非 0 结果时的错误，可以识别的账号限制会转换为对应错误
+    pub fn server_error(command: impl Into<String>, code: i32, message: impl Into<String>) -> Self {
+        let command = command.into();
+        let message = message.into();
+        RQError::from_restriction_code(&command, code, &message).unwrap_or(RQError::ServerError {
+            command,
+            code,
+            message,
+        })
+    }
+
+    /// 出错的命令名，只有部分错误携带
+    pub fn command(&self) -> Option<&str> {
+        match self {
+            RQError::Timeout { command, .. } | RQError::ServerError { command, .. } => {
+                Some(command)
+            }
+            RQError::CommandNameMismatch(expected, _) => Some(expected),
+            _ => None,
+        }
+    }
+
+    /// 是否是临时性错误，稍后重试可能成功
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            RQError::Timeout { .. }
+                | RQError::Network
+                | RQError::IO(_)
+                | RQError::PacketDropped
+                | RQError::RateLimited(_)
+        )
+    }
+
+    /// 因账号限制导致失败时返回限制类型
+    pub fn account_restriction(&self) -> Option<AccountRestriction> {
+        match self {
+            RQError::RealNameRequired(_) => Some(AccountRestriction::RealName),
+            RQError::AccountLevelTooLow(_) => Some(AccountRestriction::Level),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restriction_codes() {
+        let err = RQError::server_error("MessageSvc.PbSendMsg", 46, "");
+        assert_eq!(
+            err.account_restriction(),
+            Some(AccountRestriction::RealName)
+        );
+        for command in [
+            "OidbSvc.0x888_1",
+            "ProfileService.Pb.ReqSystemMsgAction.Friend",
+            "ProfileService.Pb.ReqSystemMsgAction.Group",
+        ] {
+            let err = RQError::server_error(command, 1202, "等级不足");
+            assert!(matches!(err, RQError::AccountLevelTooLow(ref m) if m == "等级不足"));
+            assert_eq!(err.account_restriction(), Some(AccountRestriction::Level));
+        }
+        // 返回码只对对应的命令生效
+        let err = RQError::server_error("OidbSvc.0x8a0_0", 1202, "");
+        assert!(matches!(err, RQError::ServerError { code: 1202, .. }));
+        assert_eq!(err.account_restriction(), None);
+    }
 }
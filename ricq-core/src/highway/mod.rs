@@ -29,6 +29,16 @@ pub struct BdhInput {
     pub send_echo: bool,
 }
 
+#[derive(Default, Debug, Clone)]
+pub struct BdhDownloadInput {
+    pub command_id: i32,
+    pub ticket: Vec<u8>,
+    pub ext: Vec<u8>,
+    pub encrypt: bool,
+    // 每次请求的分段大小
+    pub chunk_size: usize,
+}
+
 impl Session {
     fn next_seq(&self) -> i32 {
         self.seq.fetch_add(2, Ordering::Relaxed)
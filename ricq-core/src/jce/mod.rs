@@ -691,6 +691,21 @@ pub struct ReqRichInfo {
     pub dw_time: i64, // 0
 }
 
+/// 退群等群管理操作的 response
+#[derive(Debug, Clone, JceGet, JcePut, Default)]
+pub struct GroupMngRes {
+    #[jce(0)]
+    pub req_type: i32,
+    #[jce(1)]
+    pub result: u8,
+    #[jce(2)]
+    pub vec_body: Bytes,
+    #[jce(3)]
+    pub error_string: String,
+    #[jce(4)]
+    pub error_code: i16,
+}
+
 /// 获取签名response
 #[derive(Debug, Clone, JceGet, JcePut, Default)]
 pub struct GetRichSigRes {
@@ -769,6 +784,20 @@ JceStruct!(FriendInfo {
     54 => new_lover_diamond_flag: u8,
     55 => ext_sns_frd_data: Bytes,
     56 => mutual_mark_data: Bytes,
+    57 => battery_status: i32,
+});
+
+JceStruct!(GetSimpleOnlineFriendInfoReq {
+    0 => uin: i64,
+    1 => if_show_term_type: u8,
+    2 => version: i64,
+    3 => src_type: u8,
+});
+
+JceStruct!(GetSimpleOnlineFriendInfoResp {
+    0 => result: i16,
+    1 => error_code: i16,
+    2 => friend_info_list: Vec<FriendInfo>,
 });
 
 JceStruct!(TroopListRequest {
@@ -916,16 +945,28 @@ pub struct RespSummaryCard {
     pub nickname: String,
     #[jce(5)]
     pub level: i32,
+    #[jce(6)]
+    pub province: String,
     #[jce(7)]
     pub city: String,
     #[jce(8)]
     pub sign: String,
     #[jce(11)]
     pub mobile: String,
+    #[jce(14)]
+    pub qzone_feeds_desc: String,
+    #[jce(16)]
+    pub vote_count: i32,
+    #[jce(19)]
+    pub country: String,
     #[jce(23)]
     pub uin: i64,
+    #[jce(32)]
+    pub rich_sign: Bytes,
     #[jce(36)]
     pub login_days: i64,
+    #[jce(46)]
+    pub services: Vec<Bytes>,
 }
 
 #[derive(Debug, Clone, JceGet, JcePut, Default)]
@@ -991,6 +1032,46 @@ pub struct ReqFavorite {
     pub count: i32,
 }
 
+JceStruct!(AccostReqHeader {
+    0 => sh_version: i16,
+    1 => l_mid: i64,
+    2 => i_app_id: i32,
+});
+
+JceStruct!(AccostRespHeader {
+    0 => sh_version: i16,
+    1 => l_mid: i64,
+    2 => reply_code: i32,
+    3 => result: String,
+});
+
+JceStruct!(ReqInsertBlackList {
+    0 => header: AccostReqHeader,
+    1 => black_uin: i64,
+});
+
+JceStruct!(ReqDeleteBlackList {
+    0 => header: AccostReqHeader,
+    1 => black_uin: i64,
+});
+
+JceStruct!(RespBlackListOp {
+    0 => header: AccostRespHeader,
+});
+
+JceStruct!(ReqGetBlackList {
+    0 => header: AccostReqHeader,
+    1 => next_uin: i64,
+    2 => start_index: i64,
+    3 => req_num: i64,
+});
+
+JceStruct!(RespGetBlackList {
+    0 => header: AccostRespHeader,
+    1 => total: i64,
+    2 => black_uins: Vec<i64>,
+});
+
 #[derive(Debug, Clone, JceGet, JcePut, Default)]
 pub struct MsgType0x210 {
     #[jce(0)]
@@ -1,6 +1,7 @@
 #![feature(type_alias_impl_trait)]
 
 use std::sync::atomic::{AtomicI32, AtomicI64, AtomicU16, Ordering};
+use std::sync::Arc;
 
 use bytes::Bytes;
 use rand::Rng;
@@ -24,18 +25,30 @@ pub mod jce;
 pub mod msg;
 pub mod pb;
 pub mod protocol;
+pub mod replay;
 pub mod structs;
 pub mod token;
 mod utils;
+pub mod web;
 pub mod wtlogin;
 
-// build_packet: param -> bytes
-// decode_packet: bytes -> struct
 // this should be wrapped in a rwlock (readonly after login)
 // TODO: build library for other language
-// no async and await
+/// 协议编解码入口，不依赖异步运行时，可以单独用于测试服务器、回放工具或其他传输层
+///
+/// - `build_*` 方法生成 [`Packet`](protocol::packet::Packet)，经 `encode_packet` 得到可以直接发送的帧（不含长度前缀）
+/// - 收到的帧经 `decode_packet` 解密得到 Packet，再按 `command_name` 调用对应的 `decode_*` 方法
+/// - 登录相关的 `process_*` 方法会修改 sig，需要 `&mut self`
+///
+/// ```ignore
+/// let engine = Engine::new(device, version);
+/// let frame = engine.encode_packet(engine.build_heartbeat_packet());
+/// // 发送 frame，读取返回的帧
+/// let pkt = engine.decode_packet(resp_frame)?;
+/// ```
 pub struct Engine {
-    pub uin: AtomicI64,
+    /// 使用 Arc 以便调用方不获取锁也能读取
+    pub uin: Arc<AtomicI64>,
     pub transport: Transport,
     pub seq_id: AtomicU16,
     pub request_packet_request_id: AtomicI32,
@@ -48,7 +61,7 @@ pub struct Engine {
 impl Engine {
     pub fn new(device: Device, version: Version) -> Self {
         Self {
-            uin: AtomicI64::new(0),
+            uin: Arc::new(AtomicI64::new(0)),
             transport: Transport::new(device, version),
             seq_id: AtomicU16::new(0x3635),
             request_packet_request_id: AtomicI32::new(1921334513),
@@ -88,6 +101,16 @@ impl Engine {
         self.highway_apply_up_seq.fetch_add(2, Ordering::Relaxed)
     }
 
+    /// 加密并编码为发送的帧，不含长度前缀
+    pub fn encode_packet(&self, pkt: protocol::packet::Packet) -> Bytes {
+        self.transport.encode_packet(pkt)
+    }
+
+    /// 解密收到的帧（不含长度前缀）
+    pub fn decode_packet<B: bytes::Buf>(&self, frame: B) -> RQResult<protocol::packet::Packet> {
+        self.transport.decode_packet(frame)
+    }
+
     pub fn gen_token(&self) -> Token {
         Token {
             uin: self.uin(),
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use prost::Message;
+
+use crate::msg::elem::Face;
+use crate::msg::{MessageChainBuilder, PushBuilder};
+use crate::msg::{MessageElem, PushElem};
+use crate::pb::msg;
+use crate::{push_builder_impl, to_elem_vec_impl};
+
+/// 超级表情（大表情动画），对应 service type 37 的 CommonElem
+///
+/// 接收时后面会跟一个兼容旧版本的 Text
+#[derive(Default, Debug, Clone)]
+pub struct AnimatedSticker {
+    pub id: i32,
+    pub name: String,
+}
+
+impl AnimatedSticker {
+    /// 已知的超级表情 id
+    pub const KNOWN_IDS: &'static [i32] = &[
+        5, 53, 74, 75, 114, 137, 181, 311, 312, 314, 317, 318, 319, 320, 324, 325, 326, 333, 337,
+        338, 339, 341, 342, 343, 344, 345, 346, 349, 350, 351, 392, 395, 415, 419, 424, 425, 426,
+        427, 429,
+    ];
+
+    pub fn new(id: i32) -> Self {
+        Self {
+            id,
+            name: Face::name(id).into(),
+        }
+    }
+
+    /// 是否为已知的超级表情 id
+    pub fn is_known(id: i32) -> bool {
+        Self::KNOWN_IDS.contains(&id)
+    }
+}
+
+impl PushElem for AnimatedSticker {
+    fn push_to(e: Self, vec: &mut Vec<MessageElem>) {
+        let elem = msg::MsgElemInfoServtype37 {
+            packid: Some(b"1".to_vec()),
+            stickerid: Some(e.id.to_string().into_bytes()),
+            qsid: Some(e.id as u32),
+            sourcetype: Some(1),
+            stickertype: Some(1),
+            text: Some(format!("/{}", e.name).into_bytes()),
+            randomtype: Some(1),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        vec.push(msg::elem::Elem::CommonElem(msg::CommonElem {
+            service_type: Some(37),
+            pb_elem: Some(elem),
+            business_type: Some(1),
+        }));
+        vec.push(msg::elem::Elem::Text(msg::Text {
+            str: Some(format!("[{}]请使用最新版手机QQ体验新功能", e.name)),
+            ..Default::default()
+        }));
+    }
+}
+
+impl From<msg::MsgElemInfoServtype37> for AnimatedSticker {
+    fn from(e: msg::MsgElemInfoServtype37) -> Self {
+        let id = e.qsid() as i32;
+        let name = String::from_utf8_lossy(e.text())
+            .trim_start_matches('/')
+            .to_owned();
+        Self {
+            id,
+            name: if name.is_empty() {
+                Face::name(id).into()
+            } else {
+                name
+            },
+        }
+    }
+}
+
+impl fmt::Display for AnimatedSticker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.name)
+    }
+}
+
+to_elem_vec_impl!(AnimatedSticker);
+push_builder_impl!(AnimatedSticker);
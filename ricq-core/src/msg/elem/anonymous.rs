@@ -45,3 +45,25 @@ impl From<msg::AnonymousGroupMessage> for Anonymous {
         }
     }
 }
+
+impl Anonymous {
+    /// web 接口使用的匿名 id（anon_id 的 base64）
+    pub fn id(&self) -> String {
+        const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut s = String::with_capacity((self.anon_id.len() + 2) / 3 * 4);
+        for chunk in self.anon_id.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    s.push(TABLE[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    s.push('=');
+                }
+            }
+        }
+        s
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt;
 
 use bytes::{Buf, BufMut};
+use prost::Message;
 
 use crate::msg::{MessageChainBuilder, PushBuilder};
 use crate::msg::{MessageElem, PushElem};
@@ -43,15 +44,30 @@ impl PushElem for At {
 
 impl From<msg::Text> for At {
     fn from(e: msg::Text) -> Self {
-        let (_, mut attr6) = e.attr6_buf().split_at(7);
-        let target = attr6.get_u32() as i64;
         Self {
-            target,
+            target: at_target(&e).unwrap_or_default(),
             display: e.str.unwrap_or_default(),
         }
     }
 }
 
+/// 解析 Text 中的 at 目标，兼容 attr6_buf 和新版 pb_reserve 两种编码，0 表示 @全体成员
+pub(crate) fn at_target(e: &msg::Text) -> Option<i64> {
+    let attr6 = e.attr6_buf();
+    if attr6.len() >= 11 {
+        return Some((&attr6[7..11]).get_u32() as i64);
+    }
+    if !e.str().starts_with('@') {
+        return None;
+    }
+    let attr = msg::TextResvAttr::decode(e.pb_reserve()).ok()?;
+    match attr.at_type() {
+        1 => Some(0),
+        2 if attr.at_member_uin() != 0 => Some(attr.at_member_uin() as i64),
+        _ => None,
+    }
+}
+
 impl fmt::Display for At {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[{}]", self.display)
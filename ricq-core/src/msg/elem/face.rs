@@ -28,6 +28,26 @@ impl Face {
     pub fn new_from_name(name: &str) -> Option<Self> {
         face_name_map(name).map(Self::new)
     }
+
+    /// 连发表情，count 超过 [`FaceBurst::MAX_COUNT`] 时截断
+    pub fn burst(id: i32, count: u32) -> FaceBurst {
+        FaceBurst {
+            face: Self::new(id),
+            count: count.clamp(1, FaceBurst::MAX_COUNT),
+        }
+    }
+}
+
+/// 连发表情：同一表情连续发送时客户端合并成的一个元素，对应 service type 23 的 CommonElem
+#[derive(Default, Debug, Clone)]
+pub struct FaceBurst {
+    pub face: Face,
+    pub count: u32,
+}
+
+impl FaceBurst {
+    /// 一次最多连发的数量
+    pub const MAX_COUNT: u32 = 10;
 }
 
 impl PushElem for Face {
@@ -57,6 +77,31 @@ impl PushElem for Face {
     }
 }
 
+impl PushElem for FaceBurst {
+    fn push_to(e: Self, vec: &mut Vec<MessageElem>) {
+        let text = format!("/{}", e.face.name).into_bytes();
+        let elem = msg::MsgElemInfoServtype23 {
+            face_type: Some(1),
+            face_bubble_count: Some(e.count),
+            face_summary: Some(format!("[{}]x{}", e.face.name, e.count).into_bytes()),
+            flag: Some(1),
+            others: None,
+            yellow_face: Some(msg::MsgElemInfoServtype33 {
+                index: Some(e.face.index as u32),
+                text: Some(text.clone()),
+                compat: Some(text),
+                buf: None,
+            }),
+        }
+        .encode_to_vec();
+        vec.push(msg::elem::Elem::CommonElem(msg::CommonElem {
+            service_type: Some(23),
+            pb_elem: Some(elem),
+            business_type: Some(1),
+        }));
+    }
+}
+
 impl From<msg::Face> for Face {
     fn from(e: msg::Face) -> Self {
         Self::new(e.index())
@@ -69,14 +114,31 @@ impl From<msg::MsgElemInfoServtype33> for Face {
     }
 }
 
+impl From<msg::MsgElemInfoServtype23> for FaceBurst {
+    fn from(e: msg::MsgElemInfoServtype23) -> Self {
+        Self {
+            count: e.face_bubble_count(),
+            face: e.yellow_face.map(Face::from).unwrap_or_default(),
+        }
+    }
+}
+
 impl fmt::Display for Face {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[{}]", self.name)
     }
 }
 
+impl fmt::Display for FaceBurst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.face, self.count)
+    }
+}
+
 to_elem_vec_impl!(Face);
 push_builder_impl!(Face);
+to_elem_vec_impl!(FaceBurst);
+push_builder_impl!(FaceBurst);
 
 #[cfg(test)]
 mod tests {
@@ -87,6 +149,23 @@ mod tests {
         let name = Face::name(1);
         println!("{name:?}")
     }
+
+    #[test]
+    fn test_face_burst_round_trip() {
+        let mut elems = Vec::new();
+        FaceBurst::push_to(Face::burst(14, 5), &mut elems);
+        assert_eq!(elems.len(), 1);
+        match crate::msg::elem::RQElem::from(elems.pop().unwrap()) {
+            crate::msg::elem::RQElem::FaceBurst(burst) => {
+                assert_eq!(burst.face.index, 14);
+                assert_eq!(burst.face.name, "微笑");
+                assert_eq!(burst.count, 5);
+                assert_eq!(burst.to_string(), "[微笑]x5");
+            }
+            e => panic!("unexpected elem {e:?}"),
+        }
+        assert_eq!(Face::burst(14, 100).count, FaceBurst::MAX_COUNT);
+    }
 }
 
 // pub fn face_id_map(key: i32) -> Option<&'static str> {
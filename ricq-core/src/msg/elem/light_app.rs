@@ -2,12 +2,15 @@ use std::fmt;
 use std::io::{Read, Write};
 
 use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
 
 use super::fmt_extract_attr;
 use crate::msg::{MessageChainBuilder, PushBuilder};
 use crate::msg::{MessageElem, PushElem};
 use crate::pb::msg;
-use crate::{push_builder_impl, to_elem_vec_impl};
+use crate::web::{avatar_url, group_avatar_url, AvatarSize};
+use crate::{push_builder_impl, to_elem_vec_impl, RQError, RQResult};
 
 // Some of the share card message will be a LightApp with pkg id `com.tencent.structmsg`
 #[derive(Default, Debug, Clone)]
@@ -19,6 +22,217 @@ impl LightApp {
     pub fn new(content: String) -> Self {
         Self { content }
     }
+
+    /// 将 json 内容解析为指定类型，常见的卡片可以使用 [`LightAppCard`] 和内置的 meta 类型
+    ///
+    /// ```ignore
+    /// let card: LightAppCard<MusicMeta> = light_app.parse()?;
+    /// ```
+    pub fn parse<T: DeserializeOwned>(&self) -> RQResult<T> {
+        serde_json::from_str(&self.content).map_err(|e| RQError::Decode(e.to_string()))
+    }
+
+    /// 卡片的 app 字段，如 `com.tencent.map`
+    pub fn app(&self) -> Option<String> {
+        self.parse::<serde_json::Value>()
+            .ok()?
+            .get("app")?
+            .as_str()
+            .map(String::from)
+    }
+
+    /// 位置分享卡片，lat/lon 为纬度和经度
+    pub fn location(lat: f64, lon: f64, name: &str, address: &str) -> Self {
+        let content = serde_json::json!({
+            "app": "com.tencent.map",
+            "desc": "地图",
+            "view": "LocationShare",
+            "ver": "0.0.0.1",
+            "prompt": format!("[位置]{name}"),
+            "from": 1,
+            "meta": {
+                "Location.Search": {
+                    "id": "",
+                    "name": name,
+                    "address": address,
+                    "lat": lat.to_string(),
+                    "lng": lon.to_string(),
+                    "from": "plusPanel",
+                }
+            },
+            "config": { "forward": 1, "autosize": 1, "type": "card" },
+        });
+        Self::new(content.to_string())
+    }
+
+    /// 推荐好友名片 (`com.tencent.contact.lua`)
+    pub fn contact_card(uin: i64, nickname: &str) -> Self {
+        Self::share_contact(
+            "com.tencent.contact.lua",
+            "推荐好友",
+            nickname,
+            format!("帐号：{uin}"),
+            avatar_url(uin, AvatarSize::S140),
+            format!(
+                "mqqapi://card/show_pslcard?src_type=internal&source=sharecard&version=1&uin={uin}"
+            ),
+        )
+    }
+
+    /// 推荐群聊名片 (`com.tencent.troopsharecard`)，可以用 [`GroupShareMeta`] 解析
+    pub fn group_share_card(group_code: i64, group_name: &str) -> Self {
+        Self::share_contact(
+            "com.tencent.troopsharecard",
+            "推荐群聊",
+            group_name,
+            format!("群号：{group_code}"),
+            group_avatar_url(group_code, AvatarSize::S140),
+            format!("mqqapi://card/show_pslcard?src_type=internal&version=1&uin={group_code}&card_type=group&source=qrcode"),
+        )
+    }
+
+    fn share_contact(
+        app: &str,
+        tag: &str,
+        nickname: &str,
+        contact: String,
+        avatar: String,
+        jump_url: String,
+    ) -> Self {
+        let content = serde_json::json!({
+            "app": app,
+            "desc": "",
+            "view": "contact",
+            "ver": "0.0.0.1",
+            "prompt": format!("{tag}：{nickname}"),
+            "meta": {
+                "contact": {
+                    "nickname": nickname,
+                    "contact": contact,
+                    "avatar": avatar,
+                    "jumpUrl": jump_url,
+                    "tag": tag,
+                }
+            },
+            "config": { "forward": 1, "autosize": 1, "type": "normal" },
+        });
+        Self::new(content.to_string())
+    }
+}
+
+/// LightApp 卡片的公共结构，M 为 meta 的类型
+#[derive(Debug, Clone, Deserialize)]
+pub struct LightAppCard<M> {
+    #[serde(default)]
+    pub app: String,
+    #[serde(default)]
+    pub view: String,
+    #[serde(default)]
+    pub prompt: String,
+    pub meta: M,
+}
+
+/// 推荐群聊卡片 (`com.tencent.troopsharecard`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupShareMeta {
+    pub contact: GroupShareContact,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GroupShareContact {
+    #[serde(default)]
+    pub nickname: String,
+    /// 如 "群号：123456"
+    #[serde(default)]
+    pub contact: String,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(rename = "jumpUrl", default)]
+    pub jump_url: String,
+    #[serde(default)]
+    pub tag: String,
+}
+
+/// 小程序分享卡片 (`com.tencent.miniapp_01`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct MiniAppMeta {
+    pub detail_1: MiniAppDetail,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MiniAppDetail {
+    #[serde(default)]
+    pub appid: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub desc: String,
+    #[serde(default)]
+    pub preview: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub qqdocurl: String,
+    #[serde(default)]
+    pub icon: String,
+}
+
+/// 音乐分享卡片 (`com.tencent.structmsg`，view 为 `music`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct MusicMeta {
+    pub music: MusicDetail,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MusicDetail {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub desc: String,
+    #[serde(rename = "jumpUrl", default)]
+    pub jump_url: String,
+    #[serde(rename = "musicUrl", default)]
+    pub music_url: String,
+    #[serde(default)]
+    pub preview: String,
+    /// 来源，如 "QQ音乐"
+    #[serde(default)]
+    pub tag: String,
+}
+
+/// 位置分享卡片 (`com.tencent.map`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocationMeta {
+    #[serde(rename = "Location.Search")]
+    pub location: LocationDetail,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LocationDetail {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub address: String,
+    /// 纬度
+    #[serde(deserialize_with = "deserialize_coordinate", default)]
+    pub lat: f64,
+    /// 经度
+    #[serde(rename = "lng", deserialize_with = "deserialize_coordinate", default)]
+    pub lon: f64,
+}
+
+/// 坐标可能是字符串也可能是数字
+fn deserialize_coordinate<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Coordinate {
+        Number(f64),
+        Str(String),
+    }
+    match Coordinate::deserialize(deserializer)? {
+        Coordinate::Number(n) => Ok(n),
+        Coordinate::Str(s) => s.parse().map_err(serde::de::Error::custom),
+    }
 }
 
 impl PushElem for LightApp {
@@ -72,3 +286,28 @@ impl fmt::Display for LightApp {
 
 to_elem_vec_impl!(LightApp);
 push_builder_impl!(LightApp);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_round_trip() {
+        let app = LightApp::location(39.9, 116.4, "天安门", "北京市东城区");
+        assert_eq!(app.app().as_deref(), Some("com.tencent.map"));
+        let card: LightAppCard<LocationMeta> = app.parse().unwrap();
+        assert_eq!(card.meta.location.name, "天安门");
+        assert_eq!(card.meta.location.lat, 39.9);
+        assert_eq!(card.meta.location.lon, 116.4);
+    }
+
+    #[test]
+    fn group_share_card_round_trip() {
+        let app = LightApp::group_share_card(123456, "测试群");
+        assert_eq!(app.app().as_deref(), Some("com.tencent.troopsharecard"));
+        let card: LightAppCard<GroupShareMeta> = app.parse().unwrap();
+        assert_eq!(card.prompt, "推荐群聊：测试群");
+        assert_eq!(card.meta.contact.nickname, "测试群");
+        assert_eq!(card.meta.contact.contact, "群号：123456");
+    }
+}
@@ -2,18 +2,24 @@ use std::fmt;
 
 use prost::Message;
 
+pub(crate) use at::at_target;
 pub use group_image::calculate_image_resource_id;
 pub(crate) use text::flush_builder;
 
 pub use crate::msg::elem::{
+    animated_sticker::AnimatedSticker,
     anonymous::Anonymous,
     at::At,
-    face::Face,
+    face::{Face, FaceBurst},
     flash_image::FlashImage,
     friend_image::FriendImage,
     group_image::GroupImage,
-    light_app::LightApp,
+    light_app::{
+        GroupShareContact, GroupShareMeta, LightApp, LightAppCard, LocationDetail, LocationMeta,
+        MiniAppDetail, MiniAppMeta, MusicDetail, MusicMeta,
+    },
     market_face::{Dice, FingerGuessing, MarketFace},
+    red_packet::RedPacket,
     reply::Reply,
     rich_msg::RichMsg,
     text::Text,
@@ -21,6 +27,7 @@ pub use crate::msg::elem::{
 };
 use crate::pb::msg;
 
+mod animated_sticker;
 mod anonymous;
 mod at;
 mod face;
@@ -29,6 +36,7 @@ mod friend_image;
 mod group_image;
 mod light_app;
 mod market_face;
+mod red_packet;
 mod reply;
 mod rich_msg;
 mod text;
@@ -39,6 +47,8 @@ pub enum RQElem {
     At(at::At),
     Text(text::Text),
     Face(face::Face),
+    FaceBurst(face::FaceBurst),
+    AnimatedSticker(animated_sticker::AnimatedSticker),
     MarketFace(market_face::MarketFace),
     Dice(market_face::Dice),
     FingerGuessing(market_face::FingerGuessing),
@@ -48,6 +58,7 @@ pub enum RQElem {
     GroupImage(group_image::GroupImage),
     FlashImage(flash_image::FlashImage),
     VideoFile(video_file::VideoFile),
+    RedPacket(red_packet::RedPacket),
     Other(Box<msg::elem::Elem>),
 }
 
@@ -56,7 +67,7 @@ impl From<msg::elem::Elem> for RQElem {
         match elem {
             msg::elem::Elem::Text(e) => {
                 // TODO guild at
-                if !e.attr6_buf().is_empty() {
+                if at_target(&e).is_some() {
                     RQElem::At(at::At::from(e))
                 } else {
                     RQElem::Text(text::Text::from(e))
@@ -78,6 +89,13 @@ impl From<msg::elem::Elem> for RQElem {
                         RQElem::Other(Box::new(elem))
                     }
                 }
+                23 => {
+                    if let Ok(burst) = msg::MsgElemInfoServtype23::decode(e.pb_elem()) {
+                        RQElem::FaceBurst(face::FaceBurst::from(burst))
+                    } else {
+                        RQElem::Other(Box::new(elem))
+                    }
+                }
                 33 => {
                     if let Ok(new_face) = msg::MsgElemInfoServtype33::decode(e.pb_elem()) {
                         RQElem::Face(face::Face::from(new_face))
@@ -85,6 +103,13 @@ impl From<msg::elem::Elem> for RQElem {
                         RQElem::Other(Box::new(elem))
                     }
                 }
+                37 => {
+                    if let Ok(sticker) = msg::MsgElemInfoServtype37::decode(e.pb_elem()) {
+                        RQElem::AnimatedSticker(animated_sticker::AnimatedSticker::from(sticker))
+                    } else {
+                        RQElem::Other(Box::new(elem))
+                    }
+                }
                 _ => RQElem::Other(Box::new(elem)),
             },
             msg::elem::Elem::MarketFace(e) => {
@@ -103,25 +128,41 @@ impl From<msg::elem::Elem> for RQElem {
                 RQElem::FriendImage(friend_image::FriendImage::from(e))
             }
             msg::elem::Elem::CustomFace(e) => RQElem::GroupImage(group_image::GroupImage::from(e)),
+            msg::elem::Elem::QqWalletMsg(e) => RQElem::RedPacket(red_packet::RedPacket::from(e)),
             _ => RQElem::Other(Box::new(elem)),
         }
     }
 }
 
+impl RQElem {
+    /// 元素本身的文本，不含 Display 在元素后追加的空格，不显示的元素返回 None
+    pub(crate) fn display_inner(&self) -> Option<&dyn fmt::Display> {
+        Some(match self {
+            RQElem::At(e) => e,
+            RQElem::Text(e) => e,
+            RQElem::Face(e) => e,
+            RQElem::FaceBurst(e) => e,
+            RQElem::AnimatedSticker(e) => e,
+            RQElem::GroupImage(e) => e,
+            RQElem::FriendImage(e) => e,
+            RQElem::FlashImage(e) => e,
+            RQElem::LightApp(e) => e,
+            RQElem::RichMsg(e) => e,
+            RQElem::RedPacket(e) => e,
+            _ => return None,
+        })
+    }
+}
+
 impl fmt::Display for RQElem {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            RQElem::At(e) => fmt::Display::fmt(e, f),
-            RQElem::Text(e) => fmt::Display::fmt(e, f),
-            RQElem::Face(e) => fmt::Display::fmt(e, f),
-            RQElem::GroupImage(e) => fmt::Display::fmt(e, f),
-            RQElem::FriendImage(e) => fmt::Display::fmt(e, f),
-            RQElem::FlashImage(e) => fmt::Display::fmt(e, f),
-            RQElem::LightApp(e) => fmt::Display::fmt(e, f),
-            RQElem::RichMsg(e) => fmt::Display::fmt(e, f),
-            _ => return Ok(()),
-        }?;
-        f.write_str(" ")
+        match self.display_inner() {
+            Some(e) => {
+                fmt::Display::fmt(e, f)?;
+                f.write_str(" ")
+            }
+            None => Ok(()),
+        }
     }
 }
 
@@ -156,6 +197,8 @@ macro_rules! impl_from {
 impl_from!(At, at::At);
 impl_from!(Text, text::Text);
 impl_from!(Face, face::Face);
+impl_from!(FaceBurst, face::FaceBurst);
+impl_from!(AnimatedSticker, animated_sticker::AnimatedSticker);
 impl_from!(MarketFace, market_face::MarketFace);
 impl_from!(Dice, market_face::Dice);
 impl_from!(FingerGuessing, market_face::FingerGuessing);
@@ -164,4 +207,35 @@ impl_from!(RichMsg, rich_msg::RichMsg);
 impl_from!(FriendImage, friend_image::FriendImage);
 impl_from!(GroupImage, group_image::GroupImage);
 impl_from!(FlashImage, flash_image::FlashImage);
+impl_from!(RedPacket, red_packet::RedPacket);
 impl_from!(Other, Box<msg::elem::Elem>);
+
+macro_rules! impl_try_from {
+    ($key: tt, $fty: ty) => {
+        impl TryFrom<RQElem> for $fty {
+            type Error = RQElem;
+
+            fn try_from(e: RQElem) -> Result<Self, Self::Error> {
+                match e {
+                    RQElem::$key(e) => Ok(e),
+                    e => Err(e),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from!(At, at::At);
+impl_try_from!(Text, text::Text);
+impl_try_from!(Face, face::Face);
+impl_try_from!(AnimatedSticker, animated_sticker::AnimatedSticker);
+impl_try_from!(MarketFace, market_face::MarketFace);
+impl_try_from!(Dice, market_face::Dice);
+impl_try_from!(FingerGuessing, market_face::FingerGuessing);
+impl_try_from!(LightApp, light_app::LightApp);
+impl_try_from!(RichMsg, rich_msg::RichMsg);
+impl_try_from!(FriendImage, friend_image::FriendImage);
+impl_try_from!(GroupImage, group_image::GroupImage);
+impl_try_from!(FlashImage, flash_image::FlashImage);
+impl_try_from!(VideoFile, video_file::VideoFile);
+impl_try_from!(RedPacket, red_packet::RedPacket);
@@ -0,0 +1,52 @@
+use std::fmt;
+
+use crate::pb::msg;
+
+/// QQ 红包或转账（QQWalletMsg），只能接收
+#[derive(Default, Debug, Clone)]
+pub struct RedPacket {
+    /// 红包类型，如 2 普通红包、3 拼手气红包、6 口令红包
+    pub msg_type: i32,
+    pub red_type: i32,
+    pub sender_uin: i64,
+    /// 如 "恭喜发财"
+    pub title: String,
+    pub bill_no: String,
+}
+
+impl RedPacket {
+    /// 转账消息的 msg_type
+    pub const TRANSFER_MSG_TYPE: i32 = 1;
+
+    /// 是否为转账
+    pub fn is_transfer(&self) -> bool {
+        self.msg_type == Self::TRANSFER_MSG_TYPE
+    }
+}
+
+impl From<msg::QqWalletMsg> for RedPacket {
+    fn from(e: msg::QqWalletMsg) -> Self {
+        let body = e.aio_body.unwrap_or_default();
+        Self {
+            msg_type: body.msg_type(),
+            red_type: body.red_type(),
+            sender_uin: body.send_uin() as i64,
+            title: body
+                .receiver
+                .as_ref()
+                .map(|r| r.title().to_owned())
+                .unwrap_or_default(),
+            bill_no: String::from_utf8_lossy(body.bill_no()).into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for RedPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_transfer() {
+            write!(f, "[QQ转账]{}", self.title)
+        } else {
+            write!(f, "[QQ红包]{}", self.title)
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt;
 
 use crate::msg::{MessageChainBuilder, MessageElem, PushBuilder};
 use crate::pb::msg;
+use crate::structs::{FriendMessage, GroupMessage};
 
 use super::super::MessageChain;
 
@@ -41,6 +42,28 @@ impl PushBuilder for Reply {
     }
 }
 
+impl From<&GroupMessage> for Reply {
+    fn from(m: &GroupMessage) -> Self {
+        Self {
+            reply_seq: m.seqs.first().copied().unwrap_or_default(),
+            sender: m.from_uin,
+            time: m.time,
+            elements: m.elements.clone(),
+        }
+    }
+}
+
+impl From<&FriendMessage> for Reply {
+    fn from(m: &FriendMessage) -> Self {
+        Self {
+            reply_seq: m.seqs.first().copied().unwrap_or_default(),
+            sender: m.from_uin,
+            time: m.time,
+            elements: m.elements.clone(),
+        }
+    }
+}
+
 impl From<msg::SourceMsg> for Reply {
     fn from(e: msg::SourceMsg) -> Self {
         Self {
@@ -17,6 +17,40 @@ pub struct RichMsg {
     pub template1: String,
 }
 
+impl RichMsg {
+    /// 链接分享卡片（serviceID 1），cover 为封面图片地址
+    pub fn share_url(title: &str, summary: &str, url: &str, cover: &str) -> Self {
+        let (title, summary, url, cover) = (
+            xml_escape(title),
+            xml_escape(summary),
+            xml_escape(url),
+            xml_escape(cover),
+        );
+        Self {
+            service_id: 1,
+            template1: format!(
+                r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><msg serviceID="1" templateID="1" action="web" brief="[分享] {title}" sourceMsgId="0" url="{url}" flag="0" adverSign="0" multiMsgFlag="0"><item layout="2"><picture cover="{cover}" w="0" h="0" /><title>{title}</title><summary>{summary}</summary></item><source name="" icon="" action="" appid="-1" /></msg>"#
+            ),
+        }
+    }
+}
+
+/// 转义 xml 属性和文本中的特殊字符
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 impl From<msg::RichMsg> for RichMsg {
     fn from(e: msg::RichMsg) -> Self {
         let data = e.template1.unwrap_or_default();
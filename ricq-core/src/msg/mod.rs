@@ -8,6 +8,10 @@ use crate::pb::msg;
 pub mod elem;
 mod fragment;
 mod macros;
+mod parse;
+mod shortcode;
+
+pub use parse::*;
 
 pub type MessageElem = msg::elem::Elem;
 
@@ -75,6 +79,25 @@ impl MessageChain {
         })
     }
 
+    /// 获取此[`MessageChain`]中的红包或转账
+    pub fn red_packet(&self) -> Option<RedPacket> {
+        self.0.iter().find_map(|e| match e {
+            MessageElem::QqWalletMsg(wallet) => Some(RedPacket::from(wallet.clone())),
+            _ => None,
+        })
+    }
+
+    /// 此[`MessageChain`]中所有 at 的目标，0 表示 @全体成员
+    pub fn at_targets(&self) -> Vec<i64> {
+        self.0
+            .iter()
+            .filter_map(|e| match e {
+                MessageElem::Text(text) => elem::at_target(text),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn with_anonymous(&mut self, anonymous: Anonymous) {
         self.0.insert(0, MessageElem::from(anonymous))
     }
@@ -84,6 +107,83 @@ impl MessageChain {
         let index = if self.anonymous().is_some() { 1 } else { 0 };
         self.0.insert(index, MessageElem::from(reply))
     }
+
+    /// 遍历消息元素，不消耗[`MessageChain`]
+    pub fn iter(&self) -> impl Iterator<Item = RQElem> + '_ {
+        self.0
+            .iter()
+            .filter(|e| !is_meta_elem(e))
+            .map(|e| RQElem::from(e.clone()))
+    }
+
+    /// 所有纯文本元素的内容，不包括 at
+    pub fn texts(&self) -> Vec<String> {
+        self.iter()
+            .filter_map(|e| match e {
+                RQElem::Text(text) => Some(text.content),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 所有图片元素（群图片、好友图片、闪照）
+    pub fn images(&self) -> Vec<RQElem> {
+        self.iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    RQElem::GroupImage(_) | RQElem::FriendImage(_) | RQElem::FlashImage(_)
+                )
+            })
+            .collect()
+    }
+
+    /// 第一个指定类型的元素
+    ///
+    /// ```rust
+    /// use ricq_core::msg::elem::{Face, Text};
+    /// use ricq_core::msg::MessageChain;
+    /// let mut chain = MessageChain::new(Text::new("hi".into()));
+    /// chain.push(Face::new(1));
+    /// assert_eq!(chain.first::<Face>().map(|f| f.index), Some(1));
+    /// ```
+    pub fn first<T: TryFrom<RQElem>>(&self) -> Option<T> {
+        self.iter().find_map(|e| T::try_from(e).ok())
+    }
+
+    /// 只保留 f 返回 true 的元素，引用回复和匿名信息总是保留
+    pub fn retain<F: FnMut(&RQElem) -> bool>(&mut self, mut f: F) {
+        self.0
+            .retain(|e| is_meta_elem(e) || f(&RQElem::from(e.clone())))
+    }
+
+    /// 删除所有指定类型的元素，如 `chain.remove_all::<GroupImage>()`
+    pub fn remove_all<T: TryFrom<RQElem>>(&mut self) {
+        self.retain(|e| T::try_from(e.clone()).is_err())
+    }
+
+    /// 逐个替换元素，f 返回 None 时保留原元素，返回 Some 时替换为其中的元素（为空即删除）
+    ///
+    /// 未替换的元素保持原样，不会重新编码
+    pub fn map_elems<F: FnMut(RQElem) -> Option<MessageChain>>(&mut self, mut f: F) {
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .flat_map(|e| {
+                if is_meta_elem(&e) {
+                    return vec![e];
+                }
+                match f(RQElem::from(e.clone())) {
+                    Some(chain) => chain.0,
+                    None => vec![e],
+                }
+            })
+            .collect();
+    }
+}
+
+/// 引用回复和匿名信息，不作为普通元素遍历
+fn is_meta_elem(e: &MessageElem) -> bool {
+    matches!(e, MessageElem::SrcMsg(_) | MessageElem::AnonGroupMsg(_))
 }
 
 impl<E> FromIterator<E> for MessageChain
@@ -102,11 +202,20 @@ impl IntoIterator for MessageChain {
     fn into_iter(self) -> Self::IntoIter {
         self.0
             .into_iter()
-            .filter(|e| !matches!(e, MessageElem::SrcMsg(_) | MessageElem::AnonGroupMsg(_)))
+            .filter(|e| !is_meta_elem(e))
             .map(RQElem::from)
     }
 }
 
+impl<'a> IntoIterator for &'a MessageChain {
+    type Item = RQElem;
+    type IntoIter = impl Iterator<Item = RQElem> + 'a;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl From<Vec<msg::Elem>> for MessageChain {
     fn from(elements: Vec<msg::Elem>) -> Self {
         Self(elements.into_iter().filter_map(|e| e.elem).collect())
@@ -195,6 +304,62 @@ impl MessageChainBuilder {
         self
     }
 
+    /// 添加一段文本，同 [`MessageChainBuilder::push_str`]
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.push_str(text)
+    }
+
+    /// 换行
+    pub fn newline(&mut self) -> &mut Self {
+        self.push_str("\n")
+    }
+
+    /// at 群成员
+    pub fn at(&mut self, target: i64) -> &mut Self {
+        self.push(At::new(target))
+    }
+
+    /// @全体成员
+    pub fn at_all(&mut self) -> &mut Self {
+        self.push(At {
+            target: 0,
+            display: "@全体成员".into(),
+        })
+    }
+
+    /// 添加表情
+    pub fn face(&mut self, id: i32) -> &mut Self {
+        self.push(Face::new(id))
+    }
+
+    /// 添加已上传的图片
+    pub fn image<I: ImageElem>(&mut self, image: I) -> &mut Self {
+        self.push(image)
+    }
+
+    /// 创建以引用回复开头的构造器
+    ///
+    /// 引用回复只能出现在消息开头，因此只能在创建时指定，添加其他元素后无法再调用：
+    ///
+    /// ```compile_fail
+    /// use ricq_core::msg::elem::Reply;
+    /// use ricq_core::msg::MessageChainBuilder;
+    /// let mut builder = MessageChainBuilder::new();
+    /// builder.text("收到").with_reply(Reply::default());
+    /// ```
+    ///
+    /// ## 示例
+    /// ```ignore
+    /// let mut builder = MessageChainBuilder::with_reply(&event.inner);
+    /// builder.text("收到").face(1);
+    /// ```
+    ///
+    pub fn with_reply(reply: impl Into<Reply>) -> Self {
+        let mut builder = Self::new();
+        builder.push(reply.into());
+        builder
+    }
+
     /// 将此[`MessageChainBuilder`]构造为[`MessageChain`]
     ///
     /// ## 示例
@@ -223,6 +388,13 @@ pub trait PushBuilder {
     fn push_builder(elem: Self, builder: &mut MessageChainBuilder);
 }
 
+/// 可以通过 [`MessageChainBuilder::image`] 添加的图片
+pub trait ImageElem: PushBuilder {}
+
+impl ImageElem for GroupImage {}
+impl ImageElem for FriendImage {}
+impl ImageElem for FlashImage {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,6 +408,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_typed_access() {
+        let mut chain = MessageChain::default();
+        chain.with_reply(Reply::default());
+        chain.push(Text::new("hello".into()));
+        chain.push(Face::new(1));
+        chain.push(Text::new("world".into()));
+        assert_eq!(chain.texts(), vec!["hello", "world"]);
+        assert_eq!(chain.first::<Face>().map(|f| f.index), Some(1));
+
+        chain.remove_all::<Face>();
+        assert!(chain.first::<Face>().is_none());
+        assert!(chain.reply().is_some());
+
+        chain.map_elems(|e| match e {
+            RQElem::Text(t) if t.content == "world" => Some(MessageChain::default()),
+            _ => None,
+        });
+        assert_eq!((&chain).into_iter().count(), 1);
+    }
+
     #[test]
     fn test_display() {
         let mut chain = MessageChain::default();
@@ -288,4 +481,29 @@ mod tests {
             println!("{item:?}")
         }
     }
+
+    #[test]
+    fn test_at_targets() {
+        let mut chain = MessageChain::default();
+        chain.push(At::new(12345));
+        chain.push(Text::new("hello".into()));
+        chain.push(At::new(0));
+        assert_eq!(chain.at_targets(), vec![12345, 0]);
+    }
+
+    #[test]
+    fn test_builder_reply_first() {
+        let mut builder = MessageChainBuilder::with_reply(Reply::default());
+        builder.text("hello").newline().at(12345).face(1);
+        let chain = builder.build();
+        assert!(matches!(chain.0.first(), Some(MessageElem::SrcMsg(_))));
+        assert_eq!(
+            chain
+                .0
+                .iter()
+                .filter(|e| matches!(e, MessageElem::SrcMsg(_)))
+                .count(),
+            1
+        );
+    }
 }
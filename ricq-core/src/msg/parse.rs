@@ -0,0 +1,273 @@
+use crate::command::online_push::GroupMessagePart;
+use crate::pb::msg;
+use crate::structs::{FriendMessage, GroupMessage, GroupTempMessage, TempSessionMessage};
+use crate::{RQError, RQResult};
+
+use super::MessageChain;
+
+/// [`parse_message`] 的解析结果
+#[derive(Debug, Clone)]
+pub enum ParsedMessage {
+    Group(GroupMessage),
+    Friend(FriendMessage),
+    GroupTemp(GroupTempMessage),
+    TempSession(TempSessionMessage),
+}
+
+/// 将 pb 消息解析为群消息、好友消息或临时会话消息，不需要在线的 Client
+///
+/// 用于解析持久化的原始消息，群消息的多个分片需要用 [`parse_group_message`] 合并
+pub fn parse_message(msg: msg::Message) -> RQResult<ParsedMessage> {
+    let head = msg.head.as_ref().ok_or(RQError::EmptyField("head"))?;
+    if head.group_info.is_some() {
+        let part = GroupMessagePart::from_message(msg)?;
+        return Ok(ParsedMessage::Group(parse_group_message(vec![part])));
+    }
+    match head.msg_type() {
+        140 | 141 if is_group_temp(head) => parse_temp_message(msg).map(ParsedMessage::GroupTemp),
+        140 | 141 => parse_temp_session_message(msg).map(ParsedMessage::TempSession),
+        _ => parse_friend_message(msg).map(ParsedMessage::Friend),
+    }
+}
+
+/// 合并群消息分片，分片按 pkg_index 排序
+pub fn parse_group_message(mut parts: Vec<GroupMessagePart>) -> GroupMessage {
+    parts.sort_by(|a, b| a.pkg_index.cmp(&b.pkg_index));
+
+    let group_code = parts.first().map(|p| p.group_code).unwrap_or_default();
+    let group_name = parts
+        .first_mut()
+        .map(|p| std::mem::take(&mut p.group_name))
+        .unwrap_or_default();
+    let group_card = parts
+        .first_mut()
+        .map(|p| std::mem::take(&mut p.group_card))
+        .unwrap_or_default();
+    let from_uin = parts.first().map(|p| p.from_uin).unwrap_or_default();
+    let time = parts.first().map(|p| p.time).unwrap_or_default();
+
+    let mut seqs = Vec::with_capacity(parts.len());
+    let mut rands = Vec::with_capacity(parts.len());
+    let mut elements = Vec::with_capacity(6); // number by experience
+    for p in parts {
+        seqs.push(p.seq);
+        rands.push(p.rand);
+        elements.extend(p.elems.into_iter().filter_map(|e| e.elem));
+    }
+
+    GroupMessage {
+        seqs,
+        rands,
+        group_code,
+        group_name,
+        group_card,
+        from_uin,
+        time,
+        elements: MessageChain(elements),
+    }
+
+    // TODO: extInfo
+    // TODO: group_card_update
+    // TODO: ptt_store
+}
+
+/// 解析好友消息（不包括语音）
+pub fn parse_friend_message(msg: msg::Message) -> RQResult<FriendMessage> {
+    let head = msg.head.ok_or(RQError::EmptyField("head"))?;
+    let rich_text = msg
+        .body
+        .and_then(|body| body.rich_text)
+        .ok_or(RQError::EmptyField("rich_text"))?;
+    let rand = rich_text.attr.as_ref().map(|attr| attr.random());
+    Ok(FriendMessage {
+        seqs: vec![head.msg_seq()],
+        target: head.to_uin.ok_or(RQError::EmptyField("to_uin"))?,
+        time: head.msg_time.ok_or(RQError::EmptyField("msg_time"))?,
+        from_uin: head.from_uin.unwrap_or_default(),
+        from_nick: head.from_nick.unwrap_or_default(),
+        rands: vec![rand.unwrap_or_default()],
+        elements: MessageChain::from(rich_text.elems), // todo ptt_store
+    })
+}
+
+/// 解析群临时会话消息
+pub fn parse_temp_message(msg: msg::Message) -> RQResult<GroupTempMessage> {
+    let head = msg.head.ok_or(RQError::EmptyField("head"))?;
+    let tmp_head = head
+        .c2c_tmp_msg_head
+        .ok_or(RQError::EmptyField("c2c_tmp_msg_head"))?;
+    let rich_text = msg
+        .body
+        .and_then(|body| body.rich_text)
+        .ok_or(RQError::EmptyField("rich_text"))?;
+    let rand = rich_text.attr.as_ref().map(|attr| attr.random());
+    Ok(GroupTempMessage {
+        seqs: vec![head.msg_seq.unwrap_or_default()],
+        rands: vec![rand.unwrap_or_default()],
+        time: head.msg_time.ok_or(RQError::EmptyField("msg_time"))?,
+        from_uin: head.from_uin.unwrap_or_default(),
+        from_nick: head.from_nick.unwrap_or_default(),
+        elements: MessageChain::from(rich_text.elems), // todo ptt_store
+        group_code: tmp_head.group_code.unwrap_or_default(),
+        sig: tmp_head.sig.unwrap_or_default().into(),
+    })
+}
+
+/// 临时会话消息是否来自群
+pub fn is_group_temp(head: &msg::MessageHead) -> bool {
+    head.c2c_tmp_msg_head
+        .as_ref()
+        .map(|h| h.service_type() == 0)
+        .unwrap_or(true)
+}
+
+/// 解析非群来源的临时会话消息
+pub fn parse_temp_session_message(msg: msg::Message) -> RQResult<TempSessionMessage> {
+    let head = msg.head.ok_or(RQError::EmptyField("head"))?;
+    let tmp_head = head
+        .c2c_tmp_msg_head
+        .ok_or(RQError::EmptyField("c2c_tmp_msg_head"))?;
+    let rich_text = msg
+        .body
+        .and_then(|body| body.rich_text)
+        .ok_or(RQError::EmptyField("rich_text"))?;
+    let rand = rich_text.attr.as_ref().map(|attr| attr.random());
+    Ok(TempSessionMessage {
+        source: tmp_head.service_type().into(),
+        seqs: vec![head.msg_seq.unwrap_or_default()],
+        rands: vec![rand.unwrap_or_default()],
+        time: head.msg_time.ok_or(RQError::EmptyField("msg_time"))?,
+        from_uin: head.from_uin.unwrap_or_default(),
+        from_nick: head.from_nick.unwrap_or_default(),
+        elements: MessageChain::from(rich_text.elems),
+        sig: tmp_head.sig.unwrap_or_default().into(),
+        from_phone: tmp_head.from_phone.unwrap_or_default(),
+        to_phone: tmp_head.to_phone.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::elem::Text;
+    use crate::structs::TempSessionSource;
+
+    fn fixture(head: msg::MessageHead, text: &str) -> msg::Message {
+        let mut chain = MessageChain::default();
+        chain.push(Text::new(text.into()));
+        msg::Message {
+            head: Some(head),
+            content: None,
+            body: Some(msg::MessageBody {
+                rich_text: Some(msg::RichText {
+                    attr: Some(msg::Attr {
+                        random: Some(42),
+                        ..Default::default()
+                    }),
+                    elems: chain.into(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn head(msg_type: i32) -> msg::MessageHead {
+        msg::MessageHead {
+            from_uin: Some(10001),
+            to_uin: Some(10002),
+            msg_type: Some(msg_type),
+            msg_seq: Some(7),
+            msg_time: Some(1700000000),
+            from_nick: Some("nick".into()),
+            ..Default::default()
+        }
+    }
+
+    fn tmp_head(service_type: i32) -> msg::C2cTempMessageHead {
+        msg::C2cTempMessageHead {
+            service_type: Some(service_type),
+            group_code: Some(20001),
+            sig: Some(b"sig".to_vec()),
+            from_phone: Some("from".into()),
+            to_phone: Some("to".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_friend() {
+        let msg = fixture(head(166), "hello");
+        let ParsedMessage::Friend(m) = parse_message(msg).unwrap() else {
+            panic!("not a friend message");
+        };
+        assert_eq!(m.seqs, vec![7]);
+        assert_eq!(m.rands, vec![42]);
+        assert_eq!(m.from_uin, 10001);
+        assert_eq!(m.target, 10002);
+        assert_eq!(m.from_nick, "nick");
+        assert_eq!(m.time, 1700000000);
+        assert_eq!(m.elements.texts(), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_parse_group() {
+        let mut h = head(82);
+        h.group_info = Some(msg::GroupInfo {
+            group_code: Some(20001),
+            group_card: Some(b"card".to_vec()),
+            group_name: Some(b"group".to_vec()),
+            ..Default::default()
+        });
+        let ParsedMessage::Group(m) = parse_message(fixture(h, "hi")).unwrap() else {
+            panic!("not a group message");
+        };
+        assert_eq!(m.group_code, 20001);
+        assert_eq!(m.group_name, "group");
+        assert_eq!(m.group_card, "card");
+        assert_eq!(m.seqs, vec![7]);
+        assert_eq!(m.rands, vec![42]);
+        assert_eq!(m.elements.texts(), vec!["hi"]);
+    }
+
+    #[test]
+    fn test_parse_temp() {
+        let mut h = head(141);
+        h.c2c_tmp_msg_head = Some(tmp_head(0));
+        let ParsedMessage::GroupTemp(m) = parse_message(fixture(h, "temp")).unwrap() else {
+            panic!("not a group temp message");
+        };
+        assert_eq!(m.group_code, 20001);
+        assert_eq!(m.from_uin, 10001);
+        assert_eq!(&m.sig[..], b"sig");
+        assert_eq!(m.elements.texts(), vec!["temp"]);
+
+        let mut h = head(141);
+        h.c2c_tmp_msg_head = Some(tmp_head(130));
+        let ParsedMessage::TempSession(m) = parse_message(fixture(h, "phone")).unwrap() else {
+            panic!("not a temp session message");
+        };
+        assert_eq!(m.source, TempSessionSource::AddressBook);
+        assert_eq!(m.from_phone, "from");
+        assert_eq!(m.to_phone, "to");
+        assert_eq!(&m.sig[..], b"sig");
+        assert_eq!(m.elements.texts(), vec!["phone"]);
+    }
+
+    #[test]
+    fn test_parse_missing_fields() {
+        let mut msg = fixture(head(166), "hello");
+        msg.head = None;
+        assert!(parse_message(msg).is_err());
+
+        let mut msg = fixture(head(166), "hello");
+        msg.body = None;
+        assert!(parse_message(msg).is_err());
+
+        let mut h = head(166);
+        h.msg_time = None;
+        assert!(parse_friend_message(fixture(h, "hello")).is_err());
+
+        assert!(parse_temp_session_message(fixture(head(141), "hello")).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use super::elem::{Face, RQElem};
+use super::{MessageChain, MessageChainBuilder};
+
+macro_rules! shortcode_map {
+    ($(($code: expr, $id: expr, $emoji: expr)),*) => {
+        fn shortcode_to_face(code: &str) -> Option<i32> {
+            match code {
+                $(
+                    $code => Some($id),
+                )*
+                _ => None,
+            }
+        }
+        fn face_to_shortcode(id: i32) -> Option<(&'static str, &'static str)> {
+            match id {
+                $(
+                    $id => Some(($code, $emoji)),
+                )*
+                _ => None,
+            }
+        }
+    };
+}
+
+// (shortcode, face id, unicode emoji)
+shortcode_map!(
+    ("smile", 14, "🙂"),
+    ("grin", 13, "😁"),
+    ("joy", 182, "😂"),
+    ("wink", 172, "😉"),
+    ("blush", 6, "☺️"),
+    ("heart_eyes", 2, "😍"),
+    ("sunglasses", 16, "😎"),
+    ("cry", 9, "😭"),
+    ("sob", 173, "😭"),
+    ("tear", 5, "😢"),
+    ("angry", 11, "😠"),
+    ("rage", 326, "😡"),
+    ("sweat", 27, "😓"),
+    ("sweat_smile", 96, "😅"),
+    ("scream", 26, "😱"),
+    ("astonished", 0, "😲"),
+    ("sleeping", 8, "😴"),
+    ("yawn", 104, "🥱"),
+    ("dizzy_face", 34, "😵"),
+    ("thinking", 32, "🤔"),
+    ("shushing", 33, "🤫"),
+    ("zipper_mouth", 7, "🤐"),
+    ("nauseated", 19, "🤮"),
+    ("smirk", 178, "😏"),
+    ("facepalm", 264, "🤦"),
+    ("skull", 37, "💀"),
+    ("wave", 39, "👋"),
+    ("clap", 99, "👏"),
+    ("pray", 118, "🙏"),
+    ("ok_hand", 124, "👌"),
+    ("thumbsup", 76, "👍"),
+    ("thumbsdown", 77, "👎"),
+    ("v", 79, "✌️"),
+    ("fist", 120, "✊"),
+    ("handshake", 78, "🤝"),
+    ("heart", 66, "❤️"),
+    ("broken_heart", 67, "💔"),
+    ("rose", 63, "🌹"),
+    ("wilted_flower", 64, "🥀"),
+    ("kiss", 109, "😘"),
+    ("hug", 49, "🤗"),
+    ("ghost", 187, "👻"),
+    ("cake", 53, "🍰"),
+    ("coffee", 60, "☕"),
+    ("beer", 113, "🍺"),
+    ("tea", 171, "🍵"),
+    ("rice", 61, "🍚"),
+    ("watermelon", 89, "🍉"),
+    ("lollipop", 147, "🍭"),
+    ("gift", 69, "🎁"),
+    ("bomb", 55, "💣"),
+    ("zap", 54, "⚡"),
+    ("sunny", 74, "☀️"),
+    ("crescent_moon", 75, "🌙"),
+    ("pig", 46, "🐷"),
+    ("poop", 59, "💩"),
+    ("knife", 112, "🔪"),
+    ("dagger", 56, "🗡️"),
+    ("gun", 169, "🔫"),
+    ("pill", 168, "💊"),
+    ("airplane", 151, "✈️"),
+    ("basketball", 114, "🏀"),
+    ("soccer", 57, "⚽"),
+    ("ping_pong", 115, "🏓"),
+    ("firecracker", 137, "🧨"),
+    ("red_envelope", 192, "🧧"),
+    ("money", 158, "💵"),
+    ("doge", 179, "🐶"),
+    ("hot_pepper", 276, "🌶️")
+);
+
+impl Face {
+    /// 从 `:smile:` 形式的短代码（不含冒号）获取表情
+    pub fn from_shortcode(code: &str) -> Option<Self> {
+        shortcode_to_face(code).map(Self::new)
+    }
+
+    /// 表情对应的短代码（不含冒号）
+    pub fn shortcode(&self) -> Option<&'static str> {
+        face_to_shortcode(self.index).map(|(code, _)| code)
+    }
+
+    /// 表情对应的 unicode emoji
+    pub fn emoji(&self) -> Option<&'static str> {
+        face_to_shortcode(self.index).map(|(_, emoji)| emoji)
+    }
+}
+
+fn is_shortcode_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '+' || c == '-'
+}
+
+impl MessageChain {
+    /// 解析文本中 `:smile:` 形式的短代码为表情，无法识别的短代码和 unicode emoji 保留为文本
+    pub fn from_shortcode_text(text: &str) -> Self {
+        let mut builder = MessageChainBuilder::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(':') {
+            let (before, after) = rest.split_at(start);
+            builder.push_str(before);
+            let after = &after[1..];
+            let face = after.find(':').and_then(|end| {
+                let code = &after[..end];
+                if !code.is_empty() && code.chars().all(is_shortcode_char) {
+                    Face::from_shortcode(code).map(|face| (face, end))
+                } else {
+                    None
+                }
+            });
+            match face {
+                Some((face, end)) => {
+                    builder.push(face);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    builder.push_str(":");
+                    rest = after;
+                }
+            }
+        }
+        builder.push_str(rest);
+        builder.build()
+    }
+
+    /// 将消息渲染为文本，表情使用 `:smile:` 形式的短代码，没有短代码的表情使用 `[名称]`
+    pub fn to_shortcode_text(&self) -> String {
+        self.clone()
+            .into_iter()
+            .map(|e| match e {
+                RQElem::Face(face) => match face.shortcode() {
+                    Some(code) => format!(":{code}:"),
+                    None => face.to_string(),
+                },
+                e => e
+                    .display_inner()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcode_round_trip() {
+        let chain = MessageChain::from_shortcode_text("hi :smile: a:b :unknown: 👍:doge:");
+        assert_eq!(
+            chain.to_shortcode_text(),
+            "hi :smile: a:b :unknown: 👍:doge:"
+        );
+        let faces: Vec<i32> = chain
+            .into_iter()
+            .filter_map(|e| match e {
+                RQElem::Face(face) => Some(face.index),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(faces, vec![14, 179]);
+    }
+}
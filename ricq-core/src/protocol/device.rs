@@ -1,6 +1,7 @@
 use bytes::Bytes;
 use rand::distributions::DistString;
-use rand::{distributions::Alphanumeric, Rng, RngCore};
+use rand::rngs::StdRng;
+use rand::{distributions::Alphanumeric, Rng, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::hex::encode_hex;
@@ -59,6 +60,13 @@ impl Device {
         Self::random_with_rng(&mut rand::thread_rng())
     }
 
+    /// 由种子（如 uin）生成固定的设备信息，每次启动都得到相同的设备，无需保存 device.json
+    ///
+    /// **Notice: 升级 rand 版本后生成结果可能改变**
+    pub fn deterministic(seed: u64) -> Self {
+        Self::random_with_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
     pub fn random_with_rng<RNG: RngCore>(rng: &mut RNG) -> Self {
         Self {
             display: format!("RICQ.{}.001", rng.gen_range(100000..999999)),
@@ -137,3 +145,18 @@ pub fn random_imei<RNG: RngCore>(rng: &mut RNG) -> String {
     str.push_str(&ctrl_digit.to_string());
     str
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        let a = Device::deterministic(10000);
+        let b = Device::deterministic(10000);
+        assert_eq!(a.imei, b.imei);
+        assert_eq!(a.android_id, b.android_id);
+        assert_eq!(a.boot_id, b.boot_id);
+        assert_ne!(a.imei, Device::deterministic(10001).imei);
+    }
+}
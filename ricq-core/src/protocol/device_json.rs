@@ -0,0 +1,345 @@
+//! mirai / go-cqhttp 的 device.json 导入导出
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::hex::{decode_hex, encode_hex};
+use crate::protocol::device::{Device, OSVersion};
+use crate::{RQError, RQResult};
+
+/// mirai 旧版本 (v1) 的字符串字段以字节数组保存
+fn string_or_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrBytes {
+        String(String),
+        Bytes(Vec<u8>),
+    }
+    Ok(match StringOrBytes::deserialize(deserializer)? {
+        StringOrBytes::String(s) => s,
+        StringOrBytes::Bytes(b) => String::from_utf8_lossy(&b).into_owned(),
+    })
+}
+
+/// imsiMd5 在 mirai v1 中为字节数组，在 mirai v2 和 go-cqhttp 中为 hex 字符串
+fn hex_or_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum HexOrBytes {
+        Hex(String),
+        Bytes(Vec<u8>),
+    }
+    match HexOrBytes::deserialize(deserializer)? {
+        HexOrBytes::Hex(s) => decode_hex(&s).map_err(serde::de::Error::custom),
+        HexOrBytes::Bytes(b) => Ok(b),
+    }
+}
+
+fn serialize_hex<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode_hex(bytes))
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct MiraiVersion {
+    #[serde(deserialize_with = "string_or_bytes")]
+    incremental: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    release: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    codename: String,
+    sdk: u32,
+}
+
+impl Default for MiraiVersion {
+    fn default() -> Self {
+        OSVersion::default().into()
+    }
+}
+
+impl From<OSVersion> for MiraiVersion {
+    fn from(v: OSVersion) -> Self {
+        Self {
+            incremental: v.incremental,
+            release: v.release,
+            codename: v.codename,
+            sdk: v.sdk,
+        }
+    }
+}
+
+impl From<MiraiVersion> for OSVersion {
+    fn from(v: MiraiVersion) -> Self {
+        Self {
+            incremental: v.incremental,
+            release: v.release,
+            codename: v.codename,
+            sdk: v.sdk,
+        }
+    }
+}
+
+/// mirai DeviceInfo，缺失的字段使用随机设备的值
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct MiraiDevice {
+    #[serde(deserialize_with = "string_or_bytes")]
+    display: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    product: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    device: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    board: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    brand: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    model: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    bootloader: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    fingerprint: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    boot_id: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    proc_version: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    base_band: String,
+    version: MiraiVersion,
+    #[serde(deserialize_with = "string_or_bytes")]
+    sim_info: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    os_type: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    mac_address: String,
+    #[serde(rename = "wifiBSSID", deserialize_with = "string_or_bytes")]
+    wifi_bssid: String,
+    #[serde(rename = "wifiSSID", deserialize_with = "string_or_bytes")]
+    wifi_ssid: String,
+    #[serde(deserialize_with = "hex_or_bytes", serialize_with = "serialize_hex")]
+    imsi_md5: Vec<u8>,
+    #[serde(deserialize_with = "string_or_bytes")]
+    imei: String,
+    #[serde(deserialize_with = "string_or_bytes")]
+    apn: String,
+    /// 旧版本 mirai 没有该字段，使用 display 代替
+    #[serde(deserialize_with = "string_or_bytes")]
+    android_id: String,
+}
+
+impl Default for MiraiDevice {
+    fn default() -> Self {
+        let mut device: Self = Device::random().into();
+        device.android_id = String::new();
+        device
+    }
+}
+
+impl From<Device> for MiraiDevice {
+    fn from(d: Device) -> Self {
+        Self {
+            display: d.display,
+            product: d.product,
+            device: d.device,
+            board: d.board,
+            brand: d.brand,
+            model: d.model,
+            bootloader: d.bootloader,
+            fingerprint: d.finger_print,
+            boot_id: d.boot_id,
+            proc_version: d.proc_version,
+            base_band: d.base_band,
+            version: d.version.into(),
+            sim_info: d.sim_info,
+            os_type: d.os_type,
+            mac_address: d.mac_address,
+            wifi_bssid: d.wifi_bssid,
+            wifi_ssid: d.wifi_ssid,
+            imsi_md5: d.imsi_md5,
+            imei: d.imei,
+            apn: d.apn,
+            android_id: d.android_id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MiraiDeviceFile {
+    device_info_version: u32,
+    data: MiraiDevice,
+}
+
+/// go-cqhttp device.json，缺失的字段使用随机设备的值
+#[derive(Deserialize)]
+#[serde(default)]
+struct GocqDevice {
+    display: String,
+    product: String,
+    device: String,
+    board: String,
+    model: String,
+    finger_print: String,
+    boot_id: String,
+    proc_version: String,
+    imei: String,
+    brand: String,
+    bootloader: String,
+    base_band: String,
+    version: OSVersion,
+    sim_info: String,
+    os_type: String,
+    mac_address: String,
+    ip_address: Vec<u8>,
+    wifi_bssid: String,
+    wifi_ssid: String,
+    #[serde(deserialize_with = "hex_or_bytes")]
+    imsi_md5: Vec<u8>,
+    android_id: String,
+    apn: String,
+    vendor_name: String,
+    vendor_os_name: String,
+}
+
+impl Default for GocqDevice {
+    fn default() -> Self {
+        let d = Device::random();
+        Self {
+            display: d.display,
+            product: d.product,
+            device: d.device,
+            board: d.board,
+            model: d.model,
+            finger_print: d.finger_print,
+            boot_id: d.boot_id,
+            proc_version: d.proc_version,
+            imei: d.imei,
+            brand: d.brand,
+            bootloader: d.bootloader,
+            base_band: d.base_band,
+            version: d.version,
+            sim_info: d.sim_info,
+            os_type: d.os_type,
+            mac_address: d.mac_address,
+            ip_address: d.ip_address,
+            wifi_bssid: d.wifi_bssid,
+            wifi_ssid: d.wifi_ssid,
+            imsi_md5: d.imsi_md5,
+            android_id: d.android_id,
+            apn: d.apn,
+            vendor_name: d.vendor_name,
+            vendor_os_name: d.vendor_os_name,
+        }
+    }
+}
+
+impl Device {
+    /// 读取 mirai 的 device.json，支持 deviceInfoVersion 2 和旧版本（字节数组）格式
+    pub fn from_mirai_json(json: &str) -> RQResult<Self> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| RQError::Decode(e.to_string()))?;
+        let device: MiraiDevice = if value.get("deviceInfoVersion").is_some() {
+            serde_json::from_value::<MiraiDeviceFile>(value).map(|f| f.data)
+        } else {
+            serde_json::from_value(value)
+        }
+        .map_err(|e| RQError::Decode(e.to_string()))?;
+        let random = Device::random();
+        Ok(Self {
+            android_id: if device.android_id.is_empty() {
+                device.display.clone()
+            } else {
+                device.android_id
+            },
+            display: device.display,
+            product: device.product,
+            device: device.device,
+            board: device.board,
+            model: device.model,
+            finger_print: device.fingerprint,
+            boot_id: device.boot_id,
+            proc_version: device.proc_version,
+            imei: device.imei,
+            brand: device.brand,
+            bootloader: device.bootloader,
+            base_band: device.base_band,
+            version: device.version.into(),
+            sim_info: device.sim_info,
+            os_type: device.os_type,
+            mac_address: device.mac_address,
+            wifi_bssid: device.wifi_bssid,
+            wifi_ssid: device.wifi_ssid,
+            imsi_md5: device.imsi_md5,
+            apn: device.apn,
+            // mirai 没有以下字段
+            ip_address: random.ip_address,
+            vendor_name: random.vendor_name,
+            vendor_os_name: random.vendor_os_name,
+        })
+    }
+
+    /// 导出为 mirai 的 device.json (deviceInfoVersion 2)
+    pub fn to_mirai_json(&self) -> String {
+        serde_json::to_string_pretty(&MiraiDeviceFile {
+            device_info_version: 2,
+            data: self.clone().into(),
+        })
+        .unwrap()
+    }
+
+    /// 读取 go-cqhttp 的 device.json，忽略其中的 protocol 字段
+    pub fn from_gocq_json(json: &str) -> RQResult<Self> {
+        let d: GocqDevice =
+            serde_json::from_str(json).map_err(|e| RQError::Decode(e.to_string()))?;
+        Ok(Self {
+            display: d.display,
+            product: d.product,
+            device: d.device,
+            board: d.board,
+            model: d.model,
+            finger_print: d.finger_print,
+            boot_id: d.boot_id,
+            proc_version: d.proc_version,
+            imei: d.imei,
+            brand: d.brand,
+            bootloader: d.bootloader,
+            base_band: d.base_band,
+            version: d.version,
+            sim_info: d.sim_info,
+            os_type: d.os_type,
+            mac_address: d.mac_address,
+            ip_address: d.ip_address,
+            wifi_bssid: d.wifi_bssid,
+            wifi_ssid: d.wifi_ssid,
+            imsi_md5: d.imsi_md5,
+            android_id: d.android_id,
+            apn: d.apn,
+            vendor_name: d.vendor_name,
+            vendor_os_name: d.vendor_os_name,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirai_round_trip() {
+        let device = Device::random();
+        let parsed = Device::from_mirai_json(&device.to_mirai_json()).unwrap();
+        assert_eq!(parsed.imei, device.imei);
+        assert_eq!(parsed.finger_print, device.finger_print);
+        assert_eq!(parsed.imsi_md5, device.imsi_md5);
+        assert_eq!(parsed.android_id, device.android_id);
+    }
+
+    #[test]
+    fn mirai_v1_bytes() {
+        let json = r#"{"display":[77,73,82,65,73],"imei":"123456789012345","imsiMd5":[1,2,3]}"#;
+        let device = Device::from_mirai_json(json).unwrap();
+        assert_eq!(device.display, "MIRAI");
+        assert_eq!(device.android_id, "MIRAI");
+        assert_eq!(device.imei, "123456789012345");
+        assert_eq!(device.imsi_md5, vec![1, 2, 3]);
+    }
+}
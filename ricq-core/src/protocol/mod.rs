@@ -1,4 +1,5 @@
 pub mod device;
+mod device_json;
 pub mod oicq;
 pub mod packet;
 pub mod sig;
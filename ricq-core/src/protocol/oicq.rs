@@ -2,7 +2,7 @@ use bytes::{Buf, BufMut, Bytes, BytesMut};
 use rand::Rng;
 
 use crate::binary::BinaryWriter;
-use crate::crypto::{qqtea_decrypt, EncryptECDH};
+use crate::crypto::{qqtea_decrypt_bytes, EncryptECDH};
 use crate::{RQError, RQResult};
 
 #[derive(Debug, derivative::Derivative)]
@@ -103,12 +103,12 @@ impl Codec {
             0 => {
                 let len = reader.remaining() - 1;
                 let d = reader.copy_to_bytes(len);
-                m.body = Bytes::from(qqtea_decrypt(&d, &self.ecdh.initial_share_key));
+                m.body = qqtea_decrypt_bytes(&d, &self.ecdh.initial_share_key);
             }
             3 => {
                 let len = reader.remaining() - 1;
                 let d = reader.copy_to_bytes(len);
-                m.body = Bytes::from(qqtea_decrypt(&d, &self.wt_session_ticket_key));
+                m.body = qqtea_decrypt_bytes(&d, &self.wt_session_ticket_key);
             }
             _ => return Err(RQError::UnknownEncryptType),
         }
@@ -63,6 +63,16 @@ pub struct Packet {
     pub command_name: String,
     pub uin: i64,
     pub message: String,
+    /// 外部签名服务计算的签名，写入 SSO 头
+    pub sign: Option<SignResult>,
+}
+
+/// 外部签名服务（qsign 等）返回的结果
+#[derive(Default, Debug, Clone)]
+pub struct SignResult {
+    pub sign: Bytes,
+    pub token: Bytes,
+    pub extra: Bytes,
 }
 
 impl Packet {
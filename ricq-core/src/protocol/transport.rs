@@ -5,7 +5,7 @@ use flate2::read::ZlibDecoder;
 
 use crate::binary::{BinaryReader, BinaryWriter};
 use crate::command::common::PbToBytes;
-use crate::crypto::{qqtea_decrypt, qqtea_encrypt};
+use crate::crypto::{qqtea_decrypt_bytes, qqtea_encrypt};
 use crate::protocol::{
     device::Device,
     packet::{EncryptType, Packet, PacketType},
@@ -91,11 +91,12 @@ impl Transport {
 
         pkt.uin = r.read_string().parse().unwrap_or_default();
 
-        let mut body = Bytes::from(r.chunk().to_owned());
+        // Bytes/BytesMut 的 copy_to_bytes 不复制数据
+        let mut body = r.copy_to_bytes(r.remaining());
         match pkt.encrypt_type {
             EncryptType::NoEncrypt => {}
-            EncryptType::D2Key => body = Bytes::from(qqtea_decrypt(&body, &self.sig.d2key)),
-            EncryptType::EmptyKey => body = Bytes::from(qqtea_decrypt(&body, &[0; 16])),
+            EncryptType::D2Key => body = qqtea_decrypt_bytes(&body, &self.sig.d2key),
+            EncryptType::EmptyKey => body = qqtea_decrypt_bytes(&body, &[0; 16]),
         }
 
         self.decode_sso_frame(&mut pkt, body)?;
@@ -135,7 +136,21 @@ impl Transport {
             w.put_u16(self.device.ksid().len() as u16 + 2);
             w.put_slice(&self.device.ksid());
         }
-        w.put_u32(0x04);
+        match &pkt.sign {
+            Some(sign) => {
+                let reserve = pb::msf::SsoReserveField {
+                    sec_info: Some(pb::msf::SsoSecureInfo {
+                        sec_sig: Some(sign.sign.to_vec()),
+                        sec_device_token: Some(sign.token.to_vec()),
+                        sec_extra: Some(sign.extra.to_vec()),
+                    }),
+                }
+                .to_bytes();
+                w.put_u32(reserve.len() as u32 + 4);
+                w.put_slice(&reserve);
+            }
+            None => w.put_u32(0x04),
+        }
 
         // write len
         let len = w.len() - pos;
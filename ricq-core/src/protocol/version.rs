@@ -1,6 +1,6 @@
 use std::convert::TryFrom;
 
-#[derive(Debug, Clone, derivative::Derivative, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derivative::Derivative, serde::Deserialize)]
 #[derivative(Default)]
 pub enum Protocol {
     #[derivative(Default)]
@@ -9,6 +9,22 @@ pub enum Protocol {
     AndroidWatch,
     MacOS,
     QiDian,
+    AndroidPad,
+}
+
+impl Protocol {
+    pub const ALL: [Protocol; 6] = [
+        Protocol::IPad,
+        Protocol::AndroidPhone,
+        Protocol::AndroidWatch,
+        Protocol::MacOS,
+        Protocol::QiDian,
+        Protocol::AndroidPad,
+    ];
+
+    pub const fn version(self) -> Version {
+        get_version(self)
+    }
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -35,6 +51,25 @@ pub const fn get_version(p: Protocol) -> Version {
         Protocol::AndroidWatch => ANDROID_WATCH,
         Protocol::MacOS => MACOS,
         Protocol::QiDian => QIDIAN,
+        Protocol::AndroidPad => ANDROID_PAD,
+    }
+}
+
+/// 自定义构建的客户端可以覆盖部分字段
+impl Version {
+    pub const fn with_apk_id(mut self, apk_id: &'static str) -> Self {
+        self.apk_id = apk_id;
+        self
+    }
+
+    pub const fn with_sub_app_id(mut self, sub_app_id: u32) -> Self {
+        self.sub_app_id = sub_app_id;
+        self
+    }
+
+    pub const fn with_apk_sign(mut self, apk_sign: &'static [u8]) -> Self {
+        self.apk_sign = apk_sign;
+        self
     }
 }
 
@@ -63,6 +98,25 @@ pub const ANDROID_PHONE: Version = Version {
     protocol: Protocol::AndroidPhone,
 };
 
+pub const ANDROID_PAD: Version = Version {
+    apk_id: "com.tencent.mobileqq",
+    app_id: 537152242,
+    sub_app_id: 537152242,
+    sort_version_name: "8.9.33",
+    build_ver: "8.9.33.10335",
+    build_time: 1673599898,
+    apk_sign: &[
+        0xA6, 0xB7, 0x45, 0xBF, 0x24, 0xA2, 0xC2, 0x77, 0x52, 0x77, 0x16, 0xF6, 0xF3, 0x6E, 0xB6,
+        0x8D,
+    ],
+    sdk_version: "6.0.0.2534",
+    sso_version: 19,
+    misc_bitmap: 150470524,
+    sub_sig_map: 0x10400,
+    main_sig_map: 34869472,
+    protocol: Protocol::AndroidPad,
+};
+
 pub const IPAD: Version = Version {
     apk_id: "com.tencent.minihd.qq",
     app_id: 537065739,
@@ -145,6 +199,7 @@ impl TryFrom<&str> for Protocol {
             "AndroidWatch" => Ok(Protocol::AndroidWatch),
             "MacOS" => Ok(Protocol::MacOS),
             "QiDian" => Ok(Protocol::QiDian),
+            "AndroidPad" => Ok(Protocol::AndroidPad),
             _ => Err(()),
         }
     }
@@ -161,6 +216,7 @@ impl TryFrom<u8> for Protocol {
             3 => Ok(Protocol::MacOS),
             4 => Ok(Protocol::QiDian),
             5 => Ok(Protocol::IPad),
+            6 => Ok(Protocol::AndroidPad),
             _ => Err(()),
         }
     }
@@ -1,4 +1,6 @@
 use bytes::Bytes;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
 pub use crate::command::multi_msg::{ForwardMessage, ForwardNode, MessageNode};
@@ -6,8 +8,9 @@ pub use crate::command::oidb_svc::{
     LinkShare, MusicShare, MusicVersion, ProfileDetailUpdate, ShareTarget,
 };
 pub use crate::command::stat_svc::{CustomOnlineStatus, ExtOnlineStatus, OnlineStatus, Status};
+use crate::msg::elem::RedPacket;
 use crate::msg::MessageChain;
-use crate::{jce, pb};
+use crate::{jce, pb, RQError};
 
 #[derive(Default, Debug)]
 pub struct AccountInfo {
@@ -23,7 +26,7 @@ pub struct AddressInfo {
     pub file_storage_info: jce::FileStoragePushFSSvcList,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct OtherClientInfo {
     pub app_id: i64,
     pub instance_id: i32,
@@ -31,6 +34,56 @@ pub struct OtherClientInfo {
     pub device_kind: String,
 }
 
+/// 其他客户端上线/下线
+#[derive(Debug, Clone)]
+pub struct OtherClientStatusChanged {
+    pub client: OtherClientInfo,
+    pub online: bool,
+}
+
+/// 下线原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KickedOfflineKind {
+    /// 被其他客户端顶下线 (MessageSvc.PushForceOffline)
+    OtherClient,
+    /// 服务端强制下线 (StatSvc.ReqMSFOffline)，如账号冻结、修改密码
+    Server { kick_type: u8 },
+}
+
+/// 被踢下线
+#[derive(Debug, Clone)]
+pub struct KickedOffline {
+    pub kind: KickedOfflineKind,
+    pub title: String,
+    pub message: String,
+    /// 是否为同一设备
+    pub same_device: bool,
+}
+
+impl From<jce::RequestPushForceOffline> for KickedOffline {
+    fn from(offline: jce::RequestPushForceOffline) -> Self {
+        Self {
+            kind: KickedOfflineKind::OtherClient,
+            title: offline.title,
+            message: offline.tips,
+            same_device: offline.same_device != 0,
+        }
+    }
+}
+
+impl From<jce::RequestMSFForceOffline> for KickedOffline {
+    fn from(offline: jce::RequestMSFForceOffline) -> Self {
+        Self {
+            kind: KickedOfflineKind::Server {
+                kick_type: offline.kick_type,
+            },
+            title: offline.title,
+            message: offline.info,
+            same_device: offline.same_device != 0,
+        }
+    }
+}
+
 pub struct QiDianAccountInfo {
     pub master_uin: i64,
     pub ext_name: String,
@@ -46,7 +99,7 @@ pub struct BigDataReqSessionInfo {
     pub session_key: Bytes,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct GroupInfo {
     pub uin: i64,
     pub code: i64,
@@ -63,6 +116,8 @@ pub struct GroupInfo {
     pub my_shut_up_timestamp: i64,
     // 最后一条信息的SEQ,只有通过 GetGroupInfo 函数获取的 GroupInfo 才会有
     pub last_msg_seq: i64,
+    /// 群等级头衔设置的版本，修改头衔名称后变化，只有通过 GetGroupInfo 函数获取的 GroupInfo 才会有
+    pub level_name_seq: u32,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -98,6 +153,8 @@ pub struct FriendInfo {
     pub remark: String,
     pub face_id: i16,
     pub group_id: u8,
+    /// 在线状态（在线/离开/忙碌等），未知时为 None
+    pub status: Option<OnlineStatus>,
 }
 
 /// 好友分组信息
@@ -121,10 +178,30 @@ pub struct SummaryCardInfo {
     pub sign: String,
     pub mobile: String,
     pub login_days: i64,
+    pub province: String,
+    pub country: String,
+    /// 空间动态摘要
+    pub qzone_feeds_desc: String,
+    /// 被点赞数
+    pub like_count: i32,
+    /// 富文本签名的原始数据
+    pub rich_sign: Bytes,
+    pub qid: String,
+    /// 开通的会员特权（会员、超级会员、黄钻等）
+    pub privileges: Vec<VipPrivilege>,
     /// 用于点赞
     pub cookie: Bytes,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct VipPrivilege {
+    /// 特权类型
+    pub kind: i32,
+    pub level: i32,
+    /// 付费类型，如年费
+    pub fee_type: i32,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct FriendMessage {
     pub seqs: Vec<i32>,
@@ -156,7 +233,86 @@ pub struct GroupTempMessage {
     pub from_nick: String,
     pub time: i32,
     pub elements: MessageChain,
+    /// 非群来源的临时会话为 0
     pub group_code: i64,
+    /// 临时会话签名，回复非群来源的临时会话时需要
+    pub sig: Bytes,
+}
+
+impl GroupTempMessage {
+    /// 回复该临时会话使用的 RoutingHead
+    pub fn routing_head(&self) -> pb::msg::routing_head::RoutingHead {
+        use crate::command::message_svc::{grp_tmp_routing_head, wpa_tmp_routing_head};
+        if self.group_code != 0 {
+            grp_tmp_routing_head(self.group_code, self.from_uin)
+        } else {
+            wpa_tmp_routing_head(self.from_uin, self.sig.clone())
+        }
+    }
+}
+
+/// 临时会话来源，对应 C2CTmpMsgHead.service_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempSessionSource {
+    /// 群
+    Group,
+    /// 多人聊天
+    MultiChat,
+    /// 通讯录
+    AddressBook,
+    /// 热聊
+    HotChat,
+    /// 系统消息
+    SystemMessage,
+    /// QQ 咨询、资料卡、搜索等
+    Consulting,
+    Other(i32),
+}
+
+impl From<i32> for TempSessionSource {
+    fn from(service_type: i32) -> Self {
+        match service_type {
+            0 => Self::Group,
+            1 => Self::MultiChat,
+            130 => Self::AddressBook,
+            132 => Self::HotChat,
+            134 => Self::SystemMessage,
+            201 => Self::Consulting,
+            _ => Self::Other(service_type),
+        }
+    }
+}
+
+/// 非群来源的临时会话消息（陌生人通过资料卡、搜索等发起）
+#[derive(Debug, Clone)]
+pub struct TempSessionMessage {
+    pub source: TempSessionSource,
+    pub seqs: Vec<i32>,
+    pub rands: Vec<i32>,
+    pub from_uin: i64,
+    pub from_nick: String,
+    pub time: i32,
+    pub elements: MessageChain,
+    /// 回复时需要的签名
+    pub sig: Bytes,
+    pub from_phone: String,
+    pub to_phone: String,
+}
+
+impl TempSessionMessage {
+    /// 回复该临时会话使用的 RoutingHead
+    pub fn routing_head(&self) -> pb::msg::routing_head::RoutingHead {
+        use crate::command::message_svc::{address_list_tmp_routing_head, wpa_tmp_routing_head};
+        match self.source {
+            TempSessionSource::AddressBook => address_list_tmp_routing_head(
+                self.from_uin,
+                self.sig.clone(),
+                self.to_phone.clone(),
+                self.from_phone.clone(),
+            ),
+            _ => wpa_tmp_routing_head(self.from_uin, self.sig.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -169,10 +325,41 @@ pub struct NewMember {
 pub struct GroupMute {
     pub group_code: i64,
     pub operator_uin: i64,
+    /// 全员禁言时为 0
     pub target_uin: i64,
+    /// 解除禁言时为 0
     pub duration: Duration,
 }
 
+/// 自己被禁言
+#[derive(Debug, Clone, Default)]
+pub struct BotMuted {
+    pub group_code: i64,
+    /// 发送消息失败时发现的禁言为 None
+    pub operator_uin: Option<i64>,
+    /// 发送消息失败时发现的禁言为 None
+    pub duration: Option<Duration>,
+}
+
+/// 自己被解除禁言
+#[derive(Debug, Clone, Default)]
+pub struct BotUnmuted {
+    pub group_code: i64,
+    pub operator_uin: i64,
+}
+
+impl GroupMute {
+    /// 是否为全员禁言（或解除全员禁言）
+    pub fn is_mute_all(&self) -> bool {
+        self.target_uin == 0
+    }
+
+    /// 是否为解除禁言
+    pub fn is_unmute(&self) -> bool {
+        self.duration.is_zero()
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct FriendMessageRecall {
     pub msg_seq: i32,
@@ -187,13 +374,29 @@ pub struct GroupMessageRecall {
     pub operator_uin: i64,
     pub author_uin: i64,
     pub time: i32,
+    /// 被撤回的原消息，仅在消息仍在本地缓存中时存在
+    pub message: Option<MessageChain>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct GroupLeave {
     pub group_code: i64,
     pub member_uin: i64,
+    /// 踢人的管理员或解散群的群主，主动退群时为 None
     pub operator_uin: Option<i64>,
+    pub kind: GroupLeaveKind,
+}
+
+/// 离开群的原因
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupLeaveKind {
+    /// 主动退群
+    #[default]
+    Leave,
+    /// 被踢出
+    Kick,
+    /// 群被解散
+    Disband,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -208,11 +411,54 @@ pub struct GroupPoke {
     pub receiver: i64,
 }
 
+/// 群红包
+#[derive(Debug, Clone, Default)]
+pub struct GroupRedPacket {
+    pub group_code: i64,
+    pub sender_uin: i64,
+    pub seqs: Vec<i32>,
+    pub time: i32,
+    pub packet: RedPacket,
+}
+
+/// 好友转账
+#[derive(Debug, Clone, Default)]
+pub struct FriendTransfer {
+    pub from_uin: i64,
+    pub seqs: Vec<i32>,
+    pub time: i32,
+    pub packet: RedPacket,
+}
+
+/// 群灰条提示，如热聊、斗图等互动提示
+#[derive(Debug, Clone, Default)]
+pub struct GroupGrayTip {
+    pub group_code: i64,
+    pub busi_type: u64,
+    pub busi_id: u64,
+    /// 模板 id
+    pub templ_id: u64,
+    /// 模板参数 <name, value>
+    pub templ_params: Vec<(String, String)>,
+    pub content: String,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct GroupNameUpdate {
     pub group_code: i64,
     pub operator_uin: i64,
     pub group_name: String,
+    /// 修改前的群名称，群信息未缓存时为 None
+    pub old_group_name: Option<String>,
+}
+
+/// 群名片变更，通过对比群消息中的名片与缓存发现
+#[derive(Debug, Clone, Default)]
+pub struct MemberCardChanged {
+    pub group_code: i64,
+    pub uin: i64,
+    pub old: String,
+    pub new: String,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -220,6 +466,40 @@ pub struct DeleteFriend {
     pub uin: i64,
 }
 
+/// 好友登录的客户端类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FriendClientKind {
+    Mobile,
+    Pc,
+    Pad,
+    Watch,
+    #[default]
+    Unknown,
+}
+
+/// 好友在线状态，见 Client::get_friends_status
+#[derive(Debug, Clone, Default)]
+pub struct FriendOnlineStatus {
+    pub uin: i64,
+    pub online: bool,
+    /// 在线状态（在线/离开/忙碌等），离线或未知时为 None
+    pub status: Option<OnlineStatus>,
+    pub client: FriendClientKind,
+    /// 客户端描述，如 "iPhone在线"
+    pub term_desc: String,
+    /// 手机电量（百分比），未上报时为 None
+    pub battery: Option<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FriendStatusChanged {
+    pub uin: i64,
+    /// 缓存中的旧状态，未缓存时为 None
+    pub old_status: Option<OnlineStatus>,
+    /// 新的在线状态，离线时为 Offline
+    pub status: OnlineStatus,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct MemberPermissionChange {
     pub group_code: i64,
@@ -233,6 +513,15 @@ pub struct GroupDisband {
     pub operator_uin: i64,
 }
 
+/// 账号限制，服务端因此拒绝建群、发消息等操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountRestriction {
+    /// 需要实名认证
+    RealName,
+    /// 账号等级不足
+    Level,
+}
+
 // 用于撤回
 #[derive(Debug, Clone, Default)]
 pub struct MessageReceipt {
@@ -241,6 +530,111 @@ pub struct MessageReceipt {
     pub time: i64,
 }
 
+/// 消息所在会话的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageTargetKind {
+    Group,
+    Friend,
+}
+
+/// 可持久化的消息标识，重启后仍可用于撤回、引用、获取消息
+///
+/// 字符串格式为 `g:<group_code>:<seqs>:<rands>:<time>`（好友消息为 `f:` 开头），seqs 和 rands 以逗号分隔
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MessageId {
+    pub kind: MessageTargetKind,
+    /// 群号或好友 uin
+    pub target: i64,
+    pub seqs: Vec<i32>,
+    pub rands: Vec<i32>,
+    pub time: i64,
+}
+
+impl MessageId {
+    pub fn group(group_code: i64, receipt: &MessageReceipt) -> Self {
+        Self::new(MessageTargetKind::Group, group_code, receipt)
+    }
+
+    pub fn friend(uin: i64, receipt: &MessageReceipt) -> Self {
+        Self::new(MessageTargetKind::Friend, uin, receipt)
+    }
+
+    fn new(kind: MessageTargetKind, target: i64, receipt: &MessageReceipt) -> Self {
+        Self {
+            kind,
+            target,
+            seqs: receipt.seqs.clone(),
+            rands: receipt.rands.clone(),
+            time: receipt.time,
+        }
+    }
+
+    pub fn receipt(&self) -> MessageReceipt {
+        MessageReceipt {
+            seqs: self.seqs.clone(),
+            rands: self.rands.clone(),
+            time: self.time,
+        }
+    }
+}
+
+impl From<&GroupMessage> for MessageId {
+    fn from(m: &GroupMessage) -> Self {
+        Self {
+            kind: MessageTargetKind::Group,
+            target: m.group_code,
+            seqs: m.seqs.clone(),
+            rands: m.rands.clone(),
+            time: m.time as i64,
+        }
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |v: &[i32]| v.iter().map(i32::to_string).collect::<Vec<_>>().join(",");
+        let kind = match self.kind {
+            MessageTargetKind::Group => "g",
+            MessageTargetKind::Friend => "f",
+        };
+        write!(
+            f,
+            "{}:{}:{}:{}:{}",
+            kind,
+            self.target,
+            join(&self.seqs),
+            join(&self.rands),
+            self.time
+        )
+    }
+}
+
+impl FromStr for MessageId {
+    type Err = RQError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RQError::Decode(format!("invalid message id: {}", s));
+        let split =
+            |s: &str| -> Option<Vec<i32>> { s.split(',').map(|v| v.parse().ok()).collect() };
+        let fields: Vec<&str> = s.split(':').collect();
+        let [kind, target, seqs, rands, time] = fields[..] else {
+            return Err(invalid());
+        };
+        let kind = match kind {
+            "g" => MessageTargetKind::Group,
+            "f" => MessageTargetKind::Friend,
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            kind,
+            target: target.parse().map_err(|_| invalid())?,
+            seqs: split(seqs).ok_or_else(invalid)?,
+            rands: split(rands).ok_or_else(invalid)?,
+            time: time.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct GroupAudio(pub pb::msg::Ptt);
 
@@ -327,3 +721,22 @@ pub struct GroupFileInfo {
     pub uploader_uin: u64,
     pub parent_folder_id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_id_round_trip() {
+        let receipt = MessageReceipt {
+            seqs: vec![100, 101],
+            rands: vec![-12345, 678],
+            time: 1700000000,
+        };
+        let id = MessageId::group(123456, &receipt);
+        assert_eq!(id.to_string(), "g:123456:100,101:-12345,678:1700000000");
+        assert_eq!(id.to_string().parse::<MessageId>().unwrap(), id);
+        assert!("x:1:1:1:1".parse::<MessageId>().is_err());
+        assert!("g:1:1:1".parse::<MessageId>().is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::atomic::Ordering;
+
+use serde::Deserialize;
+
+use crate::Engine;
+
+/// 群荣誉类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum GroupHonorType {
+    /// 龙王
+    Talkative = 1,
+    /// 群聊之火
+    Performer = 2,
+    /// 群聊炽焰
+    Legend = 3,
+    /// 冒尖小春笋
+    StrongNewbie = 5,
+    /// 快乐源泉
+    Emotion = 6,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GroupHonorInfo {
+    #[serde(rename = "gc", default)]
+    pub group_code: String,
+    #[serde(rename = "talkativeList", default)]
+    pub talkative_list: Vec<HonorMemberInfo>,
+    #[serde(rename = "currentTalkative", default)]
+    pub current_talkative: Option<CurrentTalkative>,
+    #[serde(rename = "actorList", default)]
+    pub actor_list: Vec<HonorMemberInfo>,
+    #[serde(rename = "legendList", default)]
+    pub legend_list: Vec<HonorMemberInfo>,
+    #[serde(rename = "strongnewbieList", default)]
+    pub strong_newbie_list: Vec<HonorMemberInfo>,
+    #[serde(rename = "emotionList", default)]
+    pub emotion_list: Vec<HonorMemberInfo>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HonorMemberInfo {
+    #[serde(default)]
+    pub uin: i64,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CurrentTalkative {
+    #[serde(default)]
+    pub uin: i64,
+    #[serde(default)]
+    pub day_count: i32,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(rename = "nick", default)]
+    pub name: String,
+}
+
+/// 群活跃数据图表，每项为一天的数据
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GroupActiveStats {
+    /// 活跃人数
+    #[serde(rename = "actMember", default)]
+    pub active_members: Vec<ActiveStatsItem>,
+    /// 发言条数
+    #[serde(rename = "sentence", default)]
+    pub messages: Vec<ActiveStatsItem>,
+    /// 入群人数
+    #[serde(default)]
+    pub join: Vec<ActiveStatsItem>,
+    /// 退群人数
+    #[serde(default)]
+    pub exit: Vec<ActiveStatsItem>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ActiveStatsItem {
+    #[serde(default)]
+    pub date: String,
+    #[serde(rename = "num", default)]
+    pub count: i64,
+}
+
+/// 群相册
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GroupAlbum {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub desc: String,
+    /// 照片数量
+    #[serde(default)]
+    pub total: i64,
+    #[serde(rename = "createtime", default)]
+    pub create_time: i64,
+    #[serde(rename = "modifytime", default)]
+    pub modify_time: i64,
+}
+
+pub fn group_honor_url(group_code: i64, honor_type: GroupHonorType) -> String {
+    format!(
+        "https://qun.qq.com/interactive/honorlist?gc={}&type={}",
+        group_code, honor_type as i32
+    )
+}
+
+/// 头像尺寸（像素），Original 为原图
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u32)]
+pub enum AvatarSize {
+    Original = 0,
+    S40 = 40,
+    S100 = 100,
+    #[default]
+    S140 = 140,
+    S640 = 640,
+}
+
+/// 用户头像地址
+pub fn avatar_url(uin: i64, size: AvatarSize) -> String {
+    format!("https://q1.qlogo.cn/g?b=qq&nk={}&s={}", uin, size as u32)
+}
+
+/// 群头像地址
+pub fn group_avatar_url(group_code: i64, size: AvatarSize) -> String {
+    format!(
+        "https://p.qlogo.cn/gh/{}/{}/{}/",
+        group_code, group_code, size as u32
+    )
+}
+
+/// 从群荣誉页面中截取 `window.__INITIAL_STATE__` 的 json
+pub fn extract_initial_state(html: &str) -> Option<&str> {
+    let start = html.find("window.__INITIAL_STATE__=")? + "window.__INITIAL_STATE__=".len();
+    let end = html[start..].find("</script>")? + start;
+    Some(html[start..end].trim().trim_end_matches(';'))
+}
+
+impl Engine {
+    /// 生成 web 接口使用的 cookie，domain 如 `qun.qq.com`
+    pub fn get_cookies(&self, domain: &str) -> String {
+        let uin = self.uin.load(Ordering::Relaxed);
+        let sig = &self.transport.sig;
+        let p_skey = sig
+            .ps_key_map
+            .get(domain)
+            .map(|k| String::from_utf8_lossy(k).into_owned())
+            .unwrap_or_default();
+        format!(
+            "uin=o{uin}; skey={}; p_uin=o{uin}; p_skey={p_skey};",
+            String::from_utf8_lossy(&sig.s_key)
+        )
+    }
+
+    /// web 接口使用的 bkn/csrf token
+    pub fn get_csrf_token(&self) -> i32 {
+        csrf_token(&self.transport.sig.s_key)
+    }
+
+    /// 使用 p_skey 计算的 g_tk，qzone 等接口需要
+    pub fn get_domain_csrf_token(&self, domain: &str) -> i32 {
+        csrf_token(
+            self.transport
+                .sig
+                .ps_key_map
+                .get(domain)
+                .map(|k| k.as_ref())
+                .unwrap_or_default(),
+        )
+    }
+}
+
+fn csrf_token(key: &[u8]) -> i32 {
+    let mut hash: i32 = 5381;
+    for c in key.iter() {
+        hash = hash.wrapping_add(hash.wrapping_shl(5).wrapping_add(*c as i32));
+    }
+    hash & 0x7fffffff
+}
@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
 use bytes::BufMut;
+use cached::Cached;
 
 use ricq_core::command::long_conn::OffPicUpResp;
+use ricq_core::command::message_svc::{c2c_routing_head, FriendRoamMessages};
 use ricq_core::command::oidb_svc::{LinkShare, MusicShare, MusicVersion, ShareTarget};
 use ricq_core::command::{friendlist::*, profile_service::*};
 use ricq_core::hex::encode_hex;
@@ -10,15 +13,14 @@ use ricq_core::highway::BdhInput;
 use ricq_core::msg::elem::FriendImage;
 use ricq_core::msg::MessageChain;
 use ricq_core::pb;
-use ricq_core::pb::msg::routing_head::RoutingHead;
-use ricq_core::structs::FriendAudio;
 use ricq_core::structs::MessageReceipt;
+use ricq_core::structs::{FriendAudio, FriendInfo, FriendMessage, FriendOnlineStatus};
 
 use crate::structs::ImageInfo;
 use crate::{RQError, RQResult};
 
 impl super::super::Client {
-    /// 获取好友请求
+    /// 获取好友请求记录，包括已处理的
     pub async fn get_friend_system_messages(&self) -> RQResult<FriendSystemMessages> {
         let req = self
             .engine
@@ -38,14 +40,51 @@ impl super::super::Client {
         msg_seq: i64,
         req_uin: i64,
         accept: bool,
+    ) -> RQResult<()> {
+        self.solve_friend_system_message_with_block(msg_seq, req_uin, accept, false)
+            .await
+    }
+
+    /// 处理好友申请，block 为 true 时拒绝并不再接收此人的好友申请
+    ///
+    /// 账号等级不足不能同意时返回 `RQError::AccountLevelTooLow`
+    pub async fn solve_friend_system_message_with_block(
+        &self,
+        msg_seq: i64,
+        req_uin: i64,
+        accept: bool,
+        block: bool,
     ) -> RQResult<()> {
         let pkt = self
             .engine
             .read()
             .await
-            .build_system_msg_friend_action_packet(msg_seq, req_uin, accept);
-        self.send_and_wait(pkt).await?;
-        Ok(())
+            .build_system_msg_friend_action_packet(msg_seq, req_uin, accept, block, "");
+        let resp = self.send_and_wait(pkt).await?;
+        self.engine.read().await.decode_system_msg_action_response(
+            resp.body,
+            "ProfileService.Pb.ReqSystemMsgAction.Friend",
+        )
+    }
+
+    /// 拒绝好友申请并附带理由，block 为 true 时不再接收此人的好友申请
+    pub async fn reject_friend_system_message(
+        &self,
+        msg_seq: i64,
+        req_uin: i64,
+        reason: &str,
+        block: bool,
+    ) -> RQResult<()> {
+        let pkt = self
+            .engine
+            .read()
+            .await
+            .build_system_msg_friend_action_packet(msg_seq, req_uin, false, block, reason);
+        let resp = self.send_and_wait(pkt).await?;
+        self.engine.read().await.decode_system_msg_action_response(
+            resp.body,
+            "ProfileService.Pb.ReqSystemMsgAction.Friend",
+        )
     }
 
     /// 获取好友列表
@@ -74,7 +113,7 @@ impl super::super::Client {
             .decode_friend_group_list_response(resp.body)
     }
 
-    /// 删除好友
+    /// 删除好友，成功后服务端推送好友列表减少，产生 `QEvent::DeleteFriend`（被对方删除时同样产生）
     /// ## Args
     /// - `del_uin` 为要删除的好友QQid
     ///
@@ -98,6 +137,50 @@ impl super::super::Client {
         }
     }
 
+    /// 拉黑，不再接收此人的消息
+    pub async fn block_user(&self, uin: i64) -> RQResult<()> {
+        let req = self.engine.read().await.build_insert_black_list_packet(uin);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_black_list_op_response(resp.body, "RespInsertBlackList")
+    }
+
+    /// 取消拉黑
+    pub async fn unblock_user(&self, uin: i64) -> RQResult<()> {
+        let req = self.engine.read().await.build_delete_black_list_packet(uin);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_black_list_op_response(resp.body, "RespDeleteBlackList")
+    }
+
+    /// 获取黑名单
+    pub async fn get_blacklist(&self) -> RQResult<Vec<i64>> {
+        let mut output = Vec::new();
+        loop {
+            let req = self
+                .engine
+                .read()
+                .await
+                .build_get_black_list_packet(output.len() as i64, 100);
+            let resp = self.send_and_wait(req).await?;
+            let resp = self
+                .engine
+                .read()
+                .await
+                .decode_get_black_list_response(resp.body)?;
+            let empty = resp.black_uins.is_empty();
+            output.extend(resp.black_uins);
+            if empty || output.len() as i64 >= resp.total {
+                break;
+            }
+        }
+        Ok(output)
+    }
+
     /// 刷新好友列表
     pub async fn get_friend_list(&self) -> RQResult<FriendListResponse> {
         let mut output = FriendListResponse::default();
@@ -112,9 +195,59 @@ impl super::super::Client {
                 break;
             }
         }
+        {
+            let mut cache = self.info_cache.lock().await;
+            for f in &output.friends {
+                cache.friends.cache_set(f.uin, Some(f.clone()));
+            }
+        }
         Ok(output)
     }
 
+    /// 批量查询好友的在线状态、客户端类型和电量，按 uins 的顺序返回，不在线的好友 online 为 false
+    pub async fn get_friends_status(&self, uins: Vec<i64>) -> RQResult<Vec<FriendOnlineStatus>> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_online_friend_info_request_packet();
+        let resp = self.send_and_wait(req).await?;
+        let mut online: HashMap<i64, FriendOnlineStatus> = self
+            .engine
+            .read()
+            .await
+            .decode_online_friend_info_response(resp.body)?
+            .into_iter()
+            .map(|s| (s.uin, s))
+            .collect();
+        Ok(uins
+            .into_iter()
+            .map(|uin| {
+                online.remove(&uin).unwrap_or(FriendOnlineStatus {
+                    uin,
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+
+    /// 从缓存获取好友信息，缓存不存在或已过期时刷新好友列表，不是好友的结果同样会缓存
+    pub async fn get_friend(&self, uin: i64) -> RQResult<Option<FriendInfo>> {
+        if let Some(friend) = self.info_cache.lock().await.friends.cache_get(&uin) {
+            return Ok(friend.clone());
+        }
+        let friend = self
+            .get_friend_list()
+            .await?
+            .friends
+            .into_iter()
+            .find(|f| f.uin == uin);
+        if friend.is_none() {
+            self.info_cache.lock().await.friends.cache_set(uin, None);
+        }
+        Ok(friend)
+    }
+
     /// 好友列表-添加好友分组
     pub async fn friend_list_add_group(&self, sort_id: u8, group_name: String) -> RQResult<()> {
         let req = self
@@ -148,11 +281,68 @@ impl super::super::Client {
         Ok(())
     }
 
+    /// 获取好友漫游消息 (low level api)
+    ///
+    /// 从 last_msg_time 向前拉取最多 count 条，继续拉取时使用返回的 last_msg_time 和 random
+    pub async fn get_friend_roam_messages(
+        &self,
+        uin: i64,
+        last_msg_time: i64,
+        random: i64,
+        count: u32,
+    ) -> RQResult<FriendRoamMessages> {
+        let req = self.engine.read().await.build_get_friend_roam_msg_request(
+            uin,
+            last_msg_time,
+            random,
+            count,
+        );
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_get_friend_roam_msg_response(resp.body)
+    }
+
+    /// 获取好友历史消息，时间范围为 [start_time, end_time]（秒），按时间排序
+    ///
+    /// 需要账号开启消息漫游，语音消息会被跳过
+    pub async fn get_friend_messages(
+        &self,
+        uin: i64,
+        start_time: i64,
+        end_time: i64,
+    ) -> RQResult<Vec<FriendMessage>> {
+        let mut messages = Vec::new();
+        let mut last_msg_time = end_time;
+        let mut random = 0;
+        loop {
+            let resp = self
+                .get_friend_roam_messages(uin, last_msg_time, random, 20)
+                .await?;
+            let reached_start = resp.messages.is_empty()
+                || resp.messages.iter().any(|m| (m.time as i64) < start_time);
+            messages.extend(resp.messages.into_iter().filter(|m| {
+                let time = m.time as i64;
+                time >= start_time && time <= end_time
+            }));
+            let stuck = (resp.last_msg_time, resp.random) == (last_msg_time, random);
+            if resp.complete || reached_start || stuck {
+                break;
+            }
+            last_msg_time = resp.last_msg_time;
+            random = resp.random;
+        }
+        messages.sort_by_key(|m| (m.time, m.seqs.first().copied()));
+        messages.dedup_by(|a, b| a.seqs == b.seqs && a.rands == b.rands);
+        Ok(messages)
+    }
+
     /// 好友戳一戳
     pub async fn friend_poke(&self, target: i64) -> RQResult<()> {
         let req = self.engine.read().await.build_friend_poke_packet(target);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        self.engine.read().await.decode_oidb_result(resp.body)
     }
 
     /// 发送好友消息
@@ -180,14 +370,47 @@ impl super::super::Client {
         message_chain: MessageChain,
         ptt: Option<pb::msg::Ptt>,
     ) -> RQResult<MessageReceipt> {
-        self.send_message(
-            RoutingHead::C2c(pb::msg::C2c {
-                to_uin: Some(target),
-            }),
-            message_chain,
-            ptt,
-        )
-        .await
+        self.send_message(c2c_routing_head(target), message_chain, ptt)
+            .await
+    }
+
+    /// 向多个好友发送同一条消息，消息内容只编码一次
+    ///
+    /// 返回的结果与 targets 顺序一致
+    pub async fn send_friend_messages(
+        &self,
+        targets: Vec<i64>,
+        message_chain: MessageChain,
+    ) -> Vec<RQResult<MessageReceipt>> {
+        let body = self
+            .engine
+            .read()
+            .await
+            .encode_message_body(message_chain.into(), None);
+        let mut receipts = Vec::with_capacity(targets.len());
+        for target in targets {
+            receipts.push(
+                self.send_message_body(c2c_routing_head(target), &body)
+                    .await,
+            );
+        }
+        receipts
+    }
+
+    /// 服务器上已有该图片时直接返回图片元素，不需要上传，没有时返回 None
+    pub async fn try_get_friend_image(
+        &self,
+        target: i64,
+        md5: Vec<u8>,
+        size: u32,
+    ) -> RQResult<Option<FriendImage>> {
+        let image_info = self.cached_image_info(md5, size).await;
+        match self.get_off_pic_store(target, &image_info).await? {
+            OffPicUpResp::Exist { res_id, uuid } => {
+                Ok(Some(image_info.into_friend_image(res_id, uuid)))
+            }
+            OffPicUpResp::UploadRequired { .. } => Ok(None),
+        }
     }
 
     pub async fn upload_friend_image(&self, target: i64, data: Vec<u8>) -> RQResult<FriendImage> {
@@ -195,7 +418,9 @@ impl super::super::Client {
         let image_store = self.get_off_pic_store(target, &image_info).await?;
 
         let friend_image = match image_store {
-            OffPicUpResp::Exist { res_id, uuid } => image_info.into_friend_image(res_id, uuid),
+            OffPicUpResp::Exist { res_id, uuid } => {
+                image_info.clone().into_friend_image(res_id, uuid)
+            }
             OffPicUpResp::UploadRequired {
                 res_id,
                 uuid,
@@ -221,9 +446,10 @@ impl super::super::Client {
                     },
                 )
                 .await?;
-                image_info.into_friend_image(res_id, uuid)
+                image_info.clone().into_friend_image(res_id, uuid)
             }
         };
+        self.image_cache.lock().await.insert(image_info);
         Ok(friend_image)
     }
 
@@ -1,31 +1,40 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, UNIX_EPOCH};
 
 use bytes::Bytes;
 use cached::Cached;
+use futures_util::Stream;
 use prost::Message;
 
 use ricq_core::command::common::PbToBytes;
 use ricq_core::command::img_store::GroupImageStoreResp;
+use ricq_core::command::message_svc::grp_tmp_routing_head;
 use ricq_core::command::multi_msg::gen_forward_preview;
+use ricq_core::command::online_push::GroupMessagePart;
 use ricq_core::command::{friendlist::*, oidb_svc::*, profile_service::*};
-use ricq_core::common::group_code2uin;
 use ricq_core::hex::encode_hex;
 use ricq_core::highway::BdhInput;
-use ricq_core::msg::elem::{Anonymous, GroupImage, RichMsg, VideoFile};
-use ricq_core::msg::MessageChain;
+use ricq_core::msg::elem::{Anonymous, GroupImage, RQElem, Reply, RichMsg, VideoFile};
+use ricq_core::msg::{parse_group_message, MessageChain};
 use ricq_core::pb;
 use ricq_core::pb::short_video::ShortVideoUploadRsp;
 use ricq_core::structs::{ForwardMessage, GroupFileCount, GroupFileList, MessageNode};
-use ricq_core::structs::{GroupAudio, GroupMemberPermission};
+use ricq_core::structs::{GroupAudio, GroupMemberPermission, GroupMessage};
 use ricq_core::structs::{GroupInfo, GroupMemberInfo, MessageReceipt};
+use ricq_core::structs::{MessageId, MessageTargetKind};
 
+use crate::client::recall::SentTarget;
 use crate::structs::ImageInfo;
 use crate::{RQError, RQResult};
 
 impl super::super::Client {
-    /// 获取进群申请信息
-    async fn get_group_system_messages(&self, suspicious: bool) -> RQResult<GroupSystemMessages> {
+    /// 获取进群申请和邀请记录，包括已处理的
+    ///
+    /// suspicious 为 true 时获取被过滤的风险申请
+    pub async fn get_group_system_messages(
+        &self,
+        suspicious: bool,
+    ) -> RQResult<GroupSystemMessages> {
         let req = self
             .engine
             .read()
@@ -49,6 +58,8 @@ impl super::super::Client {
     }
 
     /// 处理加群申请
+    ///
+    /// 账号等级不足不能同意入群邀请时返回 `RQError::AccountLevelTooLow`
     #[allow(clippy::too_many_arguments)]
     pub async fn solve_group_system_message(
         &self,
@@ -75,9 +86,11 @@ impl super::super::Client {
                 block,
                 reason,
             );
-        self.send_and_wait(pkt).await?;
-
-        Ok(())
+        let resp = self.send_and_wait(pkt).await?;
+        self.engine.read().await.decode_system_msg_action_response(
+            resp.body,
+            "ProfileService.Pb.ReqSystemMsgAction.Group",
+        )
     }
 
     /// 获取群列表
@@ -101,8 +114,68 @@ impl super::super::Client {
         group_code: i64,
         message_chain: MessageChain,
     ) -> RQResult<MessageReceipt> {
-        self._send_group_message(group_code, message_chain.into(), None)
-            .await
+        let at_all = message_chain
+            .clone()
+            .into_iter()
+            .any(|e| matches!(e, RQElem::At(at) if at.target == 0));
+        if at_all {
+            self.quota.lock().await.check_at_all(group_code)?;
+        }
+        let receipt = self
+            ._send_group_message(group_code, message_chain.clone().into(), None)
+            .await?;
+        if at_all {
+            self.quota.lock().await.add_at_all(group_code);
+        }
+        self.store_group_message(group_code, &receipt.seqs, message_chain)
+            .await;
+        Ok(receipt)
+    }
+
+    /// 向多个群依次发送同一条消息，每条之间间隔 interval，返回每个群的发送结果
+    ///
+    /// 消息中的群图片会在每个群按 md5 重新获取，服务器上已有的图片不需要重复上传
+    pub async fn broadcast_group_message(
+        &self,
+        group_codes: &[i64],
+        message_chain: MessageChain,
+        interval: Duration,
+    ) -> Vec<(i64, RQResult<MessageReceipt>)> {
+        let images: Vec<GroupImage> = message_chain
+            .iter()
+            .filter_map(|e| match e {
+                RQElem::GroupImage(image) => Some(image),
+                _ => None,
+            })
+            .collect();
+        let mut results = Vec::with_capacity(group_codes.len());
+        for (i, &group_code) in group_codes.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(interval).await;
+            }
+            let mut chain = message_chain.clone();
+            let mut replaced = HashMap::new();
+            for image in &images {
+                let image_info = ImageInfo::from_group_image(image);
+                match self.group_image_if_exists(group_code, image_info).await {
+                    Ok(Some(new)) => {
+                        replaced.insert(image.md5.clone(), new);
+                    }
+                    Ok(None) => {}
+                    Err(err) => tracing::warn!("failed to get group image: {}", err),
+                }
+            }
+            if !replaced.is_empty() {
+                chain.map_elems(|e| match e {
+                    RQElem::GroupImage(image) => {
+                        replaced.get(&image.md5).cloned().map(MessageChain::new)
+                    }
+                    _ => None,
+                });
+            }
+            results.push((group_code, self.send_group_message(group_code, chain).await));
+        }
+        results
     }
 
     /// 发送群语音
@@ -121,6 +194,7 @@ impl super::super::Client {
         elems: Vec<pb::msg::Elem>,
         ptt: Option<pb::msg::Ptt>,
     ) -> RQResult<MessageReceipt> {
+        self.rate_limiter.acquire_group_message(group_code).await?;
         let ran = (rand::random::<u32>() >> 1) as i32;
         let (tx, rx) = tokio::sync::oneshot::channel();
         {
@@ -131,7 +205,17 @@ impl super::super::Client {
             .read()
             .await
             .build_group_sending_packet(group_code, elems, ptt, ran, 1, 0, 0, false);
-        let _ = self.send_and_wait(req).await?;
+        let result = match self.send_message_packet(req).await {
+            Err(RQError::ServerError { code: 120, .. }) => {
+                self.notify_bot_muted(group_code).await;
+                Err(RQError::BotMuted(group_code))
+            }
+            result => result,
+        };
+        self.record_send_result(SentTarget::Group(group_code), result.is_ok());
+        result?;
+        // 禁言到期没有推送，发送成功时清除记录
+        self.muted_groups.lock().await.remove(&group_code);
         let mut receipt = MessageReceipt {
             seqs: vec![0],
             rands: vec![ran],
@@ -146,6 +230,8 @@ impl super::super::Client {
             Ok(Err(_)) => {} //todo
             Err(_) => {}
         }
+        self.record_sent_message(SentTarget::Group(group_code), &receipt)
+            .await;
         Ok(receipt)
     }
 
@@ -157,10 +243,7 @@ impl super::super::Client {
         message_chain: MessageChain,
     ) -> RQResult<MessageReceipt> {
         self.send_message(
-            pb::msg::routing_head::RoutingHead::GrpTmp(pb::msg::GrpTmp {
-                group_uin: Some(group_code2uin(group_code)),
-                to_uin: Some(user_uin),
-            }),
+            grp_tmp_routing_head(group_code, user_uin),
             message_chain,
             None,
         )
@@ -179,10 +262,78 @@ impl super::super::Client {
             .await
             .build_group_member_info_request_packet(group_code, uin);
         let resp = self.send_and_wait(req).await?;
-        self.engine
+        let mut member = self
+            .engine
             .read()
             .await
-            .decode_group_member_info_response(resp.body)
+            .decode_group_member_info_response(resp.body)?;
+        member.group_code = group_code;
+        // 该接口不返回禁言时间，使用成员列表中的记录
+        if let Some(cached) = self
+            .info_cache
+            .lock()
+            .await
+            .members
+            .cache_get(&(group_code, uin))
+        {
+            member.shut_up_timestamp = cached.shut_up_timestamp;
+        }
+        Ok(member)
+    }
+
+    /// 批量获取群成员信息，返回 uin 到成员信息的映射
+    ///
+    /// 分页拉取群成员列表并按 uins 过滤，请求的成员都找到后不再拉取后面的页。
+    /// 不在群内的成员不在结果中，拉取到的成员会进入成员缓存
+    pub async fn get_group_members_info(
+        &self,
+        group_code: i64,
+        uins: Vec<i64>,
+    ) -> RQResult<HashMap<i64, GroupMemberInfo>> {
+        let owner_uin = self
+            .get_group(group_code)
+            .await?
+            .map(|g| g.owner_uin)
+            .unwrap_or_default();
+        let mut wanted: HashSet<i64> = uins.into_iter().collect();
+        let mut members = HashMap::with_capacity(wanted.len());
+        let mut next_uin = 0;
+        while !wanted.is_empty() {
+            let resp = self
+                ._get_group_member_list(group_code, next_uin, owner_uin)
+                .await?;
+            for member in resp.list {
+                if wanted.remove(&member.uin) {
+                    members.insert(member.uin, member);
+                }
+            }
+            if resp.next_uin == 0 {
+                break;
+            }
+            next_uin = resp.next_uin;
+        }
+        Ok(members)
+    }
+
+    /// 创建群聊并邀请 members，返回新群号，新群会加入群列表和信息缓存
+    ///
+    /// 账号等级不足时返回 `RQError::AccountLevelTooLow`
+    pub async fn create_group(&self, name: &str, members: Vec<i64>) -> RQResult<i64> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_create_group_packet(name.into(), members);
+        let resp = self.send_and_wait(req).await?;
+        let group_code = self
+            .engine
+            .read()
+            .await
+            .decode_create_group_response(resp.body)?;
+        if let Err(err) = self.refresh_group_info(group_code).await {
+            tracing::warn!("failed to load created group {}: {}", group_code, err);
+        }
+        Ok(group_code)
     }
 
     /// 批量获取群信息
@@ -204,6 +355,77 @@ impl super::super::Client {
         Ok(self.get_group_infos(vec![group_code]).await?.pop())
     }
 
+    /// 从缓存获取群信息，缓存不存在或已过期时重新拉取
+    pub async fn get_group(&self, group_code: i64) -> RQResult<Option<GroupInfo>> {
+        if let Some(group) = self.info_cache.lock().await.groups.cache_get(&group_code) {
+            return Ok(Some(group.clone()));
+        }
+        let group = self.get_group_info(group_code).await?;
+        if let Some(group) = &group {
+            self.info_cache
+                .lock()
+                .await
+                .groups
+                .cache_set(group_code, group.clone());
+        }
+        Ok(group)
+    }
+
+    /// 从缓存获取群成员信息，缓存不存在或已过期时重新拉取
+    pub async fn get_member(&self, group_code: i64, uin: i64) -> RQResult<GroupMemberInfo> {
+        if let Some(member) = self
+            .info_cache
+            .lock()
+            .await
+            .members
+            .cache_get(&(group_code, uin))
+        {
+            return Ok(member.clone());
+        }
+        let member = self.get_group_member_info(group_code, uin).await?;
+        self.info_cache
+            .lock()
+            .await
+            .members
+            .cache_set((group_code, uin), member.clone());
+        Ok(member)
+    }
+
+    /// 获取自己在群内的权限，使用成员缓存，权限变化事件会同步更新缓存
+    pub async fn get_self_group_permission(
+        &self,
+        group_code: i64,
+    ) -> RQResult<GroupMemberPermission> {
+        let uin = self.uin().await;
+        Ok(self.get_member(group_code, uin).await?.permission)
+    }
+
+    /// 获取与 uin 的共同群
+    ///
+    /// 逐个群查询成员信息，群较多时较慢，查询结果会进入成员缓存
+    pub async fn get_mutual_groups(&self, uin: i64) -> RQResult<Vec<i64>> {
+        let mut group_codes: Vec<i64> = self.group_list.read().await.keys().copied().collect();
+        if group_codes.is_empty() {
+            group_codes = self
+                .reload_group_list(false)
+                .await?
+                .iter()
+                .map(|g| g.code)
+                .collect();
+        }
+        let mut mutual = Vec::new();
+        for group_code in group_codes {
+            match self.get_member(group_code, uin).await {
+                Ok(member) if member.uin == uin => mutual.push(group_code),
+                // 不在群内时没有 mem_info
+                Ok(_) | Err(RQError::Decode(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        mutual.sort_unstable();
+        Ok(mutual)
+    }
+
     /// 刷新群列表
     pub async fn get_group_list(&self) -> RQResult<Vec<GroupInfo>> {
         // 获取群列表
@@ -222,6 +444,70 @@ impl super::super::Client {
         Ok(groups)
     }
 
+    /// 重新加载群列表并更新本地记录
+    ///
+    /// incremental 为 true 时只返回新增或信息有变化的群，否则返回全部群
+    pub async fn reload_group_list(&self, incremental: bool) -> RQResult<Vec<GroupInfo>> {
+        let groups = self.get_group_list().await?;
+        let mut group_list = self.group_list.write().await;
+        let changed = if incremental {
+            groups
+                .iter()
+                .filter(|g| group_list.get(&g.code) != Some(g))
+                .cloned()
+                .collect()
+        } else {
+            groups.clone()
+        };
+        {
+            let mut cache = self.info_cache.lock().await;
+            for g in &groups {
+                cache.groups.cache_set(g.code, g.clone());
+            }
+        }
+        {
+            let now = UNIX_EPOCH.elapsed().unwrap().as_secs() as i64;
+            let mut updated = self.group_info_updated.lock().unwrap();
+            updated.clear();
+            updated.extend(groups.iter().map(|g| (g.code, now)));
+        }
+        *group_list = groups.into_iter().map(|g| (g.code, g)).collect();
+        Ok(changed)
+    }
+
+    /// 重新拉取群的详细信息（群名、群主、人数上限、创建时间、等级等），同时更新群列表和信息缓存
+    ///
+    /// 返回 None 表示已经不在该群，会从群列表和缓存中移除
+    pub async fn refresh_group_info(&self, group_code: i64) -> RQResult<Option<GroupInfo>> {
+        let group = self.get_group_info(group_code).await?;
+        // 持有群列表写锁期间更新缓存，读取方不会看到新旧信息混合的状态
+        let mut group_list = self.group_list.write().await;
+        let mut cache = self.info_cache.lock().await;
+        let mut updated = self.group_info_updated.lock().unwrap();
+        match &group {
+            Some(group) => {
+                group_list.insert(group_code, group.clone());
+                cache.groups.cache_set(group_code, group.clone());
+                updated.insert(group_code, UNIX_EPOCH.elapsed().unwrap().as_secs() as i64);
+            }
+            None => {
+                group_list.remove(&group_code);
+                cache.invalidate_group(group_code);
+                updated.remove(&group_code);
+            }
+        }
+        Ok(group)
+    }
+
+    /// 群信息最后一次通过 refresh_group_info 或 reload_group_list 更新的时间戳（秒）
+    pub fn group_info_updated_at(&self, group_code: i64) -> Option<i64> {
+        self.group_info_updated
+            .lock()
+            .unwrap()
+            .get(&group_code)
+            .copied()
+    }
+
     /// 获取群成员列表 (low level api)
     async fn _get_group_member_list(
         &self,
@@ -235,10 +521,17 @@ impl super::super::Client {
             .await
             .build_group_member_list_request_packet(group_code, next_uin);
         let resp = self.send_and_wait(req).await?;
-        self.engine
+        let mut resp = self
+            .engine
             .read()
             .await
-            .decode_group_member_list_response(resp.body, group_owner_uin)
+            .decode_group_member_list_response(resp.body, group_owner_uin)?;
+        let mut cache = self.info_cache.lock().await;
+        for m in resp.list.iter_mut() {
+            m.group_code = group_code;
+            cache.members.cache_set((group_code, m.uin), m.clone());
+        }
+        Ok(resp)
     }
 
     /// 获取群成员列表
@@ -256,9 +549,6 @@ impl super::super::Client {
             if resp.list.is_empty() {
                 return Err(RQError::EmptyField("GroupMemberListResponse.list"));
             }
-            for m in resp.list.iter_mut() {
-                m.group_code = group_code;
-            }
             list.append(&mut resp.list);
             next_uin = resp.next_uin;
             if next_uin == 0 {
@@ -268,6 +558,28 @@ impl super::super::Client {
         Ok(list)
     }
 
+    /// 分页获取群成员列表，每拉取一页产出一批成员，适用于成员很多的群
+    pub fn get_group_member_list_paginated(
+        &self,
+        group_code: i64,
+        group_owner_uin: i64,
+    ) -> impl Stream<Item = RQResult<Vec<GroupMemberInfo>>> + '_ {
+        futures_util::stream::try_unfold(Some(0), move |next_uin| async move {
+            let Some(next_uin) = next_uin else {
+                return Ok(None);
+            };
+            let resp = self
+                ._get_group_member_list(group_code, next_uin, group_owner_uin)
+                .await?;
+            let next_uin = if resp.next_uin == 0 {
+                None
+            } else {
+                Some(resp.next_uin)
+            };
+            Ok(Some((resp.list, next_uin)))
+        })
+    }
+
     /// 标记群消息已读
     pub async fn mark_group_message_readed(&self, group_code: i64, seq: i32) -> RQResult<()> {
         let req = self
@@ -279,6 +591,75 @@ impl super::super::Client {
         Ok(())
     }
 
+    /// 获取群历史消息，seq 范围为 [from_seq, to_seq]，按 seq 排序
+    ///
+    /// 语音消息和已撤回的消息会被跳过，分片消息会合并为一条
+    pub async fn get_group_messages(
+        &self,
+        group_code: i64,
+        from_seq: i64,
+        to_seq: i64,
+    ) -> RQResult<Vec<GroupMessage>> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_get_group_msg_request(group_code, from_seq, to_seq);
+        let resp = self.send_and_wait(req).await?;
+        let parts = self
+            .engine
+            .read()
+            .await
+            .decode_get_group_msg_response(resp.body)?;
+        let mut merged: Vec<Vec<GroupMessagePart>> = Vec::new();
+        let mut div_index: HashMap<i32, usize> = HashMap::new();
+        for part in parts.into_iter().filter(|p| p.ptt.is_none()) {
+            if part.pkg_num > 1 {
+                if let Some(&i) = div_index.get(&part.div_seq) {
+                    merged[i].push(part);
+                    continue;
+                }
+                div_index.insert(part.div_seq, merged.len());
+            }
+            merged.push(vec![part]);
+        }
+        let mut messages: Vec<_> = merged.into_iter().map(parse_group_message).collect();
+        messages.sort_by_key(|m| m.seqs.first().copied());
+        Ok(messages)
+    }
+
+    /// 通过 MessageId 获取群消息，消息不存在（如已撤回）时返回 None，暂不支持好友消息
+    pub async fn get_message_by_id(&self, id: &MessageId) -> RQResult<Option<GroupMessage>> {
+        if id.kind != MessageTargetKind::Group {
+            return Err(RQError::Other(
+                "only group messages can be fetched by id".into(),
+            ));
+        }
+        let Some(&seq) = id.seqs.first() else {
+            return Err(RQError::EmptyField("seqs"));
+        };
+        let seq = seq as i64;
+        Ok(self
+            .get_group_messages(id.target, seq, seq)
+            .await?
+            .into_iter()
+            .find(|m| m.seqs.first().copied() == Some(seq as i32)))
+    }
+
+    /// 引用回复 MessageId 对应的群消息
+    pub async fn reply_message(
+        &self,
+        id: &MessageId,
+        mut message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        let message = self
+            .get_message_by_id(id)
+            .await?
+            .ok_or(RQError::EmptyField("message"))?;
+        message_chain.with_reply(Reply::from(&message));
+        self.send_group_message(id.target, message_chain).await
+    }
+
     /// 群禁言 (解除禁言 duration=0)
     pub async fn group_mute(
         &self,
@@ -306,14 +687,31 @@ impl super::super::Client {
         Ok(())
     }
 
+    /// 获取群内禁言中的成员，返回 <成员 uin, 禁言结束时间戳（秒）>
+    pub async fn get_group_mute_list(&self, group_code: i64) -> RQResult<HashMap<i64, i64>> {
+        let group = self
+            .get_group(group_code)
+            .await?
+            .ok_or(RQError::EmptyField("group"))?;
+        let now = UNIX_EPOCH.elapsed().unwrap().as_secs() as i64;
+        Ok(self
+            .get_group_member_list(group_code, group.owner_uin)
+            .await?
+            .into_iter()
+            .filter(|m| m.shut_up_timestamp > now)
+            .map(|m| (m.uin, m.shut_up_timestamp))
+            .collect())
+    }
+
     /// 修改群名称
     pub async fn update_group_name(&self, group_code: i64, name: String) -> RQResult<()> {
         let req = self
             .engine
             .read()
             .await
-            .build_group_name_update_packet(group_code, name);
+            .build_group_name_update_packet(group_code, name.clone());
         let _ = self.send_and_wait(req).await?;
+        self.rename_cached_group(group_code, &name).await;
         Ok(())
     }
 
@@ -330,13 +728,13 @@ impl super::super::Client {
 
     /// 设置群管理员
     ///
-    /// flag: true 设置管理员 false 取消管理员
-    pub async fn group_set_admin(&self, group_code: i64, member: i64, flag: bool) -> RQResult<()> {
+    /// enable: true 设置管理员 false 取消管理员
+    pub async fn group_set_admin(&self, group_code: i64, uin: i64, enable: bool) -> RQResult<()> {
         let req = self
             .engine
             .read()
             .await
-            .build_group_admin_set_packet(group_code, member, flag);
+            .build_group_admin_set_packet(group_code, uin, enable);
         let _ = self.send_and_wait(req).await?;
         Ok(())
     }
@@ -348,8 +746,8 @@ impl super::super::Client {
             .read()
             .await
             .build_group_poke_packet(group_code, target);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        self.engine.read().await.decode_oidb_result(resp.body)
     }
 
     /// 群踢人
@@ -360,32 +758,74 @@ impl super::super::Client {
         kick_msg: &str,
         block: bool,
     ) -> RQResult<()> {
-        let req = self.engine.read().await.build_group_kick_packet(
-            group_code,
-            member_uins,
-            kick_msg,
-            block,
-        );
-        let _ = self.send_and_wait(req).await?;
+        self.kick_group_members(group_code, member_uins, block, kick_msg)
+            .await?;
         Ok(())
     }
 
+    /// 批量踢人，所有成员在同一个包中发送
+    ///
+    /// block 为 true 时设置“不再接收此人加群申请”，返回踢出失败的成员 uin
+    pub async fn kick_group_members(
+        &self,
+        group_code: i64,
+        uins: Vec<i64>,
+        block: bool,
+        reason: &str,
+    ) -> RQResult<Vec<i64>> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_kick_packet(group_code, uins, reason, block);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_kick_response(resp.body)
+    }
+
+    /// 邀请好友加群
     pub async fn group_invite(&self, group_code: i64, uin: i64) -> RQResult<()> {
         let req = self
             .engine
             .read()
             .await
             .build_group_invite_packet(group_code, uin);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        let result = self.engine.read().await.decode_oidb_result(resp.body);
+        self.check_restriction(&resp.command_name, result).await
     }
 
+    /// 邀请好友加群，同 group_invite
+    #[deprecated(note = "use `group_invite` instead")]
+    pub async fn invite_friend_to_group(&self, group_code: i64, uin: i64) -> RQResult<()> {
+        self.group_invite(group_code, uin).await
+    }
+
+    /// 退出群，群主需要先转让或解散，否则返回 `Err(RQError::OwnerCannotQuit)`
     pub async fn group_quit(&self, group_code: i64) -> RQResult<()> {
+        if let Some(group) = self.get_group(group_code).await?
+            && group.owner_uin == self.uin().await
+        {
+            return Err(RQError::OwnerCannotQuit);
+        }
         let req = self.engine.read().await.build_quit_group_packet(group_code);
-        let _ = self.send_and_wait(req).await?;
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_mng_response(resp.body)?;
+        self.remove_cached_group(group_code).await;
         Ok(())
     }
 
+    /// 退出群，同 group_quit
+    #[deprecated(note = "use `group_quit` instead")]
+    pub async fn quit_group(&self, group_code: i64) -> RQResult<()> {
+        self.group_quit(group_code).await
+    }
+
     /// 获取群 @全体成员 剩余次数
     pub async fn group_at_all_remain(&self, group_code: i64) -> RQResult<GroupAtAllRemainInfo> {
         let req = self
@@ -400,7 +840,67 @@ impl super::super::Client {
             .decode_group_at_all_remain_response(resp.body)
     }
 
-    /// 设置群头衔
+    /// 获取自己在群里剩余的免费礼物和鲜花数量
+    pub async fn get_group_gift_stock(&self, group_code: i64) -> RQResult<GroupGiftStock> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_gift_stock_request_packet(group_code);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_gift_stock_response(resp.body)
+    }
+
+    /// 获取群礼物排行，start 从 0 开始，`is_end` 为 true 时没有下一页
+    pub async fn get_group_gift_rank(
+        &self,
+        group_code: i64,
+        rank_type: GroupGiftRankType,
+        start: u32,
+        count: u32,
+    ) -> RQResult<GroupGiftRank> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_gift_rank_request_packet(group_code, rank_type, start, count);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_gift_rank_response(resp.body)
+    }
+
+    /// 给群成员送礼物，返回今日剩余的免费礼物数量
+    ///
+    /// 超过本地记录的每日免费礼物配额时返回 `RQError::QuotaExceeded("gift")`，不会发包
+    pub async fn send_group_gift(
+        &self,
+        group_code: i64,
+        uin: i64,
+        product_id: u32,
+        count: u32,
+    ) -> RQResult<u32> {
+        self.quota.lock().await.check_gift(count)?;
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_send_gift_packet(group_code, uin, product_id, count);
+        let resp = self.send_and_wait(req).await?;
+        let remain = self
+            .engine
+            .read()
+            .await
+            .decode_group_send_gift_response(resp.body)?;
+        self.quota.lock().await.set_gift_remain(remain);
+        Ok(remain)
+    }
+
+    /// 设置群成员头衔，需要群主权限，new_title 为空时取消头衔
     pub async fn group_edit_special_title(
         &self,
         group_code: i64,
@@ -416,6 +916,18 @@ impl super::super::Client {
         Ok(())
     }
 
+    /// 设置群成员头衔，同 group_edit_special_title
+    #[deprecated(note = "use `group_edit_special_title` instead")]
+    pub async fn set_group_special_title(
+        &self,
+        group_code: i64,
+        uin: i64,
+        title: impl Into<String>,
+    ) -> RQResult<()> {
+        self.group_edit_special_title(group_code, uin, title.into())
+            .await
+    }
+
     /// 获取自己的匿名信息（用于发送群消息）
     pub async fn get_anony_info(&self, group_code: i64) -> RQResult<Option<Anonymous>> {
         let req = self
@@ -430,6 +942,20 @@ impl super::super::Client {
             .decode_get_anony_info_response(resp.body)
     }
 
+    /// 匿名发送群消息，群未开启匿名聊天时返回错误
+    pub async fn send_group_message_anonymous(
+        &self,
+        group_code: i64,
+        mut message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        let anonymous = self
+            .get_anony_info(group_code)
+            .await?
+            .ok_or_else(|| RQError::Other("anonymous chat is disabled".into()))?;
+        message_chain.with_anonymous(anonymous);
+        self.send_group_message(group_code, message_chain).await
+    }
+
     /// 分享群音乐
     pub async fn send_group_music_share(
         &self,
@@ -515,6 +1041,56 @@ impl super::super::Client {
             .decode_group_image_store_response(resp.body)
     }
 
+    /// 重新获取群图片的下载地址，用于旧图片链接过期后重新下载
+    pub async fn refresh_image_url(
+        &self,
+        group_code: i64,
+        file_id: i64,
+        md5: Vec<u8>,
+    ) -> RQResult<String> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_image_url_packet(group_code, file_id, md5);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_image_url_response(resp.body)
+    }
+
+    /// 服务器上已有该图片时直接返回图片元素，不需要上传，没有时返回 None
+    ///
+    /// md5 在本地图片缓存中时使用缓存的尺寸和格式
+    pub async fn try_get_group_image(
+        &self,
+        group_code: i64,
+        md5: Vec<u8>,
+        size: u32,
+    ) -> RQResult<Option<GroupImage>> {
+        let image_info = self.cached_image_info(md5, size).await;
+        self.group_image_if_exists(group_code, image_info).await
+    }
+
+    pub(crate) async fn group_image_if_exists(
+        &self,
+        group_code: i64,
+        image_info: ImageInfo,
+    ) -> RQResult<Option<GroupImage>> {
+        match self.get_group_image_store(group_code, &image_info).await? {
+            GroupImageStoreResp::Exist { file_id, addrs } => {
+                let signature = self.highway_session.read().await.session_key.to_vec();
+                Ok(Some(image_info.into_group_image(
+                    file_id,
+                    addrs.first().cloned().unwrap_or_default(),
+                    signature,
+                )))
+            }
+            GroupImageStoreResp::NotExist { .. } => Ok(None),
+        }
+    }
+
     /// 上传群图片
     pub async fn upload_group_image(&self, group_code: i64, data: Vec<u8>) -> RQResult<GroupImage> {
         let image_info = ImageInfo::try_new(&data)?;
@@ -522,7 +1098,7 @@ impl super::super::Client {
         let image_store = self.get_group_image_store(group_code, &image_info).await?;
         let signature = self.highway_session.read().await.session_key.to_vec();
         let group_image = match image_store {
-            GroupImageStoreResp::Exist { file_id, addrs } => image_info.into_group_image(
+            GroupImageStoreResp::Exist { file_id, addrs } => image_info.clone().into_group_image(
                 file_id,
                 addrs.first().cloned().unwrap_or_default(),
                 signature,
@@ -551,9 +1127,12 @@ impl super::super::Client {
                     },
                 )
                 .await?;
-                image_info.into_group_image(file_id, addr, signature)
+                image_info
+                    .clone()
+                    .into_group_image(file_id, addr, signature)
             }
         };
+        self.image_cache.lock().await.insert(image_info);
         Ok(group_image)
     }
 
@@ -848,14 +1427,23 @@ impl super::super::Client {
 
     /// 群聊打卡
     pub async fn group_sign_in(&self, group_code: i64) -> RQResult<()> {
+        self.send_group_sign(group_code).await.map(|_| ())
+    }
+
+    /// 群聊打卡，返回打卡提示，服务端拒绝时返回 `RQError::ServerError`
+    pub async fn send_group_sign(&self, group_code: i64) -> RQResult<GroupSignInResult> {
         let req = self
             .engine
             .read()
             .await
             .build_group_sign_in_packet(group_code);
-        self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_sign_in_response(resp.body)
     }
+
     // 获取群文件列表
     pub async fn get_group_file_list(
         &self,
@@ -888,7 +1476,7 @@ impl super::super::Client {
             .await
             .decode_group_file_count_response(resp.body)
     }
-    /// 获取文件下载链接
+    /// 获取文件下载链接，链接有时效，过期后重新调用即可获取新的链接
     /// # Examples
     /// ```
     /// let file_list = client.get_group_file_list(group_code, "/", 0).await.unwrap();
@@ -922,4 +1510,17 @@ impl super::super::Client {
             .await
             .decode_group_file_download_response(resp.body, file_name)
     }
+
+    /// 重新获取群文件的下载地址，同 get_group_file_download
+    #[deprecated(note = "use `get_group_file_download` instead")]
+    pub async fn refresh_group_file_url(
+        &self,
+        group_code: i64,
+        file_id: &str,
+        bus_id: u32,
+        file_name: &str,
+    ) -> RQResult<String> {
+        self.get_group_file_download(group_code, file_id, bus_id, file_name)
+            .await
+    }
 }
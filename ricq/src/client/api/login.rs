@@ -1,8 +1,12 @@
 use std::sync::atomic::Ordering;
 
+use crate::ext::login::CaptchaSolver;
+use crate::handler::QEvent;
 use crate::jce::SvcRespRegister;
+use crate::HeartbeatKind;
 use crate::{RQError, RQResult};
 use ricq_core::command::wtlogin::*;
+use ricq_core::structs::OnlineStatus;
 use ricq_core::token::Token;
 
 /// 登录相关
@@ -61,7 +65,7 @@ impl super::super::Client {
         uin: i64,
         password_md5: &[u8],
     ) -> RQResult<LoginResponse> {
-        self.engine.read().await.uin.store(uin, Ordering::Relaxed);
+        self.uin.store(uin, Ordering::Relaxed);
         let req = self
             .engine
             .read()
@@ -78,16 +82,28 @@ impl super::super::Client {
             .await
     }
 
-    /// 密码登录 - 请求短信验证码
-    pub async fn request_sms(&self) -> RQResult<LoginResponse> {
+    /// 密码登录 - 请求短信验证码，需要先收到 DeviceLocked
+    ///
+    /// 请求过于频繁时返回 `Err(RQError::SmsTooFrequent)`
+    pub async fn request_sms(&self) -> RQResult<SmsRequestResult> {
         let req = self.engine.read().await.build_sms_request_packet();
         let resp = self.send_and_wait(req).await?;
         let resp = self.engine.read().await.decode_login_response(resp.body)?;
         self.process_login_response(resp.clone()).await;
-        Ok(resp)
+        match resp {
+            LoginResponse::DeviceLocked(l) => Ok(SmsRequestResult {
+                phone: l.sms_phone,
+                available_count: l.sms_available_count,
+                resend_interval: l.sms_resend_interval.unwrap_or(60),
+            }),
+            LoginResponse::TooManySMSRequest => Err(RQError::SmsTooFrequent),
+            other => Err(RQError::Other(format!("invalid sms resp: {other:?}"))),
+        }
     }
 
     /// 密码登录 - 提交短信验证码
+    ///
+    /// 验证码错误时返回 `Err(RQError::SmsCodeRejected)`，成功时可能还需要 device_lock_login
     pub async fn submit_sms_code(&self, code: &str) -> RQResult<LoginResponse> {
         let req = self
             .engine
@@ -97,7 +113,15 @@ impl super::super::Client {
         let resp = self.send_and_wait(req).await?;
         let resp = self.engine.read().await.decode_login_response(resp.body)?;
         self.process_login_response(resp.clone()).await;
-        Ok(resp)
+        match resp {
+            LoginResponse::UnknownStatus(s) => Err(RQError::SmsCodeRejected(s.message)),
+            // 验证码错误与密码错误是同一个 status
+            LoginResponse::Failed(f) if f.kind == LoginFailedKind::WrongPassword => {
+                Err(RQError::SmsCodeRejected(f.message))
+            }
+            LoginResponse::TooManySMSRequest => Err(RQError::SmsTooFrequent),
+            resp => Ok(resp),
+        }
     }
 
     /// 密码登录 - 提交滑块ticket
@@ -109,6 +133,28 @@ impl super::super::Client {
         Ok(resp)
     }
 
+    /// 密码登录 - 使用 CaptchaSolver 获取并提交滑块 ticket
+    ///
+    /// ticket 过期或无效时服务端会再次返回 NeedCaptcha，最多尝试 max_attempts 次，
+    /// 之后返回 `Err(RQError::CaptchaTicketRejected)`
+    pub async fn submit_ticket_with(
+        &self,
+        captcha: &LoginNeedCaptcha,
+        solver: &dyn CaptchaSolver,
+        max_attempts: usize,
+    ) -> RQResult<LoginResponse> {
+        let mut verify_url = captcha.verify_url.clone();
+        for _ in 0..max_attempts {
+            let url = verify_url.ok_or(RQError::EmptyField("verify_url"))?;
+            let ticket = solver.solve(&url).await?;
+            match self.submit_ticket(&ticket).await? {
+                LoginResponse::NeedCaptcha(captcha) => verify_url = captcha.verify_url,
+                resp => return Ok(resp),
+            }
+        }
+        Err(RQError::CaptchaTicketRejected(max_attempts))
+    }
+
     /// 设备锁登录 - 二维码、密码登录都需要
     pub async fn device_lock_login(&self) -> RQResult<LoginResponse> {
         let req = self.engine.read().await.build_device_lock_login_packet();
@@ -141,9 +187,35 @@ impl super::super::Client {
         Ok(resp)
     }
 
+    /// 换 token 并重新注册，成功后产生 CredentialsRefreshed 事件，返回新的 token
+    pub async fn refresh_token(&self) -> RQResult<Token> {
+        match self.request_change_sig(Some(3554528)).await? {
+            LoginResponse::Success(_) => {}
+            resp => {
+                tracing::warn!("unexpected exchange_emp response: {:?}", resp);
+                return Err(RQError::TokenLoginFailed);
+            }
+        }
+        self.register_client().await?;
+        let token = self.gen_token().await;
+        self.handler
+            .handle(QEvent::CredentialsRefreshed(token.clone()))
+            .await;
+        Ok(token)
+    }
+
     /// 注册客户端，登录后必须注册
     pub async fn register_client(&self) -> RQResult<SvcRespRegister> {
-        let req = self.engine.read().await.build_client_register_packet();
+        let status = if self.invisible.load(Ordering::Relaxed) {
+            OnlineStatus::Invisible
+        } else {
+            OnlineStatus::Online
+        };
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_client_register_packet_with_status(status);
         let resp = self.send_and_wait(req).await?;
         let resp = self
             .engine
@@ -157,8 +229,12 @@ impl super::super::Client {
         Ok(resp)
     }
 
+    /// 发送一次心跳，心跳包类型见 `Config::heartbeat`
     pub async fn heartbeat(&self) -> RQResult<()> {
-        let req = self.engine.read().await.build_heartbeat_packet();
+        let req = match self.heartbeat_config().kind {
+            HeartbeatKind::Alive => self.engine.read().await.build_heartbeat_packet(),
+            HeartbeatKind::Sso => self.engine.read().await.build_sso_heartbeat_packet(),
+        };
         let _ = self.send_and_wait(req).await?;
         Ok(())
     }
@@ -1,26 +1,38 @@
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
+use std::sync::Weak;
 use std::time::UNIX_EPOCH;
 
 use bytes::Bytes;
-use cached::Cached;
+use prost::Message;
 
+use ricq_core::command::common::PbToBytes;
 use ricq_core::command::message_svc::MessageSyncResponse;
 use ricq_core::command::oidb_svc::*;
 use ricq_core::common::{group_code2uin, RQAddr};
+use ricq_core::hex::encode_hex;
 use ricq_core::highway::BdhInput;
 use ricq_core::msg::MessageChain;
 use ricq_core::pb;
-use ricq_core::structs::Status;
-use ricq_core::structs::SummaryCardInfo;
+use ricq_core::protocol::device::random_uuid;
+use ricq_core::protocol::packet::Packet;
+use ricq_core::structs::{BotMuted, SummaryCardInfo};
 use ricq_core::structs::{ForwardMessage, MessageReceipt};
+use ricq_core::structs::{OnlineStatus, Status};
+use ricq_core::Engine;
 
+use crate::client::event::{AccountCapabilityNotice, BotMutedEvent};
+use crate::client::recall::SentTarget;
+use crate::handler::QEvent;
 use crate::jce::SvcDevLoginInfo;
+use crate::structs::ImageInfo;
 use crate::{RQError, RQResult};
 
 mod friend;
 mod group;
 mod login;
+#[cfg(feature = "web")]
+mod web;
 
 /// API
 impl super::Client {
@@ -44,6 +56,22 @@ impl super::Client {
         Ok(())
     }
 
+    /// 切换隐身状态，之后重新注册客户端（如断线重连）时也会保持
+    pub async fn set_invisible(&self, invisible: bool) -> RQResult<()> {
+        self.invisible.store(invisible, Ordering::Relaxed);
+        self.update_online_status(if invisible {
+            OnlineStatus::Invisible
+        } else {
+            OnlineStatus::Online
+        })
+        .await
+    }
+
+    /// 是否处于隐身状态
+    pub fn is_invisible(&self) -> bool {
+        self.invisible.load(Ordering::Relaxed)
+    }
+
     /// 修改签名
     pub async fn update_signature(&self, signature: String) -> RQResult<()> {
         let req = self
@@ -117,12 +145,14 @@ impl super::Client {
         source: i32,
         cookies: Bytes,
     ) -> RQResult<()> {
+        self.quota.lock().await.check_like(uin, count as u32)?;
         let req = self
             .engine
             .read()
             .await
             .build_send_like_packet(uin, count, source, cookies);
         let _ = self.send_and_wait(req).await?;
+        self.quota.lock().await.add_like(uin, count as u32);
         Ok(())
     }
 
@@ -151,6 +181,61 @@ impl super::Client {
         Ok(decode)
     }
 
+    /// 上传图片并进行 OCR 识别
+    pub async fn ocr_image(&self, data: Bytes) -> RQResult<OcrResponse> {
+        let image_info = ImageInfo::try_new(&data)?;
+        let url = self.upload_ocr_image(data.to_vec()).await?;
+        self.image_ocr(
+            url,
+            encode_hex(&image_info.md5).to_uppercase(),
+            image_info.size as i32,
+            image_info.width as i32,
+            image_info.height as i32,
+        )
+        .await
+    }
+
+    /// 通过 highway 上传 OCR 图片，返回下载地址
+    async fn upload_ocr_image(&self, data: Vec<u8>) -> RQResult<String> {
+        let addr = self
+            .highway_addrs
+            .read()
+            .await
+            .first()
+            .ok_or(RQError::EmptyField("highway_addrs"))?
+            .clone();
+        if self.highway_session.read().await.session_key.is_empty() {
+            return Err(RQError::EmptyField("highway_session_key"));
+        }
+        let ext = pb::CommFileExtReq {
+            action_type: 0,
+            uuid: random_uuid(&mut rand::thread_rng()).into_bytes(),
+        };
+        let rsp = self
+            .highway_upload_bdh(
+                addr.into(),
+                BdhInput {
+                    command_id: 76,
+                    body: data,
+                    ticket: self.highway_session.read().await.sig_session.to_vec(),
+                    ext: ext.to_bytes().to_vec(),
+                    encrypt: false,
+                    chunk_size: 256 * 1024,
+                    send_echo: true,
+                },
+            )
+            .await?;
+        let rsp = pb::CommFileExtRsp::decode(&*rsp)?;
+        if rsp.retcode != 0 {
+            return Err(RQError::server_error(
+                "PicUp.DataUp",
+                rsp.retcode,
+                "failed to upload ocr image",
+            ));
+        }
+        Ok(String::from_utf8_lossy(&rsp.download_url).into_owned())
+    }
+
     // 标记消息已收到，server 不再重复推送
     pub async fn delete_message(&self, items: Vec<pb::MessageItem>) -> RQResult<()> {
         let req = self
@@ -196,43 +281,51 @@ impl super::Client {
     }
 
     // 从服务端拉取通知
+    // 已拉取消息的确认(delete_message)跨页合并，并与下一页的拉取并发进行
     pub(crate) async fn sync_all_message(&self) -> RQResult<Vec<pb::msg::Message>> {
         const SYNC_START: i32 = 0;
         const _SYNC_CONTINUE: i32 = 1;
         const SYNC_STOP: i32 = 2;
+        const DELETE_BATCH_SIZE: usize = 100;
 
         let mut sync_flag = SYNC_START;
         let mut msgs = Vec::new();
+        let mut items: Vec<pb::MessageItem> = Vec::new();
         loop {
-            let resp = match self.sync_message(sync_flag).await {
+            let acks = if items.len() >= DELETE_BATCH_SIZE {
+                std::mem::take(&mut items)
+            } else {
+                Vec::new()
+            };
+            let (resp, ack) = tokio::join!(self.sync_message(sync_flag), async {
+                if acks.is_empty() {
+                    Ok(())
+                } else {
+                    self.delete_message(acks).await
+                }
+            });
+            if let Err(err) = ack {
+                tracing::warn!("failed to delete_message: {}", err);
+                break;
+            }
+            let resp = match resp {
                 Ok(resp) => resp,
                 Err(_) => {
                     tracing::warn!("failed to sync_message");
                     break;
                 }
             };
-            if let Err(err) = self
-                .delete_message(
-                    resp.msgs
-                        .iter()
-                        .map(|m| {
-                            let head = m.head.as_ref().unwrap();
-                            pb::MessageItem {
-                                from_uin: head.from_uin(),
-                                to_uin: head.to_uin(),
-                                msg_type: head.msg_type(),
-                                msg_seq: head.msg_seq(),
-                                msg_uid: head.msg_uid(),
-                                ..Default::default()
-                            }
-                        })
-                        .collect(),
-                )
-                .await
-            {
-                tracing::warn!("failed to delete_message: {}", err);
-                break;
-            }
+            items.extend(resp.msgs.iter().map(|m| {
+                let head = m.head.as_ref().unwrap();
+                pb::MessageItem {
+                    from_uin: head.from_uin(),
+                    to_uin: head.to_uin(),
+                    msg_type: head.msg_type(),
+                    msg_seq: head.msg_seq(),
+                    msg_uid: head.msg_uid(),
+                    ..Default::default()
+                }
+            }));
             match resp.msg_rsp_type {
                 0 => {
                     let mut engine = self.engine.write().await;
@@ -263,6 +356,12 @@ impl super::Client {
                 break;
             }
         }
+        if !items.is_empty() {
+            if let Err(err) = self.delete_message(items).await {
+                tracing::warn!("failed to delete_message: {}", err);
+            }
+        }
+        self.persist_sync_cookies().await;
         Ok(msgs)
     }
 
@@ -401,29 +500,123 @@ impl super::Client {
         routing_head: pb::msg::routing_head::RoutingHead,
         message_chain: MessageChain,
         ptt: Option<pb::msg::Ptt>,
+    ) -> RQResult<MessageReceipt> {
+        let elems = message_chain.into();
+        self.send_message_with(routing_head, |engine, routing_head, seq, ran, time| {
+            engine.build_send_message_packet(routing_head, elems, ptt, seq, ran, time)
+        })
+        .await
+    }
+
+    /// 发送已编码的消息，见 Engine::encode_message_body
+    pub async fn send_message_body(
+        &self,
+        routing_head: pb::msg::routing_head::RoutingHead,
+        body: &[u8],
+    ) -> RQResult<MessageReceipt> {
+        self.send_message_with(routing_head, |engine, routing_head, seq, ran, time| {
+            engine.build_send_message_packet_with_body(routing_head, body, seq, ran, time)
+        })
+        .await
+    }
+
+    /// 分配 seq 和 rand，由 build 构造 MessageSvc.PbSendMsg 包发送，并记录发送结果
+    async fn send_message_with(
+        &self,
+        routing_head: pb::msg::routing_head::RoutingHead,
+        build: impl FnOnce(&Engine, pb::msg::routing_head::RoutingHead, i32, i32, i64) -> Packet,
     ) -> RQResult<MessageReceipt> {
         let time = UNIX_EPOCH.elapsed().unwrap().as_secs() as i64;
         let seq = self.engine.read().await.next_friend_seq();
         let ran = (rand::random::<u32>() >> 1) as i32;
-        let (tx, _) = tokio::sync::oneshot::channel();
-        {
-            self.receipt_waiters.lock().await.cache_set(ran, tx);
+        let target = sent_target(&routing_head);
+        let req = build(&*self.engine.read().await, routing_head, seq, ran, time);
+        let result = self.send_message_packet(req).await;
+        if let Some(target) = target {
+            self.record_send_result(target, result.is_ok());
         }
-        let req = self.engine.read().await.build_send_message_packet(
-            routing_head,
-            message_chain.into(),
-            ptt,
-            seq,
-            ran,
-            time,
-        );
-        self.send_and_wait(req).await?;
+        result?;
         let receipt = MessageReceipt {
             seqs: vec![seq],
             rands: vec![ran],
-            time: UNIX_EPOCH.elapsed().unwrap().as_secs() as i64,
+            time,
         };
+        if let Some(target) = target {
+            self.record_sent_message(target, &receipt).await;
+        }
         // 除了群聊，都不需要等 receipt 的 seq
         Ok(receipt)
     }
+
+    /// 发送 MessageSvc.PbSendMsg 并检查返回结果
+    pub(crate) async fn send_message_packet(&self, req: Packet) -> RQResult<()> {
+        let resp = self.send_and_wait(req).await?;
+        let result = self
+            .engine
+            .read()
+            .await
+            .decode_send_message_response(resp.body);
+        self.check_restriction(&resp.command_name, result).await
+    }
+
+    /// 操作因账号限制失败时产生 AccountCapabilityNotice 事件，每种限制只产生一次
+    pub(crate) async fn check_restriction<T>(
+        &self,
+        command: &str,
+        result: RQResult<T>,
+    ) -> RQResult<T> {
+        if let Err(err) = &result
+            && let Some(restriction) = err.account_restriction()
+            && self.notified_restrictions.lock().await.insert(restriction)
+        {
+            let message = match err {
+                RQError::RealNameRequired(s) | RQError::AccountLevelTooLow(s) => s.clone(),
+                _ => err.to_string(),
+            };
+            self.handler
+                .handle(QEvent::AccountCapabilityNotice(AccountCapabilityNotice {
+                    command: command.into(),
+                    restriction,
+                    message,
+                }))
+                .await;
+        }
+        result
+    }
+
+    /// 发送群消息时发现被禁言，没有收到过禁言推送时产生 BotMuted 事件
+    async fn notify_bot_muted(&self, group_code: i64) {
+        if !self.muted_groups.lock().await.insert(group_code) {
+            return;
+        }
+        let Some(client) = self.weak_self.get().and_then(Weak::upgrade) else {
+            return;
+        };
+        self.handler
+            .handle(QEvent::BotMuted(BotMutedEvent {
+                client,
+                inner: BotMuted {
+                    group_code,
+                    operator_uin: None,
+                    duration: None,
+                },
+            }))
+            .await;
+    }
+
+    /// 本地图片缓存中有该 md5 时使用缓存的图片信息
+    async fn cached_image_info(&self, md5: Vec<u8>, size: u32) -> ImageInfo {
+        match self.image_cache.lock().await.get(&md5) {
+            Some(info) => info.clone(),
+            None => ImageInfo::from_md5(md5, size),
+        }
+    }
+}
+
+/// 可以撤回的私聊消息的接收方
+fn sent_target(routing_head: &pb::msg::routing_head::RoutingHead) -> Option<SentTarget> {
+    match routing_head {
+        pb::msg::routing_head::RoutingHead::C2c(c2c) => c2c.to_uin.map(SentTarget::Friend),
+        _ => None,
+    }
 }
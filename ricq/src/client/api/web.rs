@@ -0,0 +1,400 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use base64::Engine as _;
+use bytes::Bytes;
+use cached::Cached;
+use ricq_core::hex::encode_hex;
+use ricq_core::msg::elem::{Anonymous, GroupImage};
+use ricq_core::web::{
+    avatar_url, extract_initial_state, group_avatar_url, group_honor_url, AvatarSize,
+    GroupActiveStats, GroupAlbum, GroupHonorInfo, GroupHonorType,
+};
+
+use crate::structs::ImageInfo;
+use crate::{RQError, RQResult};
+
+/// upload_group_image_from_url 下载图片的大小上限
+const MAX_URL_IMAGE_SIZE: usize = 30 * 1024 * 1024;
+/// 群相册分片上传每片的大小
+const ALBUM_SLICE_SIZE: usize = 16 * 1024;
+
+/// web API，需要开启 `web` feature
+impl super::super::Client {
+    /// 带上登录 cookie 请求 web 接口，返回 body 文本
+    pub(crate) async fn web_get(&self, url: &str, domain: &str) -> RQResult<String> {
+        let cookies = self.engine.read().await.get_cookies(domain);
+        reqwest::Client::new()
+            .get(url)
+            .header("Cookie", cookies)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(format!("web request failed: {e}")))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(format!("web request failed: {e}")))
+    }
+
+    /// 带上登录 cookie 以表单 POST 请求 web 接口，返回 body 文本
+    pub(crate) async fn web_post_form(
+        &self,
+        url: &str,
+        domain: &str,
+        form: &[(&str, String)],
+    ) -> RQResult<String> {
+        let cookies = self.engine.read().await.get_cookies(domain);
+        reqwest::Client::new()
+            .post(url)
+            .header("Cookie", cookies)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(format!("web request failed: {e}")))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(format!("web request failed: {e}")))
+    }
+
+    /// 带上登录 cookie 以 json POST 请求 web 接口，返回 body 文本
+    pub(crate) async fn web_post_json(
+        &self,
+        url: &str,
+        domain: &str,
+        body: &serde_json::Value,
+    ) -> RQResult<String> {
+        let cookies = self.engine.read().await.get_cookies(domain);
+        reqwest::Client::new()
+            .post(url)
+            .header("Cookie", cookies)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| RQError::Other(format!("web request failed: {e}")))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(format!("web request failed: {e}")))
+    }
+
+    /// 开启或关闭群匿名聊天
+    pub async fn set_group_anonymous_chat(&self, group_code: i64, enable: bool) -> RQResult<()> {
+        let bkn = self.engine.read().await.get_csrf_token();
+        let resp = self
+            .web_post_form(
+                "https://qqweb.qq.com/c/anonymoustalk/set_anony_switch",
+                "qqweb.qq.com",
+                &[
+                    ("bkn", bkn.to_string()),
+                    ("value", (enable as u8).to_string()),
+                    ("group_code", group_code.to_string()),
+                ],
+            )
+            .await?;
+        check_retcode(&resp)
+    }
+
+    /// 禁言匿名成员，anonymous 为收到的消息中的 `MessageChain::anonymous`
+    pub async fn ban_anonymous_member(
+        &self,
+        group_code: i64,
+        anonymous: &Anonymous,
+        duration: Duration,
+    ) -> RQResult<()> {
+        let bkn = self.engine.read().await.get_csrf_token();
+        let resp = self
+            .web_post_form(
+                "https://qqweb.qq.com/c/anonymoustalk/blacklist",
+                "qqweb.qq.com",
+                &[
+                    ("anony_id", anonymous.id()),
+                    ("group_code", group_code.to_string()),
+                    ("seconds", duration.as_secs().to_string()),
+                    ("anony_nick", anonymous.nick.clone()),
+                    ("bkn", bkn.to_string()),
+                ],
+            )
+            .await?;
+        check_retcode(&resp)
+    }
+
+    /// 获取群荣誉信息（龙王、群聊之火等）
+    pub async fn get_group_honor_info(
+        &self,
+        group_code: i64,
+        honor_type: GroupHonorType,
+    ) -> RQResult<GroupHonorInfo> {
+        let html = self
+            .web_get(&group_honor_url(group_code, honor_type), "qun.qq.com")
+            .await?;
+        let state = extract_initial_state(&html)
+            .ok_or_else(|| RQError::Decode("group honor initial state not found".into()))?;
+        serde_json::from_str(state).map_err(|e| RQError::Decode(e.to_string()))
+    }
+
+    /// 获取群活跃数据（活跃人数、发言条数、入群和退群人数）
+    pub async fn get_group_active_stats(&self, group_code: i64) -> RQResult<GroupActiveStats> {
+        let bkn = self.engine.read().await.get_csrf_token();
+        let resp = self
+            .web_get(
+                &format!(
+                    "https://qqweb.qq.com/c/activedata/get_mygroup_data?bkn={}&gc={}",
+                    bkn, group_code
+                ),
+                "qqweb.qq.com",
+            )
+            .await?;
+        check_retcode(&resp)?;
+        let mut resp: serde_json::Value =
+            serde_json::from_str(&resp).map_err(|e| RQError::Decode(e.to_string()))?;
+        serde_json::from_value(resp["data"].take()).map_err(|e| RQError::Decode(e.to_string()))
+    }
+
+    /// 获取群相册列表
+    pub async fn list_group_albums(&self, group_code: i64) -> RQResult<Vec<GroupAlbum>> {
+        let (uin, g_tk) = {
+            let engine = self.engine.read().await;
+            (engine.uin(), engine.get_domain_csrf_token("qzone.qq.com"))
+        };
+        let resp = self
+            .web_get(
+                &format!(
+                    "https://h5.qzone.qq.com/proxy/domain/u.photo.qzone.qq.com/cgi-bin/upp/qun_list_album_v2?g_tk={}&qunId={}&uin={}&start=0&num=1000&getMemberRole=1&inCharset=utf-8&outCharset=utf-8&source=qzone&attach_info=&callbackFun=",
+                    g_tk, group_code, uin
+                ),
+                "qzone.qq.com",
+            )
+            .await?;
+        let mut resp: serde_json::Value =
+            serde_json::from_str(&resp).map_err(|e| RQError::Decode(e.to_string()))?;
+        match resp["code"].as_i64() {
+            None | Some(0) => {}
+            Some(code) => return Err(RQError::UnsuccessfulRetCode(code as i32)),
+        }
+        match resp["data"]["album"].take() {
+            serde_json::Value::Null => Ok(Vec::new()),
+            albums => serde_json::from_value(albums).map_err(|e| RQError::Decode(e.to_string())),
+        }
+    }
+
+    /// 上传图片到群相册，album_id 见 [`list_group_albums`](Self::list_group_albums)
+    ///
+    /// 使用 qzone 网页的分片上传接口，先申请上传会话再按 16KB 分片上传，接口没有公开文档，网页版改动时可能失效
+    pub async fn upload_group_album_photo(
+        &self,
+        group_code: i64,
+        album_id: &str,
+        data: Vec<u8>,
+    ) -> RQResult<()> {
+        let album = self
+            .list_group_albums(group_code)
+            .await?
+            .into_iter()
+            .find(|a| a.id == album_id)
+            .ok_or_else(|| {
+                RQError::Other(format!(
+                    "album {} not found in group {}",
+                    album_id, group_code
+                ))
+            })?;
+        let (uin, g_tk, p_skey) = {
+            let engine = self.engine.read().await;
+            let p_skey = engine
+                .transport
+                .sig
+                .ps_key_map
+                .get("qzone.qq.com")
+                .map(|k| String::from_utf8_lossy(k).into_owned())
+                .unwrap_or_default();
+            (
+                engine.uin(),
+                engine.get_domain_csrf_token("qzone.qq.com"),
+                p_skey,
+            )
+        };
+        let checksum = encode_hex(&md5::compute(&data).0);
+        let now = timestamp();
+        let control = serde_json::json!({
+            "control_req": [{
+                "uin": uin.to_string(),
+                "token": { "type": 4, "data": p_skey, "appid": 5 },
+                "appid": "qun",
+                "checksum": checksum,
+                "check_type": 0,
+                "file_len": data.len(),
+                "env": { "refer": "qzone", "deviceInfo": "h5" },
+                "model": 0,
+                "biz_req": {
+                    "sPicTitle": "",
+                    "sPicDesc": "",
+                    "sAlbumName": album.title,
+                    "sAlbumID": album.id,
+                    "iAlbumTypeID": 0,
+                    "iBitmap": 0,
+                    "iUploadType": 0,
+                    "iUpPicType": 0,
+                    "iBatchID": now,
+                    "sPicPath": "",
+                    "iPicWidth": 0,
+                    "iPicHight": 0,
+                    "iWaterType": 0,
+                    "iDistinctUse": 0,
+                    "iNeedFeeds": 1,
+                    "iUploadTime": now,
+                    "mapExt": { "appid": "qun", "userid": group_code.to_string() },
+                },
+                "session": "",
+                "asy_upload": 0,
+                "cmd": "FileUpload",
+            }]
+        });
+        let resp = self
+            .web_post_json(
+                &format!(
+                    "https://h5.qzone.qq.com/webapp/json/sliceUpload/FileBatchControl/{}?g_tk={}",
+                    checksum, g_tk
+                ),
+                "qzone.qq.com",
+                &control,
+            )
+            .await?;
+        let session = check_ret(&resp)?["data"]["session"]
+            .as_str()
+            .map(String::from)
+            .ok_or(RQError::EmptyField("session"))?;
+        for (seq, chunk) in data.chunks(ALBUM_SLICE_SIZE).enumerate() {
+            let offset = seq * ALBUM_SLICE_SIZE;
+            let end = offset + chunk.len();
+            let resp = self
+                .web_post_form(
+                    &format!(
+                        "https://h5.qzone.qq.com/webapp/json/sliceUpload/FileUpload?seq={}&retry=0&offset={}&end={}&total={}&type=form&g_tk={}",
+                        seq, offset, end, data.len(), g_tk
+                    ),
+                    "qzone.qq.com",
+                    &[
+                        ("uin", uin.to_string()),
+                        ("appid", "qun".into()),
+                        ("session", session.clone()),
+                        ("offset", offset.to_string()),
+                        ("data", base64::engine::general_purpose::STANDARD.encode(chunk)),
+                        ("checksum", String::new()),
+                        ("check_type", "0".into()),
+                        ("retry", "0".into()),
+                        ("seq", seq.to_string()),
+                        ("end", end.to_string()),
+                        ("cmd", "FileUpload".into()),
+                        ("slice_size", ALBUM_SLICE_SIZE.to_string()),
+                        ("biz_req.iUploadType", "0".into()),
+                    ],
+                )
+                .await?;
+            check_ret(&resp)?;
+        }
+        Ok(())
+    }
+
+    /// 下载用户头像，带时间戳避免拿到 CDN 缓存的旧头像
+    pub async fn download_avatar(&self, uin: i64, size: AvatarSize) -> RQResult<Bytes> {
+        download(&format!("{}&t={}", avatar_url(uin, size), timestamp())).await
+    }
+
+    /// 下载群头像，带时间戳避免拿到 CDN 缓存的旧头像
+    pub async fn download_group_avatar(
+        &self,
+        group_code: i64,
+        size: AvatarSize,
+    ) -> RQResult<Bytes> {
+        download(&format!(
+            "{}?t={}",
+            group_avatar_url(group_code, size),
+            timestamp()
+        ))
+        .await
+    }
+
+    /// 上传外链图片到群
+    ///
+    /// 同一 url 上传过且服务器上仍有该图片时不会重新下载和上传，超过 30MB 的图片返回错误
+    pub async fn upload_group_image_from_url(
+        &self,
+        group_code: i64,
+        url: &str,
+    ) -> RQResult<GroupImage> {
+        let known = self
+            .url_images
+            .lock()
+            .await
+            .cache_get(&url.to_string())
+            .cloned();
+        if let Some(image_info) = known {
+            if let Some(image) = self.group_image_if_exists(group_code, image_info).await? {
+                return Ok(image);
+            }
+        }
+        let data = download_limited(url, MAX_URL_IMAGE_SIZE).await?;
+        let image_info = ImageInfo::try_new(&data)?;
+        let image = self.upload_group_image(group_code, data).await?;
+        self.url_images
+            .lock()
+            .await
+            .cache_set(url.to_string(), image_info);
+        Ok(image)
+    }
+}
+
+/// 检查 web 接口返回的 retcode 和 cgicode
+fn check_retcode(resp: &str) -> RQResult<()> {
+    let resp: serde_json::Value =
+        serde_json::from_str(resp).map_err(|e| RQError::Decode(e.to_string()))?;
+    for key in ["retcode", "cgicode"] {
+        match resp[key].as_i64() {
+            None | Some(0) => {}
+            Some(code) => return Err(RQError::UnsuccessfulRetCode(code as i32)),
+        }
+    }
+    Ok(())
+}
+
+/// 检查 qzone 接口返回的 ret，返回解析后的 json
+fn check_ret(resp: &str) -> RQResult<serde_json::Value> {
+    let resp: serde_json::Value =
+        serde_json::from_str(resp).map_err(|e| RQError::Decode(e.to_string()))?;
+    match resp["ret"].as_i64() {
+        None | Some(0) => Ok(resp),
+        Some(code) => Err(RQError::UnsuccessfulRetCode(code as i32)),
+    }
+}
+
+fn timestamp() -> u64 {
+    UNIX_EPOCH.elapsed().unwrap().as_secs()
+}
+
+async fn download(url: &str) -> RQResult<Bytes> {
+    reqwest::get(url)
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| RQError::Other(format!("web request failed: {e}")))?
+        .bytes()
+        .await
+        .map_err(|e| RQError::Other(format!("web request failed: {e}")))
+}
+
+/// 下载并限制大小，Content-Length 超限时不读取 body
+async fn download_limited(url: &str, limit: usize) -> RQResult<Vec<u8>> {
+    let err = |e: reqwest::Error| RQError::Other(format!("web request failed: {e}"));
+    let mut resp = reqwest::get(url)
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(err)?;
+    let too_large = || RQError::Other(format!("image larger than {} bytes: {}", limit, url));
+    if resp.content_length().unwrap_or_default() > limit as u64 {
+        return Err(too_large());
+    }
+    let mut data = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(err)? {
+        if data.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
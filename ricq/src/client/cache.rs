@@ -0,0 +1,243 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use cached::{Cached, SizedCache, TimedSizedCache};
+
+use ricq_core::hex::{decode_hex, encode_hex};
+use ricq_core::structs::{FriendInfo, GroupInfo, GroupMemberInfo, GroupMemberPermission};
+
+use crate::structs::ImageInfo;
+use crate::CacheConfig;
+
+/// 好友、群、群成员信息缓存，过期或被事件失效后由 get_* 方法重新拉取
+pub(crate) struct InfoCache {
+    pub groups: TimedSizedCache<i64, GroupInfo>,
+    /// <(group_code, uin), info>
+    pub members: TimedSizedCache<(i64, i64), GroupMemberInfo>,
+    /// None 表示不是好友，避免每次查询非好友时都重新拉取好友列表
+    pub friends: TimedSizedCache<i64, Option<FriendInfo>>,
+}
+
+impl InfoCache {
+    pub fn new(config: &CacheConfig) -> Self {
+        Self {
+            groups: TimedSizedCache::with_size_and_lifespan(config.capacity, config.ttl),
+            members: TimedSizedCache::with_size_and_lifespan(config.capacity, config.ttl),
+            friends: TimedSizedCache::with_size_and_lifespan(config.capacity, config.ttl),
+        }
+    }
+
+    /// 群信息变化（成员数、群名等）
+    pub fn invalidate_group(&mut self, group_code: i64) {
+        self.groups.cache_remove(&group_code);
+    }
+
+    /// 成员信息变化（群名片、权限等）
+    pub fn invalidate_member(&mut self, group_code: i64, uin: i64) {
+        self.members.cache_remove(&(group_code, uin));
+    }
+
+    /// 成员权限变化，直接更新缓存中的权限，不需要重新拉取
+    pub fn set_member_permission(
+        &mut self,
+        group_code: i64,
+        uin: i64,
+        permission: GroupMemberPermission,
+    ) {
+        if let Some(member) = self.members.cache_get_mut(&(group_code, uin)) {
+            member.permission = permission;
+        }
+    }
+
+    /// 成员加入或离开，群信息中的成员数随之变化
+    pub fn invalidate_membership(&mut self, group_code: i64, uin: i64) {
+        self.invalidate_group(group_code);
+        self.invalidate_member(group_code, uin);
+    }
+
+    pub fn invalidate_friend(&mut self, uin: i64) {
+        self.friends.cache_remove(&uin);
+    }
+}
+
+/// 上传过的图片 <md5, 图片信息>，用于 try_get_group_image / try_get_friend_image 补全图片尺寸
+///
+/// 最多保存 `CacheConfig::capacity` 条，设置了 `Config::image_cache_path` 时在后台线程追加写入文件，重启后加载。
+/// 被淘汰的条目仍留在文件中，文件行数超过容量的两倍时按当前缓存内容重写
+pub(crate) struct ImageCache {
+    images: SizedCache<Vec<u8>, ImageInfo>,
+    /// 按顺序执行文件写入，保证重写不会和之前的追加交错
+    writer: Option<(mpsc::Sender<FileOp>, JoinHandle<()>)>,
+    /// 文件中的行数
+    file_lines: usize,
+}
+
+enum FileOp {
+    Append(String),
+    Rewrite(Vec<String>),
+}
+
+impl ImageCache {
+    pub fn load(path: Option<PathBuf>, capacity: usize) -> Self {
+        let mut images = SizedCache::with_size(capacity.max(1));
+        let mut file_lines = 0;
+        if let Some(path) = &path {
+            match std::fs::read_to_string(path) {
+                Ok(text) => {
+                    file_lines = text.lines().count();
+                    for info in text.lines().filter_map(decode_image_info) {
+                        images.cache_set(info.md5.clone(), info);
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => tracing::warn!("failed to load image cache: {}", err),
+            }
+        }
+        let writer = path.map(|path| {
+            let (tx, rx) = mpsc::channel();
+            let handle = std::thread::spawn(move || {
+                for op in rx {
+                    if let Err(err) = write_file(&path, op) {
+                        tracing::warn!("failed to save image cache: {}", err);
+                    }
+                }
+            });
+            (tx, handle)
+        });
+        let mut cache = Self {
+            images,
+            writer,
+            file_lines,
+        };
+        cache.compact_if_needed();
+        cache
+    }
+
+    pub fn get(&mut self, md5: &[u8]) -> Option<&ImageInfo> {
+        self.images.cache_get(&md5.to_vec())
+    }
+
+    pub fn insert(&mut self, info: ImageInfo) {
+        if self.images.cache_get(&info.md5).is_some() {
+            return;
+        }
+        if let Some((tx, _)) = &self.writer {
+            let _ = tx.send(FileOp::Append(encode_image_info(&info)));
+            self.file_lines += 1;
+        }
+        self.images.cache_set(info.md5.clone(), info);
+        self.compact_if_needed();
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.cache_size()
+    }
+
+    fn compact_if_needed(&mut self) {
+        let Some((tx, _)) = &self.writer else {
+            return;
+        };
+        if self.file_lines <= self.images.cache_capacity().unwrap_or_default() * 2 {
+            return;
+        }
+        // value_order 从最近使用开始，倒序写入，重新加载时保持相同的淘汰顺序
+        let mut lines: Vec<String> = self.images.value_order().map(encode_image_info).collect();
+        lines.reverse();
+        self.file_lines = lines.len();
+        let _ = tx.send(FileOp::Rewrite(lines));
+    }
+
+    /// 等待文件写入完成
+    #[cfg(test)]
+    fn close(mut self) {
+        if let Some((tx, handle)) = self.writer.take() {
+            drop(tx);
+            handle.join().unwrap();
+        }
+    }
+}
+
+fn write_file(path: &Path, op: FileOp) -> io::Result<()> {
+    match op {
+        FileOp::Append(line) => std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut f| writeln!(f, "{line}")),
+        FileOp::Rewrite(lines) => {
+            // 先写临时文件再替换，写入中途失败不会丢失原文件
+            let tmp = path.with_extension("tmp");
+            let mut f = std::fs::File::create(&tmp)?;
+            for line in lines {
+                writeln!(f, "{line}")?;
+            }
+            f.sync_all()?;
+            std::fs::rename(tmp, path)
+        }
+    }
+}
+
+fn encode_image_info(info: &ImageInfo) -> String {
+    format!(
+        "{} {} {} {} {} {}",
+        encode_hex(&info.md5),
+        info.size,
+        info.width,
+        info.height,
+        info.image_type,
+        info.filename
+    )
+}
+
+fn decode_image_info(line: &str) -> Option<ImageInfo> {
+    let mut fields = line.split_whitespace();
+    Some(ImageInfo {
+        md5: decode_hex(fields.next()?).ok()?,
+        size: fields.next()?.parse().ok()?,
+        width: fields.next()?.parse().ok()?,
+        height: fields.next()?.parse().ok()?,
+        image_type: fields.next()?.parse().ok()?,
+        filename: fields.next()?.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(i: u32) -> ImageInfo {
+        ImageInfo {
+            md5: i.to_be_bytes().to_vec(),
+            size: i,
+            width: 1,
+            height: 1,
+            image_type: 1000,
+            filename: format!("{i}.png"),
+        }
+    }
+
+    #[test]
+    fn test_image_cache_file_bounded() {
+        let path = std::env::temp_dir().join(format!("ricq-image-cache-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let capacity = 4;
+
+        let mut cache = ImageCache::load(Some(path.clone()), capacity);
+        for i in 0..100 {
+            cache.insert(image(i));
+        }
+        cache.close();
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert!(lines <= capacity * 2, "{lines} lines");
+
+        // 重新加载后保留最近插入的条目
+        let mut cache = ImageCache::load(Some(path.clone()), capacity);
+        assert_eq!(cache.len(), capacity);
+        assert!(cache.get(&99u32.to_be_bytes()).is_some());
+        assert!(cache.get(&95u32.to_be_bytes()).is_none());
+        cache.close();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
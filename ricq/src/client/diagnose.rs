@@ -0,0 +1,135 @@
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use cached::Cached;
+use tokio::task::JoinSet;
+
+use crate::client::tcp::tcp_connect_timeout;
+use crate::Client;
+
+/// 服务器连通性检测结果
+#[derive(Debug, Clone)]
+pub struct AddrProbe {
+    pub addr: SocketAddr,
+    /// TCP 连接耗时，None 表示连接失败或超时
+    pub latency: Option<Duration>,
+}
+
+/// 本地缓存条目数
+#[derive(Debug, Clone, Default)]
+pub struct CacheSizes {
+    pub group_list: usize,
+    pub groups: usize,
+    pub members: usize,
+    pub friends: usize,
+    pub group_messages: usize,
+    pub images: usize,
+}
+
+/// [`Client::diagnose`] 的检测报告
+#[derive(Debug, Clone)]
+pub struct DiagnoseReport {
+    /// 见 [`crate::client::NetworkStatus`]
+    pub network_status: u8,
+    pub online: bool,
+    pub heartbeat_enabled: bool,
+    /// 心跳包往返耗时，None 表示未在线或心跳失败
+    pub heartbeat_rtt: Option<Duration>,
+    pub sso_servers: Vec<AddrProbe>,
+    pub highway_servers: Vec<AddrProbe>,
+    /// 服务器时间减本地时间（秒），由最近收到的群消息估算，没有收到过消息时为 None
+    pub clock_skew: Option<i64>,
+    /// 是否持有登录凭证 (tgt, d2)
+    pub token_valid: bool,
+    /// s_key (cookie) 剩余有效时间（秒），负数表示已过期
+    pub s_key_expires_in: i64,
+    pub cache: CacheSizes,
+}
+
+async fn probe_addrs(addrs: Vec<SocketAddr>, timeout: Duration) -> Vec<AddrProbe> {
+    let mut join_set = JoinSet::new();
+    for addr in addrs {
+        join_set.spawn(async move {
+            let start = Instant::now();
+            let latency = tcp_connect_timeout(addr, timeout)
+                .await
+                .ok()
+                .map(|_| start.elapsed());
+            AddrProbe { addr, latency }
+        });
+    }
+    let mut probes = Vec::new();
+    while let Some(result) = join_set.join_next().await {
+        if let Ok(probe) = result {
+            probes.push(probe);
+        }
+    }
+    probes.sort_by_key(|p| p.latency.unwrap_or(Duration::MAX));
+    probes
+}
+
+impl Client {
+    /// 自检：服务器连通性、心跳、时钟偏差、登录凭证和缓存状态
+    ///
+    /// 排查“收不到消息”等问题时可以先附上这份报告
+    pub async fn diagnose(&self) -> DiagnoseReport {
+        let timeout = Duration::from_secs(3);
+        let online = self.online.load(Ordering::Relaxed);
+        let heartbeat_rtt = if online {
+            let start = Instant::now();
+            self.heartbeat().await.ok().map(|_| start.elapsed())
+        } else {
+            None
+        };
+        let highway_addrs = self
+            .highway_addrs
+            .read()
+            .await
+            .iter()
+            .cloned()
+            .map(SocketAddr::from)
+            .collect();
+        let (sso_servers, highway_servers) = tokio::join!(
+            async { probe_addrs(self.get_address_list().await, timeout).await },
+            probe_addrs(highway_addrs, timeout)
+        );
+
+        let (token_valid, s_key_expires_in) = {
+            let engine = self.engine.read().await;
+            let sig = &engine.transport.sig;
+            (
+                !sig.tgt.is_empty() && !sig.d2.is_empty(),
+                sig.s_key_expired_time - UNIX_EPOCH.elapsed().unwrap().as_secs() as i64,
+            )
+        };
+        let clock_skew = match self.clock_skew.load(Ordering::Relaxed) {
+            i64::MIN => None,
+            skew => Some(skew),
+        };
+        let cache = {
+            let info_cache = self.info_cache.lock().await;
+            CacheSizes {
+                group_list: self.group_list.read().await.len(),
+                groups: info_cache.groups.cache_size(),
+                members: info_cache.members.cache_size(),
+                friends: info_cache.friends.cache_size(),
+                group_messages: self.group_message_store.read().await.cache_size(),
+                images: self.image_cache.lock().await.len(),
+            }
+        };
+
+        DiagnoseReport {
+            network_status: self.get_status(),
+            online,
+            heartbeat_enabled: self.heartbeat_enabled.load(Ordering::Relaxed),
+            heartbeat_rtt,
+            sso_servers,
+            highway_servers,
+            clock_skew,
+            token_valid,
+            s_key_expires_in,
+            cache,
+        }
+    }
+}
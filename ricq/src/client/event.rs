@@ -1,12 +1,19 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use bytes::Bytes;
 use ricq_core::command::profile_service::{JoinGroupRequest, NewFriendRequest, SelfInvited};
+use ricq_core::msg::elem::Reply;
+use ricq_core::msg::MessageChain;
+use ricq_core::structs::{AccountRestriction, GroupMemberPermission, MessageReceipt};
 use ricq_core::structs::{
-    DeleteFriend, FriendAudioMessage, FriendInfo, FriendMessageRecall, FriendPoke,
-    GroupAudioMessage, GroupDisband, GroupLeave, GroupMessageRecall, GroupMute, GroupNameUpdate,
-    GroupPoke, GroupTempMessage, MemberPermissionChange, NewMember,
+    BotMuted, BotUnmuted, DeleteFriend, FriendAudioMessage, FriendInfo, FriendMessageRecall,
+    FriendPoke, FriendStatusChanged, FriendTransfer, GroupAudioMessage, GroupDisband, GroupGrayTip,
+    GroupLeave, GroupMessageRecall, GroupMute, GroupNameUpdate, GroupPoke, GroupRedPacket,
+    GroupTempMessage, KickedOffline, MemberCardChanged, MemberPermissionChange, NewMember,
+    OtherClientStatusChanged, TempSessionMessage,
 };
-use ricq_core::{jce, RQResult};
+use ricq_core::RQResult;
 
 use crate::client::NetworkStatus;
 use crate::structs::{FriendMessage, GroupMessage};
@@ -33,10 +40,129 @@ impl GroupMessageEvent {
             )
             .await
     }
+
+    /// 消息中是否 at 了自己，不包括 @全体成员
+    pub async fn mentions_me(&self) -> bool {
+        let uin = self.client.uin().await;
+        self.inner.elements.at_targets().contains(&uin)
+    }
+
+    /// 发送者在群内的权限，使用成员缓存，不会每条消息都请求成员信息
+    pub async fn sender_permission(&self) -> RQResult<GroupMemberPermission> {
+        Ok(self
+            .client
+            .get_member(self.inner.group_code, self.inner.from_uin)
+            .await?
+            .permission)
+    }
+
+    /// 向消息所在的群发送消息
+    pub async fn send(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_group_message(self.inner.group_code, message_chain)
+            .await
+    }
+
+    /// 引用回复该消息
+    pub async fn reply(&self, mut message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        message_chain.with_reply(Reply::from(&self.inner));
+        self.send(message_chain).await
+    }
+
+    /// 将发送者移出群，block 为 true 时不再接受其加群申请
+    pub async fn kick_sender(&self, block: bool) -> RQResult<()> {
+        self.client
+            .group_kick(self.inner.group_code, vec![self.inner.from_uin], "", block)
+            .await
+    }
+
+    /// 禁言发送者
+    pub async fn mute_sender(&self, duration: Duration) -> RQResult<()> {
+        self.client
+            .group_mute(self.inner.group_code, self.inner.from_uin, duration)
+            .await
+    }
+
+    /// 发送者群名片，消息中没有时从成员缓存获取，群名片为空则返回昵称
+    pub async fn sender_card(&self) -> RQResult<String> {
+        if !self.inner.group_card.is_empty() {
+            return Ok(self.inner.group_card.clone());
+        }
+        let member = self
+            .client
+            .get_member(self.inner.group_code, self.inner.from_uin)
+            .await?;
+        Ok(if member.card_name.is_empty() {
+            member.nickname
+        } else {
+            member.card_name
+        })
+    }
+
+    /// 群名称，消息中没有时从群信息缓存获取
+    pub async fn group_name(&self) -> RQResult<String> {
+        if !self.inner.group_name.is_empty() {
+            return Ok(self.inner.group_name.clone());
+        }
+        Ok(self
+            .client
+            .get_group(self.inner.group_code)
+            .await?
+            .map(|g| g.name)
+            .unwrap_or_default())
+    }
 }
 
 pub type FriendMessageEvent = EventWithClient<FriendMessage>;
+
+impl FriendMessageEvent {
+    /// 向发送者发送消息
+    pub async fn send(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_friend_message(self.inner.from_uin, message_chain)
+            .await
+    }
+
+    /// 引用回复该消息
+    pub async fn reply(&self, mut message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        message_chain.with_reply(Reply::from(&self.inner));
+        self.send(message_chain).await
+    }
+
+    /// 撤回该消息，只能撤回自己发送的消息
+    pub async fn recall(&self) -> RQResult<()> {
+        self.client
+            .recall_friend_message(
+                self.inner.target,
+                self.inner.time as i64,
+                self.inner.seqs.clone(),
+                self.inner.rands.clone(),
+            )
+            .await
+    }
+}
+
 pub type GroupTempMessageEvent = EventWithClient<GroupTempMessage>;
+
+impl GroupTempMessageEvent {
+    /// 通过临时会话回复发送者，非群来源的临时会话使用消息中的 sig
+    pub async fn reply(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_message(self.inner.routing_head(), message_chain, None)
+            .await
+    }
+}
+
+pub type TempSessionEvent = EventWithClient<TempSessionMessage>;
+
+impl TempSessionEvent {
+    /// 通过临时会话回复发送者
+    pub async fn reply(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_message(self.inner.routing_head(), message_chain, None)
+            .await
+    }
+}
 pub type JoinGroupRequestEvent = EventWithClient<JoinGroupRequest>;
 
 impl JoinGroupRequestEvent {
@@ -85,10 +211,24 @@ impl NewFriendRequestEvent {
             .solve_friend_system_message(self.inner.msg_seq, self.inner.req_uin, false)
             .await
     }
+
+    /// 拒绝并不再接收此人的好友申请
+    pub async fn reject_and_block(&self) -> RQResult<()> {
+        self.client
+            .solve_friend_system_message_with_block(
+                self.inner.msg_seq,
+                self.inner.req_uin,
+                false,
+                true,
+            )
+            .await
+    }
 }
 
 pub type NewMemberEvent = EventWithClient<NewMember>;
 pub type GroupMuteEvent = EventWithClient<GroupMute>;
+pub type BotMutedEvent = EventWithClient<BotMuted>;
+pub type BotUnmutedEvent = EventWithClient<BotUnmuted>;
 pub type FriendMessageRecallEvent = EventWithClient<FriendMessageRecall>;
 pub type GroupMessageRecallEvent = EventWithClient<GroupMessageRecall>;
 pub type NewFriendEvent = EventWithClient<FriendInfo>;
@@ -96,10 +236,50 @@ pub type GroupLeaveEvent = EventWithClient<GroupLeave>;
 pub type GroupDisbandEvent = EventWithClient<GroupDisband>;
 pub type FriendPokeEvent = EventWithClient<FriendPoke>;
 pub type GroupPokeEvent = EventWithClient<GroupPoke>;
+pub type GroupRedPacketEvent = EventWithClient<GroupRedPacket>;
+pub type FriendTransferEvent = EventWithClient<FriendTransfer>;
+pub type GroupGrayTipEvent = EventWithClient<GroupGrayTip>;
 pub type GroupNameUpdateEvent = EventWithClient<GroupNameUpdate>;
+pub type MemberCardChangedEvent = EventWithClient<MemberCardChanged>;
 pub type DeleteFriendEvent = EventWithClient<DeleteFriend>;
+pub type FriendStatusChangedEvent = EventWithClient<FriendStatusChanged>;
 pub type MemberPermissionChangeEvent = EventWithClient<MemberPermissionChange>;
 pub type SelfInvitedEvent = EventWithClient<SelfInvited>;
+/// 自己被邀请入群，accept 后会收到 `QEvent::BotJoinGroup`
+pub type GroupInvitedEvent = SelfInvitedEvent;
+
+impl SelfInvitedEvent {
+    pub async fn accept(&self) -> RQResult<()> {
+        self.client
+            .solve_group_system_message(
+                self.inner.msg_seq,
+                self.inner.invitor_uin,
+                self.inner.group_code,
+                false,
+                true,
+                true,
+                false,
+                "".into(),
+            )
+            .await
+    }
+
+    pub async fn reject(&self, reason: String, block: bool) -> RQResult<()> {
+        self.client
+            .solve_group_system_message(
+                self.inner.msg_seq,
+                self.inner.invitor_uin,
+                self.inner.group_code,
+                false,
+                true,
+                false,
+                block,
+                reason,
+            )
+            .await
+    }
+}
+
 pub type GroupAudioMessageEvent = EventWithClient<GroupAudioMessage>;
 
 impl GroupAudioMessageEvent {
@@ -120,8 +300,8 @@ impl FriendAudioMessageEvent {
     }
 }
 
-pub type KickedOfflineEvent = EventWithClient<jce::RequestPushForceOffline>;
-pub type MSFOfflineEvent = EventWithClient<jce::RequestMSFForceOffline>;
+pub type KickedOfflineEvent = EventWithClient<KickedOffline>;
+pub type OtherClientStatusChangedEvent = EventWithClient<OtherClientStatusChanged>;
 
 #[derive(Copy, Clone, Debug)]
 #[repr(u8)]
@@ -149,3 +329,34 @@ impl ClientDisconnect {
         self.inner
     }
 }
+
+/// 操作因账号实名、等级限制失败，每种限制只通知一次
+#[derive(Debug, Clone)]
+pub struct AccountCapabilityNotice {
+    /// 失败的命令
+    pub command: String,
+    pub restriction: AccountRestriction,
+    /// 服务端返回的提示
+    pub message: String,
+}
+
+/// 没有解析的推送包
+#[derive(Debug, Clone)]
+pub struct UnhandledPush {
+    pub command: String,
+    pub seq: i32,
+    pub body_len: usize,
+    /// 包内容，仅在 `Config::report_unhandled_body` 开启时有值
+    pub body: Option<Bytes>,
+}
+
+/// handler 处理事件时 panic
+#[derive(Debug, Clone)]
+pub struct HandlerError {
+    /// 出错的事件类型，见 [`QEvent::name`](crate::handler::QEvent::name)
+    pub event: &'static str,
+    pub group_code: Option<i64>,
+    pub user_uin: Option<i64>,
+    /// panic 信息
+    pub message: String,
+}
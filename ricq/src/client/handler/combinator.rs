@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{Handler, QEvent};
+
+/// 事件处理后是否继续传递给后续 handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagation {
+    Continue,
+    Stop,
+}
+
+/// 可以中断事件传递的 handler，用于 [`HandlerChain`]
+#[async_trait]
+pub trait ChainHandler: Sync + Send {
+    async fn handle_chained(&self, event: QEvent) -> Propagation;
+}
+
+#[async_trait]
+impl<H> ChainHandler for Arc<H>
+where
+    H: ChainHandler + ?Sized,
+{
+    async fn handle_chained(&self, event: QEvent) -> Propagation {
+        self.as_ref().handle_chained(event).await
+    }
+}
+
+/// 普通 handler 处理后总是继续传递
+struct ContinueHandler<H>(H);
+
+#[async_trait]
+impl<H> ChainHandler for ContinueHandler<H>
+where
+    H: Handler + Send,
+{
+    async fn handle_chained(&self, event: QEvent) -> Propagation {
+        self.0.handle(event).await;
+        Propagation::Continue
+    }
+}
+
+/// 按顺序执行多个 handler，任意一个返回 [`Propagation::Stop`] 时停止传递
+///
+/// ```ignore
+/// let handler = HandlerChain::new()
+///     .chained(CommandHandler)
+///     .handler(DefaultHandler);
+/// ```
+#[derive(Default)]
+pub struct HandlerChain {
+    handlers: Vec<Box<dyn ChainHandler>>,
+}
+
+impl HandlerChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加普通 handler
+    pub fn handler<H>(self, handler: H) -> Self
+    where
+        H: Handler + Send + 'static,
+    {
+        self.chained(ContinueHandler(handler))
+    }
+
+    /// 添加可以中断传递的 handler
+    pub fn chained<H>(mut self, handler: H) -> Self
+    where
+        H: ChainHandler + 'static,
+    {
+        self.handlers.push(Box::new(handler));
+        self
+    }
+}
+
+#[async_trait]
+impl ChainHandler for HandlerChain {
+    async fn handle_chained(&self, event: QEvent) -> Propagation {
+        for handler in &self.handlers {
+            if handler.handle_chained(event.clone()).await == Propagation::Stop {
+                return Propagation::Stop;
+            }
+        }
+        Propagation::Continue
+    }
+}
+
+#[async_trait]
+impl Handler for HandlerChain {
+    async fn handle(&self, event: QEvent) {
+        self.handle_chained(event).await;
+    }
+}
+
+/// 只把满足条件的事件交给内部 handler
+pub struct FilterHandler<H> {
+    inner: H,
+    filter: Box<dyn Fn(&QEvent) -> bool + Send + Sync>,
+}
+
+impl<H> FilterHandler<H> {
+    pub fn new<F>(inner: H, filter: F) -> Self
+    where
+        F: Fn(&QEvent) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            inner,
+            filter: Box::new(filter),
+        }
+    }
+
+    /// 只处理指定群的事件，非群事件会被忽略
+    pub fn groups(inner: H, groups: impl IntoIterator<Item = i64>) -> Self {
+        let groups: HashSet<i64> = groups.into_iter().collect();
+        Self::new(inner, move |e| {
+            e.group_code().is_some_and(|code| groups.contains(&code))
+        })
+    }
+
+    /// 只处理指定用户发起的事件
+    pub fn uins(inner: H, uins: impl IntoIterator<Item = i64>) -> Self {
+        let uins: HashSet<i64> = uins.into_iter().collect();
+        Self::new(inner, move |e| {
+            e.user_uin().is_some_and(|uin| uins.contains(&uin))
+        })
+    }
+}
+
+#[async_trait]
+impl<H> Handler for FilterHandler<H>
+where
+    H: Handler + Send,
+{
+    async fn handle(&self, event: QEvent) {
+        if (self.filter)(&event) {
+            self.inner.handle(event).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<H> ChainHandler for FilterHandler<H>
+where
+    H: ChainHandler,
+{
+    async fn handle_chained(&self, event: QEvent) -> Propagation {
+        if (self.filter)(&event) {
+            self.inner.handle_chained(event).await
+        } else {
+            Propagation::Continue
+        }
+    }
+}
+
+/// 每个事件在单独的 task 中处理，耗时的 handler 不会阻塞后续事件
+pub struct SpawnHandler<H> {
+    inner: Arc<H>,
+}
+
+impl<H> SpawnHandler<H> {
+    pub fn new(inner: H) -> Self {
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+}
+
+#[async_trait]
+impl<H> Handler for SpawnHandler<H>
+where
+    H: Handler + Send + 'static,
+{
+    async fn handle(&self, event: QEvent) {
+        let inner = self.inner.clone();
+        tokio::spawn(async move { inner.handle(event).await });
+    }
+}
+
+/// 一组具名的 handler，可以在运行时启用或停用
+///
+/// 需要在注册后切换状态时，用 `Arc<ModuleHandler>` 加入 [`HandlerChain`]
+pub struct ModuleHandler {
+    pub name: String,
+    enabled: AtomicBool,
+    chain: HandlerChain,
+}
+
+impl ModuleHandler {
+    pub fn new<S: Into<String>>(name: S, chain: HandlerChain) -> Self {
+        Self {
+            name: name.into(),
+            enabled: AtomicBool::new(true),
+            chain,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl ChainHandler for ModuleHandler {
+    async fn handle_chained(&self, event: QEvent) -> Propagation {
+        if self.is_enabled() {
+            self.chain.handle_chained(event).await
+        } else {
+            Propagation::Continue
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for ModuleHandler {
+    async fn handle(&self, event: QEvent) {
+        self.handle_chained(event).await;
+    }
+}
@@ -1,7 +1,13 @@
+use std::any::Any;
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use futures_util::FutureExt;
+use ricq_core::token::Token;
 use tokio::sync::{
     broadcast::Sender as BroadcastSender,
     mpsc::{Sender as MpscSender, UnboundedSender},
@@ -9,6 +15,13 @@ use tokio::sync::{
 };
 
 use crate::client::event::*;
+use crate::client::recent::RecentBuffer;
+use crate::client::NetworkStatus;
+use crate::SupervisionStrategy;
+
+pub use combinator::*;
+
+mod combinator;
 
 /// 所有需要外发的数据的枚举打包
 #[derive(Clone, derivative::Derivative)]
@@ -16,55 +29,195 @@ use crate::client::event::*;
 pub enum QEvent {
     /// 登录成功事件
     Login(i64),
+    /// 定时或手动换 token 成功，可以保存新的 token
+    CredentialsRefreshed(Token),
     /// 群消息
     GroupMessage(GroupMessageEvent),
+    /// 自己发送的群消息：其他设备发送的，以及本客户端发送后没有被回执消耗的回声（如等待回执超时）
+    SelfGroupMessage(GroupMessageEvent),
     /// 群语音
     GroupAudioMessage(GroupAudioMessageEvent),
     /// 好友消息
     FriendMessage(FriendMessageEvent),
+    /// 自己发送的好友消息（同步）：其他设备发送的，以及本客户端发送后没有被回执消耗的回声
+    SelfFriendMessage(FriendMessageEvent),
     /// 群语音
     FriendAudioMessage(FriendAudioMessageEvent),
     /// 群临时消息
     GroupTempMessage(GroupTempMessageEvent),
+    /// 非群来源的临时会话消息
+    TempSession(TempSessionEvent),
     /// 加群申请
     GroupRequest(JoinGroupRequestEvent),
     /// 加群申请
     SelfInvited(SelfInvitedEvent),
     /// 加好友申请
     NewFriendRequest(NewFriendRequestEvent),
-    /// 新成员入群
+    /// 新成员入群，包括机器人自己
     NewMember(NewMemberEvent),
+    /// 机器人加入群（包括自己创建的群），群列表和缓存已更新，之后同样会产生 NewMember
+    BotJoinGroup(NewMemberEvent),
     /// 成员被禁言
     GroupMute(GroupMuteEvent),
+    /// 自己被禁言，包括发送群消息时发现被禁言
+    BotMuted(BotMutedEvent),
+    /// 自己被解除禁言
+    BotUnmuted(BotUnmutedEvent),
     /// 好友消息撤回
     FriendMessageRecall(FriendMessageRecallEvent),
     /// 群消息撤回
     GroupMessageRecall(GroupMessageRecallEvent),
+    /// 自己撤回的群消息（包括调用撤回 API 后服务端的回声）
+    SelfGroupMessageRecall(GroupMessageRecallEvent),
     /// 新好友
     NewFriend(NewFriendEvent),
-    /// 退群/被踢
+    /// 退群/被踢，原因见 kind
     GroupLeave(GroupLeaveEvent),
+    /// 自己退出群、被移出群或群被解散（在 GroupDisband 之后）
+    BotLeaveGroup(GroupLeaveEvent),
     /// 群解散
     GroupDisband(GroupDisbandEvent),
     /// 好友戳一戳
     FriendPoke(FriendPokeEvent),
     /// 群成员戳一戳
     GroupPoke(GroupPokeEvent),
+    /// 群红包，同时也会产生 GroupMessage 事件
+    GroupRedPacket(GroupRedPacketEvent),
+    /// 好友转账，同时也会产生 FriendMessage 事件
+    FriendTransfer(FriendTransferEvent),
+    /// 群灰条提示（热聊、斗图等互动提示）
+    GroupGrayTip(GroupGrayTipEvent),
     /// 群名称修改
     GroupNameUpdate(GroupNameUpdateEvent),
-    /// 好友删除
+    /// 群名片变更
+    MemberCardChanged(MemberCardChangedEvent),
+    /// 好友删除（好友列表减少推送，包括自己删除和被对方删除）
     DeleteFriend(DeleteFriendEvent),
+    /// 好友在线状态变更（在线/离开/忙碌等）
+    FriendStatusChanged(FriendStatusChangedEvent),
     /// 群成员权限变更
     MemberPermissionChange(MemberPermissionChangeEvent),
-    /// 被其他客户端踢下线
+    /// 被其他客户端踢下线或服务端强制下线，原因见 kind
     /// 不能用于掉线重连，掉线重连以 start 返回为准
     KickedOffline(KickedOfflineEvent),
-    /// 服务端强制下线
-    /// 不能用于掉线重连，掉线重连以 start 返回为准
-    MSFOffline(MSFOfflineEvent),
+    /// 其他客户端上线/下线
+    OtherClientStatusChanged(OtherClientStatusChangedEvent),
     /// 网络原因/客户端主动掉线
     /// 可用于掉线重连
     ClientDisconnect(ClientDisconnect),
+    /// handler 处理事件时 panic，见 Config::handler_panic_strategy
+    HandlerError(HandlerError),
+    /// 操作因账号实名、等级限制失败，每种限制只通知一次
+    AccountCapabilityNotice(AccountCapabilityNotice),
+    /// 没有解析的推送包，见 Client::unhandled_packets
+    Unhandled(UnhandledPush),
+}
+
+impl QEvent {
+    /// 事件相关的群号，非群事件返回 None
+    pub fn group_code(&self) -> Option<i64> {
+        match self {
+            QEvent::GroupMessage(e) | QEvent::SelfGroupMessage(e) => Some(e.inner.group_code),
+            QEvent::GroupAudioMessage(e) => Some(e.inner.group_code),
+            QEvent::GroupTempMessage(e) => Some(e.inner.group_code),
+            QEvent::GroupRequest(e) => Some(e.inner.group_code),
+            QEvent::SelfInvited(e) => Some(e.inner.group_code),
+            QEvent::NewMember(e) | QEvent::BotJoinGroup(e) => Some(e.inner.group_code),
+            QEvent::GroupMute(e) => Some(e.inner.group_code),
+            QEvent::BotMuted(e) => Some(e.inner.group_code),
+            QEvent::BotUnmuted(e) => Some(e.inner.group_code),
+            QEvent::GroupMessageRecall(e) | QEvent::SelfGroupMessageRecall(e) => {
+                Some(e.inner.group_code)
+            }
+            QEvent::GroupLeave(e) | QEvent::BotLeaveGroup(e) => Some(e.inner.group_code),
+            QEvent::GroupDisband(e) => Some(e.inner.group_code),
+            QEvent::GroupGrayTip(e) => Some(e.inner.group_code),
+            QEvent::GroupRedPacket(e) => Some(e.inner.group_code),
+            QEvent::GroupNameUpdate(e) => Some(e.inner.group_code),
+            QEvent::MemberCardChanged(e) => Some(e.inner.group_code),
+            QEvent::MemberPermissionChange(e) => Some(e.inner.group_code),
+            _ => None,
+        }
+    }
+
+    /// 事件的发起用户（消息发送者、申请人等），没有明确发起用户时返回 None
+    pub fn user_uin(&self) -> Option<i64> {
+        match self {
+            QEvent::GroupMessage(e) | QEvent::SelfGroupMessage(e) => Some(e.inner.from_uin),
+            QEvent::GroupAudioMessage(e) => Some(e.inner.from_uin),
+            QEvent::FriendMessage(e) | QEvent::SelfFriendMessage(e) => Some(e.inner.from_uin),
+            QEvent::FriendAudioMessage(e) => Some(e.inner.from_uin),
+            QEvent::GroupTempMessage(e) => Some(e.inner.from_uin),
+            QEvent::TempSession(e) => Some(e.inner.from_uin),
+            QEvent::GroupRequest(e) => Some(e.inner.req_uin),
+            QEvent::SelfInvited(e) => Some(e.inner.invitor_uin),
+            QEvent::NewFriendRequest(e) => Some(e.inner.req_uin),
+            QEvent::NewMember(e) => Some(e.inner.member_uin),
+            QEvent::FriendMessageRecall(e) => Some(e.inner.friend_uin),
+            QEvent::GroupMessageRecall(e) => Some(e.inner.operator_uin),
+            QEvent::NewFriend(e) => Some(e.inner.uin),
+            QEvent::GroupLeave(e) => Some(e.inner.member_uin),
+            QEvent::BotLeaveGroup(e) => e.inner.operator_uin,
+            QEvent::FriendPoke(e) => Some(e.inner.sender),
+            QEvent::GroupPoke(e) => Some(e.inner.sender),
+            QEvent::GroupRedPacket(e) => Some(e.inner.sender_uin),
+            QEvent::FriendTransfer(e) => Some(e.inner.from_uin),
+            QEvent::DeleteFriend(e) => Some(e.inner.uin),
+            QEvent::MemberCardChanged(e) => Some(e.inner.uin),
+            QEvent::FriendStatusChanged(e) => Some(e.inner.uin),
+            QEvent::MemberPermissionChange(e) => Some(e.inner.member_uin),
+            QEvent::BotMuted(e) => e.inner.operator_uin,
+            QEvent::BotUnmuted(e) => Some(e.inner.operator_uin),
+            _ => None,
+        }
+    }
+
+    /// 事件类型名，与枚举成员名相同
+    pub fn name(&self) -> &'static str {
+        match self {
+            QEvent::Login(_) => "Login",
+            QEvent::CredentialsRefreshed(_) => "CredentialsRefreshed",
+            QEvent::GroupMessage(_) => "GroupMessage",
+            QEvent::SelfGroupMessage(_) => "SelfGroupMessage",
+            QEvent::GroupAudioMessage(_) => "GroupAudioMessage",
+            QEvent::FriendMessage(_) => "FriendMessage",
+            QEvent::SelfFriendMessage(_) => "SelfFriendMessage",
+            QEvent::FriendAudioMessage(_) => "FriendAudioMessage",
+            QEvent::GroupTempMessage(_) => "GroupTempMessage",
+            QEvent::TempSession(_) => "TempSession",
+            QEvent::GroupRequest(_) => "GroupRequest",
+            QEvent::SelfInvited(_) => "SelfInvited",
+            QEvent::NewFriendRequest(_) => "NewFriendRequest",
+            QEvent::NewMember(_) => "NewMember",
+            QEvent::BotJoinGroup(_) => "BotJoinGroup",
+            QEvent::GroupMute(_) => "GroupMute",
+            QEvent::BotMuted(_) => "BotMuted",
+            QEvent::BotUnmuted(_) => "BotUnmuted",
+            QEvent::FriendMessageRecall(_) => "FriendMessageRecall",
+            QEvent::GroupMessageRecall(_) => "GroupMessageRecall",
+            QEvent::SelfGroupMessageRecall(_) => "SelfGroupMessageRecall",
+            QEvent::NewFriend(_) => "NewFriend",
+            QEvent::GroupLeave(_) => "GroupLeave",
+            QEvent::BotLeaveGroup(_) => "BotLeaveGroup",
+            QEvent::GroupDisband(_) => "GroupDisband",
+            QEvent::FriendPoke(_) => "FriendPoke",
+            QEvent::GroupPoke(_) => "GroupPoke",
+            QEvent::GroupRedPacket(_) => "GroupRedPacket",
+            QEvent::FriendTransfer(_) => "FriendTransfer",
+            QEvent::GroupGrayTip(_) => "GroupGrayTip",
+            QEvent::GroupNameUpdate(_) => "GroupNameUpdate",
+            QEvent::MemberCardChanged(_) => "MemberCardChanged",
+            QEvent::DeleteFriend(_) => "DeleteFriend",
+            QEvent::FriendStatusChanged(_) => "FriendStatusChanged",
+            QEvent::MemberPermissionChange(_) => "MemberPermissionChange",
+            QEvent::KickedOffline(_) => "KickedOffline",
+            QEvent::OtherClientStatusChanged(_) => "OtherClientStatusChanged",
+            QEvent::ClientDisconnect(_) => "ClientDisconnect",
+            QEvent::HandlerError(_) => "HandlerError",
+            QEvent::AccountCapabilityNotice(_) => "AccountCapabilityNotice",
+            QEvent::Unhandled(_) => "Unhandled",
+        }
+    }
 }
 
 /// 处理外发数据的接口
@@ -131,6 +284,9 @@ impl Handler for DefaultHandler {
             QEvent::GroupTempMessage(m) => {
                 tracing::info!("MESSAGE (TEMP={}): {}", m.inner.from_uin, m.inner.elements)
             }
+            QEvent::TempSession(m) => {
+                tracing::info!("MESSAGE (TEMP={}): {}", m.inner.from_uin, m.inner.elements)
+            }
             QEvent::GroupRequest(m) => {
                 tracing::info!(
                     "REQUEST (GROUP={}, UIN={}): {}",
@@ -147,6 +303,77 @@ impl Handler for DefaultHandler {
     }
 }
 
+/// Client 内部使用，先把事件广播给 event_stream 的订阅者，再交给用户 handler
+///
+/// 用户 handler panic 时产生 HandlerError 事件，并按 SupervisionStrategy 处理
+pub(crate) struct StreamingHandler<H> {
+    pub(crate) inner: H,
+    pub(crate) sender: BroadcastSender<QEvent>,
+    pub(crate) strategy: SupervisionStrategy,
+    pub(crate) status: Arc<AtomicU8>,
+    pub(crate) disconnect_signal: BroadcastSender<()>,
+    pub(crate) recent: Arc<RecentBuffer>,
+}
+
+impl<H> StreamingHandler<H> {
+    fn supervise(&self) {
+        match self.strategy {
+            SupervisionStrategy::Ignore => {}
+            SupervisionStrategy::Restart => {
+                self.disconnect_signal.send(()).ok();
+            }
+            SupervisionStrategy::Stop => {
+                self.status
+                    .store(NetworkStatus::Stop as u8, Ordering::Relaxed);
+                self.disconnect_signal.send(()).ok();
+            }
+        }
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".into()
+    }
+}
+
+#[async_trait]
+impl<H> Handler for StreamingHandler<H>
+where
+    H: Handler + Send,
+{
+    async fn handle(&self, e: QEvent) {
+        if self.sender.receiver_count() > 0 {
+            self.sender.send(e.clone()).ok();
+        }
+        self.recent.record_event(&e);
+        let is_handler_error = matches!(e, QEvent::HandlerError(_));
+        let (event, group_code, user_uin) = (e.name(), e.group_code(), e.user_uin());
+        let Err(panic) = AssertUnwindSafe(self.inner.handle(e)).catch_unwind().await else {
+            return;
+        };
+        let message = panic_message(panic);
+        tracing::error!("handler panicked on {}: {}", event, message);
+        self.recent.dump_on_error();
+        // 处理 HandlerError 时再次 panic 不再上报，避免循环
+        if is_handler_error {
+            return;
+        }
+        self.handle(QEvent::HandlerError(HandlerError {
+            event,
+            group_code,
+            user_uin,
+            message,
+        }))
+        .await;
+        self.supervise();
+    }
+}
+
 #[async_trait]
 impl Handler for BroadcastSender<QEvent> {
     async fn handle(&self, msg: QEvent) {
@@ -178,29 +405,46 @@ impl Handler for WatchSender<QEvent> {
 #[async_trait]
 pub trait PartlyHandler: Sync {
     async fn handle_login(&self, _: i64) {}
+    async fn handle_credentials_refreshed(&self, _event: Token) {}
     async fn handle_group_message(&self, _event: GroupMessageEvent) {}
+    async fn handle_self_group_message(&self, _event: GroupMessageEvent) {}
     async fn handle_group_audio(&self, _event: GroupAudioMessageEvent) {}
     async fn handle_friend_message(&self, _event: FriendMessageEvent) {}
+    async fn handle_self_friend_message(&self, _event: FriendMessageEvent) {}
     async fn handle_friend_audio(&self, _event: FriendAudioMessageEvent) {}
     async fn handle_group_temp_message(&self, _event: GroupTempMessageEvent) {}
+    async fn handle_temp_session(&self, _event: TempSessionEvent) {}
     async fn handle_group_request(&self, _event: JoinGroupRequestEvent) {}
     async fn handle_self_invited(&self, _event: SelfInvitedEvent) {}
     async fn handle_friend_request(&self, _event: NewFriendRequestEvent) {}
     async fn handle_new_member(&self, _event: NewMemberEvent) {}
+    async fn handle_bot_join_group(&self, _event: NewMemberEvent) {}
     async fn handle_group_mute(&self, _event: GroupMuteEvent) {}
+    async fn handle_bot_muted(&self, _event: BotMutedEvent) {}
+    async fn handle_bot_unmuted(&self, _event: BotUnmutedEvent) {}
     async fn handle_friend_message_recall(&self, _event: FriendMessageRecallEvent) {}
     async fn handle_group_message_recall(&self, _event: GroupMessageRecallEvent) {}
+    async fn handle_self_group_message_recall(&self, _event: GroupMessageRecallEvent) {}
     async fn handle_new_friend(&self, _event: NewFriendEvent) {}
     async fn handle_group_leave(&self, _event: GroupLeaveEvent) {}
+    async fn handle_bot_leave_group(&self, _event: GroupLeaveEvent) {}
     async fn handle_group_disband(&self, _event: GroupDisbandEvent) {}
     async fn handle_friend_poke(&self, _event: FriendPokeEvent) {}
     async fn handle_group_poke(&self, _event: GroupPokeEvent) {}
+    async fn handle_group_red_packet(&self, _event: GroupRedPacketEvent) {}
+    async fn handle_friend_transfer(&self, _event: FriendTransferEvent) {}
+    async fn handle_group_gray_tip(&self, _event: GroupGrayTipEvent) {}
     async fn handle_group_name_update(&self, _event: GroupNameUpdateEvent) {}
+    async fn handle_member_card_changed(&self, _event: MemberCardChangedEvent) {}
     async fn handle_delete_friend(&self, _event: DeleteFriendEvent) {}
+    async fn handle_friend_status_changed(&self, _event: FriendStatusChangedEvent) {}
     async fn handle_member_permission_change(&self, _event: MemberPermissionChangeEvent) {}
     async fn handle_kicked_offline(&self, _event: KickedOfflineEvent) {}
-    async fn handle_msf_offline(&self, _event: MSFOfflineEvent) {}
+    async fn handle_other_client_status_changed(&self, _event: OtherClientStatusChangedEvent) {}
     async fn handle_client_disconnect(&self, _event: ClientDisconnect) {}
+    async fn handle_handler_error(&self, _event: HandlerError) {}
+    async fn handle_account_capability_notice(&self, _event: AccountCapabilityNotice) {}
+    async fn handle_unhandled(&self, _event: UnhandledPush) {}
 }
 
 #[async_trait]
@@ -211,29 +455,46 @@ where
     async fn handle(&self, event: QEvent) {
         match event {
             QEvent::Login(uin) => self.handle_login(uin).await,
+            QEvent::CredentialsRefreshed(m) => self.handle_credentials_refreshed(m).await,
             QEvent::GroupMessage(m) => self.handle_group_message(m).await,
+            QEvent::SelfGroupMessage(m) => self.handle_self_group_message(m).await,
             QEvent::GroupAudioMessage(m) => self.handle_group_audio(m).await,
             QEvent::FriendMessage(m) => self.handle_friend_message(m).await,
+            QEvent::SelfFriendMessage(m) => self.handle_self_friend_message(m).await,
             QEvent::FriendAudioMessage(m) => self.handle_friend_audio(m).await,
             QEvent::GroupTempMessage(m) => self.handle_group_temp_message(m).await,
+            QEvent::TempSession(m) => self.handle_temp_session(m).await,
             QEvent::GroupRequest(m) => self.handle_group_request(m).await,
             QEvent::SelfInvited(m) => self.handle_self_invited(m).await,
             QEvent::NewFriendRequest(m) => self.handle_friend_request(m).await,
             QEvent::NewMember(m) => self.handle_new_member(m).await,
+            QEvent::BotJoinGroup(m) => self.handle_bot_join_group(m).await,
             QEvent::GroupMute(m) => self.handle_group_mute(m).await,
+            QEvent::BotMuted(m) => self.handle_bot_muted(m).await,
+            QEvent::BotUnmuted(m) => self.handle_bot_unmuted(m).await,
             QEvent::FriendMessageRecall(m) => self.handle_friend_message_recall(m).await,
             QEvent::GroupMessageRecall(m) => self.handle_group_message_recall(m).await,
+            QEvent::SelfGroupMessageRecall(m) => self.handle_self_group_message_recall(m).await,
             QEvent::NewFriend(m) => self.handle_new_friend(m).await,
             QEvent::GroupLeave(m) => self.handle_group_leave(m).await,
+            QEvent::BotLeaveGroup(m) => self.handle_bot_leave_group(m).await,
             QEvent::GroupDisband(m) => self.handle_group_disband(m).await,
             QEvent::FriendPoke(m) => self.handle_friend_poke(m).await,
             QEvent::GroupPoke(m) => self.handle_group_poke(m).await,
+            QEvent::GroupRedPacket(m) => self.handle_group_red_packet(m).await,
+            QEvent::FriendTransfer(m) => self.handle_friend_transfer(m).await,
+            QEvent::GroupGrayTip(m) => self.handle_group_gray_tip(m).await,
             QEvent::GroupNameUpdate(m) => self.handle_group_name_update(m).await,
+            QEvent::MemberCardChanged(m) => self.handle_member_card_changed(m).await,
             QEvent::DeleteFriend(m) => self.handle_delete_friend(m).await,
+            QEvent::FriendStatusChanged(m) => self.handle_friend_status_changed(m).await,
             QEvent::MemberPermissionChange(m) => self.handle_member_permission_change(m).await,
             QEvent::KickedOffline(m) => self.handle_kicked_offline(m).await,
-            QEvent::MSFOffline(m) => self.handle_msf_offline(m).await,
+            QEvent::OtherClientStatusChanged(m) => self.handle_other_client_status_changed(m).await,
             QEvent::ClientDisconnect(m) => self.handle_client_disconnect(m).await,
+            QEvent::HandlerError(m) => self.handle_handler_error(m).await,
+            QEvent::AccountCapabilityNotice(m) => self.handle_account_capability_notice(m).await,
+            QEvent::Unhandled(m) => self.handle_unhandled(m).await,
         }
     }
 }
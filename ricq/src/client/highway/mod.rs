@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use bytes::Bytes;
 
 mod codec;
@@ -7,3 +9,25 @@ pub struct HighwayFrame {
     pub head: Bytes,
     pub body: Bytes,
 }
+
+/// highway 上传下载统计
+#[derive(Debug, Default)]
+pub struct HighwayStats {
+    /// 完成的上传次数
+    pub uploads: AtomicU64,
+    /// 发送的分片数
+    pub chunks: AtomicU64,
+    /// 发送的分片字节数
+    pub bytes: AtomicU64,
+    /// 完成的下载次数
+    pub downloads: AtomicU64,
+    /// 下载的字节数
+    pub downloaded_bytes: AtomicU64,
+}
+
+impl HighwayStats {
+    fn add_chunk(&self, len: usize) {
+        self.chunks.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(len as u64, Ordering::Relaxed);
+    }
+}
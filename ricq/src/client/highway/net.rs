@@ -1,18 +1,19 @@
 use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures_util::{SinkExt, StreamExt};
 use tokio::net::TcpStream;
 use tokio_util::codec::Framed;
 
 use ricq_core::command::common::PbToBytes;
 use ricq_core::crypto::qqtea_encrypt;
-use ricq_core::highway::BdhInput;
+use ricq_core::highway::{BdhDownloadInput, BdhInput};
 use ricq_core::{pb, RQError, RQResult};
 
 use crate::client::highway::codec::HighwayCodec;
-use crate::client::highway::HighwayFrame;
+use crate::client::highway::{HighwayFrame, HighwayStats};
 use crate::client::tcp::tcp_connect_timeout;
 use crate::Client;
 
@@ -45,8 +46,10 @@ impl Client {
         }
         let mut ticket = input.ticket;
         let mut rsp_ext = Bytes::new();
-        for (i, chunk) in input.body.chunks(input.chunk_size).enumerate() {
-            let chunk = chunk.to_vec();
+        // 分片直接引用同一块内存，避免每个分片重新分配和复制
+        let body = Bytes::from(input.body);
+        for (i, offset) in (0..length).step_by(input.chunk_size).enumerate() {
+            let chunk = body.slice(offset..length.min(offset + input.chunk_size));
             let head = pb::ReqDataHighwayHead {
                 msg_basehead: Some(self.highway_session.read().await.build_basehead(
                     "PicUp.DataUp".into(),
@@ -64,12 +67,14 @@ impl Client {
                 req_extendinfo: input.ext.clone(),
                 ..Default::default()
             };
+            let chunk_len = chunk.len();
             stream
                 .send(HighwayFrame {
                     head: head.to_bytes(),
-                    body: Bytes::from(chunk.clone()),
+                    body: chunk,
                 })
                 .await?;
+            self.highway_stats.add_chunk(chunk_len);
             let resp = read_response(&mut stream).await?;
             let rsp_head = self
                 .highway_session
@@ -92,8 +97,97 @@ impl Client {
             }
         }
 
+        self.highway_stats.uploads.fetch_add(1, Ordering::Relaxed);
         Ok(rsp_ext)
     }
+
+    /// 从 BDH 下载资源（如长消息、部分文件），按 chunk_size 分段请求，返回完整数据
+    ///
+    /// 服务端返回了文件 md5 时会校验下载结果
+    pub async fn highway_download_bdh(
+        &self,
+        addr: SocketAddr,
+        mut input: BdhDownloadInput,
+    ) -> RQResult<Bytes> {
+        if input.encrypt {
+            let session_key = self.highway_session.read().await.session_key.clone();
+            input.ext = qqtea_encrypt(&input.ext, &session_key)
+        }
+        let stream = tcp_connect_timeout(addr, Duration::from_secs(5))
+            .await
+            .map_err(RQError::IO)?;
+        let mut stream = Framed::new(stream, HighwayCodec);
+        let mut data = BytesMut::new();
+        let mut file_md5 = Vec::new();
+        loop {
+            let offset = data.len() as i64;
+            let head = pb::ReqDataHighwayHead {
+                msg_basehead: Some(self.highway_session.read().await.build_basehead(
+                    "PicDown.DataDown".into(),
+                    4096,
+                    input.command_id,
+                    2052,
+                )),
+                msg_seghead: Some(pb::SegHead {
+                    dataoffset: offset,
+                    datalength: input.chunk_size as i32,
+                    serviceticket: input.ticket.clone(),
+                    ..Default::default()
+                }),
+                req_extendinfo: input.ext.clone(),
+                ..Default::default()
+            };
+            stream
+                .send(HighwayFrame {
+                    head: head.to_bytes(),
+                    body: Bytes::new(),
+                })
+                .await?;
+            let resp = read_response(&mut stream).await?;
+            let rsp_head = self
+                .highway_session
+                .read()
+                .await
+                .decode_rsp_head(resp.head)?;
+            if rsp_head.error_code != 0 {
+                return Err(RQError::Other(format!(
+                    "error_code = {}",
+                    rsp_head.error_code
+                )));
+            }
+            let seg = rsp_head.msg_seghead.unwrap_or_default();
+            if seg.dataoffset != offset {
+                return Err(RQError::Decode(format!(
+                    "unexpected data offset {}, expected {}",
+                    seg.dataoffset, offset
+                )));
+            }
+            if !seg.file_md5.is_empty() {
+                file_md5 = seg.file_md5;
+            }
+            data.extend_from_slice(&resp.body);
+            if data.len() as i64 >= seg.filesize {
+                break;
+            }
+            // 没有新数据时继续请求会死循环
+            if resp.body.is_empty() {
+                return Err(RQError::EmptyField("highway data"));
+            }
+        }
+        if !file_md5.is_empty() && md5::compute(&data).0 != *file_md5 {
+            return Err(RQError::Decode("highway data md5 mismatch".into()));
+        }
+        self.highway_stats.downloads.fetch_add(1, Ordering::Relaxed);
+        self.highway_stats
+            .downloaded_bytes
+            .fetch_add(data.len() as u64, Ordering::Relaxed);
+        Ok(data.freeze())
+    }
+
+    /// highway 上传下载统计
+    pub fn highway_stats(&self) -> &HighwayStats {
+        &self.highway_stats
+    }
 }
 
 async fn read_response(stream: &mut Framed<TcpStream, HighwayCodec>) -> RQResult<HighwayFrame> {
@@ -103,3 +197,69 @@ async fn read_response(stream: &mut Framed<TcpStream, HighwayCodec>) -> RQResult
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use tokio::net::TcpListener;
+
+    use ricq_core::protocol::device::Device;
+    use ricq_core::protocol::version::{get_version, Protocol};
+
+    use super::*;
+    use crate::handler::DefaultHandler;
+
+    #[tokio::test]
+    async fn test_highway_download_bdh() {
+        let data: Vec<u8> = (0..10).collect();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn({
+            let data = data.clone();
+            async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut stream = Framed::new(stream, HighwayCodec);
+                while let Some(Ok(req)) = stream.next().await {
+                    let head = pb::ReqDataHighwayHead::decode(&*req.head).unwrap();
+                    assert_eq!(head.msg_basehead.unwrap().command, "PicDown.DataDown");
+                    let seg = head.msg_seghead.unwrap();
+                    let offset = seg.dataoffset as usize;
+                    let end = data.len().min(offset + seg.datalength as usize);
+                    let rsp = pb::RspDataHighwayHead {
+                        msg_seghead: Some(pb::SegHead {
+                            filesize: data.len() as i64,
+                            dataoffset: seg.dataoffset,
+                            file_md5: md5::compute(&data).to_vec(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    };
+                    stream
+                        .send(HighwayFrame {
+                            head: rsp.to_bytes(),
+                            body: Bytes::copy_from_slice(&data[offset..end]),
+                        })
+                        .await
+                        .unwrap();
+                }
+            }
+        });
+
+        let client = Client::new(
+            Device::random(),
+            get_version(Protocol::IPad),
+            DefaultHandler,
+        );
+        let input = BdhDownloadInput {
+            chunk_size: 4,
+            ..Default::default()
+        };
+        let downloaded = client.highway_download_bdh(addr, input).await.unwrap();
+        assert_eq!(downloaded, data);
+        let stats = client.highway_stats();
+        assert_eq!(stats.downloads.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.downloaded_bytes.load(Ordering::Relaxed), 10);
+        drop(client);
+        server.await.unwrap();
+    }
+}
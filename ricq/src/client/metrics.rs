@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+/// 运行指标回调，可以接入 Prometheus 等监控系统，通过 `Config::metrics` 设置
+///
+/// 所有方法都在收发包的路径上同步调用，实现时不要阻塞
+pub trait Metrics: Send + Sync {
+    /// 发出一个包
+    fn packet_sent(&self, _command: &str) {}
+
+    /// 收到一个包
+    fn packet_received(&self, _command: &str) {}
+
+    /// send_and_wait 单次请求的耗时，超时或出错时 success 为 false
+    fn request_latency(&self, _command: &str, _latency: Duration, _success: bool) {}
+
+    /// auto_reconnect 重连成功
+    fn reconnected(&self) {}
+}
+
+impl super::Client {
+    pub(crate) fn record_reconnect(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.reconnected();
+        }
+    }
+}
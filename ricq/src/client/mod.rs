@@ -1,52 +1,91 @@
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU8, Ordering};
-use std::time::UNIX_EPOCH;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, UNIX_EPOCH};
 
+use bytes::Bytes;
 use cached::Cached;
 use tokio::sync::{broadcast, RwLock};
 use tokio::sync::{oneshot, Mutex};
 use tokio::time::{sleep, Duration};
 
-pub use net::{Connector, DefaultConnector};
+pub use diagnose::{AddrProbe, CacheSizes, DiagnoseReport};
+pub use highway::HighwayStats;
+pub use net::{framed, Connection, Connector, DefaultConnector};
+pub use quota::{QuotaStatus, DAILY_AT_ALL_LIMIT, DAILY_FREE_GIFT_LIMIT, DAILY_LIKE_LIMIT};
 use ricq_core::command::online_push::GroupMessagePart;
 use ricq_core::command::profile_service::GroupSystemMessages;
 use ricq_core::common::RQAddr;
-use ricq_core::protocol::version::Version;
-use ricq_core::protocol::{device::Device, packet::Packet};
-use ricq_core::structs::{AccountInfo, AddressInfo, OtherClientInfo};
+use ricq_core::msg::MessageChain;
+use ricq_core::protocol::device::Device;
+use ricq_core::protocol::packet::{EncryptType, Packet};
+use ricq_core::protocol::version::{get_version, Protocol, Version};
+use ricq_core::structs::{
+    AccountInfo, AccountRestriction, AddressInfo, GroupInfo, OtherClientInfo,
+};
 use ricq_core::Engine;
 pub use ricq_core::Token;
+pub use stats::{ActivityStats, MessageStats};
 
+use crate::i18n::Locale;
 use crate::{RQError, RQResult};
 
 mod api;
+mod cache;
+mod diagnose;
 pub mod event;
 pub mod handler;
 mod highway;
+pub mod metrics;
 pub(crate) mod net;
+pub mod observer;
 mod processor;
+mod quota;
+mod ratelimit;
+mod recall;
+mod recent;
+mod reload;
+pub mod sign;
+mod stats;
+pub mod store;
 mod tcp;
 
+/// 群消息去重 <(group_code, seq, rand, time)>
+type GroupMessageDedup = cached::TimedCache<(i64, i32, i32, i32), ()>;
+
 pub struct Client {
     /// QEvent Handler 调用 handle 方法外发 QEvent
     handler: Box<dyn handler::Handler + Sync + Send + 'static>,
+    /// event_stream 使用的事件广播
+    event_sender: broadcast::Sender<handler::QEvent>,
     pub engine: RwLock<Engine>,
+    /// 与 engine.uin 共享，读取时不需要获取 engine 的锁
+    uin: Arc<AtomicI64>,
 
     // 状态相关
-    /// 网络状态
-    status: AtomicU8,
+    /// 网络状态，handler panic 时 StreamingHandler 也会修改
+    status: Arc<AtomicU8>,
     /// 停止网络信号 Sender
     disconnect_signal: broadcast::Sender<()>,
     /// 是否在线
     pub online: AtomicBool,
     /// 心跳包是否已启用
     pub heartbeat_enabled: AtomicBool,
+    /// 定时换 token 是否已启用
+    pub token_refresh_enabled: AtomicBool,
+    /// 是否隐身，注册客户端时使用
+    invisible: AtomicBool,
+    /// 好友申请自动处理策略
+    friend_request_policy: crate::FriendRequestPolicy,
+    /// 群邀请自动处理策略
+    group_invite_policy: crate::GroupInvitePolicy,
 
     // 包相关
     /// 外发包 Sender
     out_pkt_sender: net::OutPktSender,
-    /// send_and_wait WaitMap
-    packet_promises: RwLock<HashMap<i32, oneshot::Sender<Packet>>>,
+    /// send_and_wait WaitMap，每次收发包都会访问，只在同步代码中短暂持有
+    packet_promises: std::sync::Mutex<HashMap<i32, oneshot::Sender<Packet>>>,
     /// 当前客户端发送消息后使用 cache 避免上报自身消息事件
     receipt_waiters: Mutex<cached::TimedCache<i32, oneshot::Sender<i32>>>,
 
@@ -60,6 +99,8 @@ pub struct Client {
 
     // statics
     pub last_message_time: AtomicI64,
+    /// 服务器时间减本地时间（秒），收到群消息时更新，i64::MIN 表示未知
+    clock_skew: AtomicI64,
     /// 调用 new 方法时的时间戳
     pub start_time: i32,
 
@@ -69,12 +110,75 @@ pub struct Client {
     c2c_cache: RwLock<cached::TimedCache<(i64, i64, i32, i64), ()>>,
     push_req_cache: RwLock<cached::TimedCache<(i16, i64), ()>>,
     push_trans_cache: RwLock<cached::TimedCache<(i32, i64), ()>>,
+    /// 群消息去重，None 表示不去重
+    group_message_dedup: Option<RwLock<GroupMessageDedup>>,
     group_sys_message_cache: RwLock<GroupSystemMessages>,
+    /// 最近的群消息 <(group_code, seq), chain>，撤回事件用于附带原消息
+    group_message_store: RwLock<cached::TimedSizedCache<(i64, i32), MessageChain>>,
+
+    /// 群列表 <group_code, info>，由 reload_group_list 维护
+    group_list: RwLock<HashMap<i64, GroupInfo>>,
+    /// 群信息最后一次从服务器拉取的时间戳（秒） <group_code, time>
+    group_info_updated: std::sync::Mutex<HashMap<i64, i64>>,
+    /// 好友、群、群成员信息缓存，由 get_friend / get_group / get_member 使用
+    info_cache: Mutex<cache::InfoCache>,
+    /// 上传过的图片 <md5, 图片信息>
+    image_cache: Mutex<cache::ImageCache>,
+    /// 上传过的外链图片 <url, 图片信息>，用于跳过重复下载
+    #[cfg(feature = "web")]
+    url_images: Mutex<cached::SizedCache<String, crate::structs::ImageInfo>>,
 
     pub highway_session: RwLock<ricq_core::highway::Session>,
     pub highway_addrs: RwLock<Vec<RQAddr>>,
+    highway_stats: highway::HighwayStats,
 
     packet_handler: RwLock<HashMap<String, broadcast::Sender<Packet>>>,
+    /// 点赞、@全体成员 等每日配额的本地记录
+    quota: Mutex<QuotaStatus>,
+    /// 最近的事件和收发包
+    recent: Arc<recent::RecentBuffer>,
+    /// crate 生成文本使用的语言
+    locale: Locale,
+    /// 外部签名服务
+    sign_provider: Option<Arc<dyn sign::SignProvider>>,
+    /// 等待中的延时撤回
+    pending_recalls: Mutex<Vec<recall::PendingRecall>>,
+    /// 延时撤回持久化文件
+    pending_recall_path: Option<PathBuf>,
+    /// 最近发送的消息 <rand, 接收方>，None 表示不记录
+    sent_messages: Option<Mutex<cached::SizedCache<i32, recall::SentTarget>>>,
+    /// 心跳配置，可以运行中修改
+    heartbeat_config: std::sync::RwLock<crate::HeartbeatConfig>,
+    /// 定时换 token 的间隔
+    token_refresh_interval: Option<Duration>,
+    /// send_and_wait 的超时和重试配置，可以运行中修改
+    request_config: std::sync::RwLock<crate::RequestConfig>,
+    /// 发包、发消息限流
+    rate_limiter: ratelimit::RateLimiter,
+    /// 已经通知过的账号限制
+    notified_restrictions: Mutex<HashSet<AccountRestriction>>,
+    /// 自己被禁言的群，避免发送失败时重复产生 BotMuted 事件
+    muted_groups: Mutex<HashSet<i64>>,
+    /// start 时记录的自身引用，用于在 &self 方法中产生带 client 的事件
+    weak_self: std::sync::OnceLock<std::sync::Weak<Client>>,
+    /// 运行指标回调
+    metrics: Option<Arc<dyn metrics::Metrics>>,
+    /// 收发包回调
+    packet_observer: Option<Arc<dyn observer::PacketObserver>>,
+    /// 会话数据持久化
+    store: Option<Arc<dyn store::Store>>,
+    /// 最后写入 store 的 (sync_cookie, pub_account_cookie)，没有变化时不重复写入
+    persisted_sync_cookies: std::sync::Mutex<(Bytes, Bytes)>,
+    /// 需要处理的推送类型，可以运行中修改
+    event_mask: std::sync::RwLock<crate::EventMask>,
+    /// 因 event_mask 跳过或无法识别的包数量
+    ignored_packets: AtomicU64,
+    /// 没有解析的推送包数量 <command, count>
+    unhandled_packets: std::sync::Mutex<HashMap<String, u64>>,
+    /// QEvent::Unhandled 是否附带包内容
+    report_unhandled_body: bool,
+    /// 消息收发统计，None 表示不统计
+    stats: Option<std::sync::Mutex<stats::MessageStats>>,
 }
 
 impl super::Client {
@@ -82,17 +186,54 @@ impl super::Client {
     ///
     /// **Notice: 该方法仅新建 Client 需要调用 start 方法连接到服务器**
     pub fn new<H>(device: Device, version: Version, handler: H) -> Client
+    where
+        H: crate::client::handler::Handler + 'static + Sync + Send,
+    {
+        Self::new_with_config(crate::Config::new(device, version), handler)
+    }
+
+    /// 使用内置协议版本新建 Client
+    ///
+    /// **Notice: 该方法仅新建 Client 需要调用 start 方法连接到服务器**
+    pub fn with_protocol<H>(device: Device, protocol: Protocol, handler: H) -> Client
+    where
+        H: crate::client::handler::Handler + 'static + Sync + Send,
+    {
+        Self::new(device, get_version(protocol), handler)
+    }
+
+    /// 新建 Clinet
+    ///
+    /// **Notice: 该方法仅新建 Client 需要调用 start 方法连接到服务器**
+    pub fn new_with_config<H>(config: crate::Config, handler: H) -> Self
     where
         H: crate::client::handler::Handler + 'static + Sync + Send,
     {
         let (out_pkt_sender, _) = tokio::sync::broadcast::channel(1024);
         let (disconnect_signal, _) = tokio::sync::broadcast::channel(8);
+        let (event_sender, _) = tokio::sync::broadcast::channel(1024);
+        let engine = Engine::new(config.device, config.version);
+        let status = Arc::new(AtomicU8::new(NetworkStatus::Unknown as u8));
+        let recent = Arc::new(recent::RecentBuffer::new(&config.recent_buffer));
 
         Client {
-            handler: Box::new(handler),
-            engine: RwLock::new(Engine::new(device, version)),
-            status: AtomicU8::new(NetworkStatus::Unknown as u8),
+            handler: Box::new(handler::StreamingHandler {
+                inner: handler,
+                sender: event_sender.clone(),
+                strategy: config.handler_panic_strategy,
+                status: status.clone(),
+                disconnect_signal: disconnect_signal.clone(),
+                recent: recent.clone(),
+            }),
+            event_sender,
+            uin: engine.uin.clone(),
+            engine: RwLock::new(engine),
+            status,
             heartbeat_enabled: AtomicBool::new(false),
+            token_refresh_enabled: AtomicBool::new(false),
+            invisible: AtomicBool::new(config.login_invisible),
+            friend_request_policy: config.friend_request_policy,
+            group_invite_policy: config.group_invite_policy,
             online: AtomicBool::new(false),
             out_pkt_sender,
             disconnect_signal,
@@ -103,64 +244,202 @@ impl super::Client {
             address: Default::default(),
             online_clients: Default::default(),
             last_message_time: Default::default(),
+            clock_skew: AtomicI64::new(i64::MIN),
             start_time: UNIX_EPOCH.elapsed().unwrap().as_secs() as i32,
             group_message_builder: RwLock::new(cached::TimedCache::with_lifespan(600)),
             c2c_cache: RwLock::new(cached::TimedCache::with_lifespan(3600)),
             push_req_cache: RwLock::new(cached::TimedCache::with_lifespan(30)),
             push_trans_cache: RwLock::new(cached::TimedCache::with_lifespan(15)),
+            group_message_dedup: (config.message_dedup_window > 0).then(|| {
+                RwLock::new(cached::TimedCache::with_lifespan(
+                    config.message_dedup_window,
+                ))
+            }),
             group_sys_message_cache: RwLock::new(Default::default()),
+            group_message_store: RwLock::new(cached::TimedSizedCache::with_size_and_lifespan(
+                4096, 3600,
+            )),
+            group_list: Default::default(),
+            group_info_updated: Default::default(),
+            info_cache: Mutex::new(cache::InfoCache::new(&config.cache)),
+            image_cache: Mutex::new(cache::ImageCache::load(
+                config.image_cache_path,
+                config.cache.capacity,
+            )),
+            #[cfg(feature = "web")]
+            url_images: Mutex::new(cached::SizedCache::with_size(1024)),
             highway_session: RwLock::new(Default::default()),
             highway_addrs: RwLock::new(Default::default()),
+            highway_stats: Default::default(),
             packet_handler: Default::default(),
+            quota: Default::default(),
+            recent,
+            locale: config.locale,
+            sign_provider: config.sign_provider,
+            pending_recalls: Default::default(),
+            pending_recall_path: config.pending_recall_path,
+            sent_messages: (config.sent_message_capacity > 0)
+                .then(|| Mutex::new(cached::SizedCache::with_size(config.sent_message_capacity))),
+            heartbeat_config: std::sync::RwLock::new(config.heartbeat),
+            token_refresh_interval: config.token_refresh_interval,
+            request_config: std::sync::RwLock::new(config.request),
+            rate_limiter: ratelimit::RateLimiter::new(config.rate_limit),
+            notified_restrictions: Default::default(),
+            muted_groups: Default::default(),
+            weak_self: Default::default(),
+            metrics: config.metrics,
+            packet_observer: config.packet_observer,
+            store: config.store,
+            persisted_sync_cookies: Default::default(),
+            event_mask: std::sync::RwLock::new(config.event_mask),
+            ignored_packets: Default::default(),
+            unhandled_packets: Default::default(),
+            report_unhandled_body: config.report_unhandled_body,
+            stats: config
+                .enable_stats
+                .then(|| std::sync::Mutex::new(stats::MessageStats::new())),
         }
     }
 
-    /// 新建 Clinet
+    /// 关闭客户端：断开连接，停止心跳，并使所有等待中的 send_and_wait 返回 `Err(RQError::ClientStopped)`
     ///
-    /// **Notice: 该方法仅新建 Client 需要调用 start 方法连接到服务器**
-    pub fn new_with_config<H>(config: crate::Config, handler: H) -> Self
-    where
-        H: crate::client::handler::Handler + 'static + Sync + Send,
-    {
-        Self::new(config.device, config.version, handler)
+    /// 调用后 start 方法会返回，可以安全地丢弃 Client
+    pub async fn close(&self) {
+        self.stop(NetworkStatus::Stop);
+        self.packet_promises.lock().unwrap().clear();
+    }
+
+    /// 优雅退出：在线时先发送注销包让账号立即下线，再 close
+    pub async fn shutdown(&self) {
+        if self.online.load(Ordering::Relaxed) {
+            let req = self.engine.read().await.build_client_unregister_packet();
+            match tokio::time::timeout(Duration::from_secs(3), self.send_and_wait(req)).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => tracing::warn!("failed to unregister client: {}", err),
+                Err(_) => tracing::warn!("unregister client timeout"),
+            }
+        }
+        self.close().await;
+    }
+
+    /// 当前使用的协议
+    pub async fn protocol(&self) -> Protocol {
+        self.engine.read().await.transport.version.protocol
+    }
+
+    /// 切换协议版本，需要在登录前调用，已登录时新版本在重新登录后生效
+    pub async fn set_version(&self, version: Version) {
+        self.engine.write().await.transport.version = version;
+    }
+
+    /// crate 生成文本使用的语言
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// 按客户端语言生成错误信息
+    pub fn error_message(&self, err: &RQError) -> String {
+        self.locale.error_message(err)
     }
 
     /// 获取当前 Client uin
     pub async fn uin(&self) -> i64 {
-        self.engine.read().await.uin.load(Ordering::Relaxed)
+        self.uin.load(Ordering::Relaxed)
     }
 
     /// 向服务器发包
+    #[tracing::instrument(skip_all, fields(command = %pkt.command_name, seq = pkt.seq_id))]
     pub async fn send(&self, pkt: Packet) -> RQResult<usize> {
         tracing::trace!("sending pkt {}-{},", pkt.command_name, pkt.seq_id);
-        let data = self.engine.read().await.transport.encode_packet(pkt);
-        self.out_pkt_sender
-            .send(data)
-            .map_err(|_| RQError::Other("failed to send out_pkt".into()))
+        self.rate_limiter.acquire_packet(&pkt.command_name).await?;
+        self.recent.record_packet(&pkt, true);
+        if let Some(metrics) = &self.metrics {
+            metrics.packet_sent(&pkt.command_name);
+        }
+        let pkt = self.sign_packet(pkt).await;
+        self.observe_packet(observer::PacketDirection::Outbound, &pkt);
+        let data = self.engine.read().await.encode_packet(pkt);
+        self.out_pkt_sender.send(data).map_err(|_| RQError::Network)
     }
 
-    /// 向服务器发包并等待接收返回的包，15 秒后超时返回 `Err(RQError::Timeout)`
+    /// 向服务器发包并等待接收返回的包，超时和重试按 `Config::request` 设置，超时返回 `Err(RQError::Timeout)`
     pub async fn send_and_wait(&self, pkt: Packet) -> RQResult<Packet> {
+        self.send_and_wait_with(pkt, self.request_config()).await
+    }
+
+    /// 同 send_and_wait，使用指定的超时和重试配置
+    pub async fn send_and_wait_with(
+        &self,
+        pkt: Packet,
+        config: crate::RequestConfig,
+    ) -> RQResult<Packet> {
+        let mut backoff = config.backoff;
+        for _ in 0..config.retries {
+            match self.send_and_wait_once(pkt.clone(), config.timeout).await {
+                Err(RQError::Timeout { command, seq }) => {
+                    tracing::debug!("retry {}-{} after {:?}", command, seq, backoff);
+                    sleep(backoff).await;
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+        self.send_and_wait_once(pkt, config.timeout).await
+    }
+
+    #[tracing::instrument(skip_all, fields(command = %pkt.command_name, seq = pkt.seq_id))]
+    async fn send_and_wait_once(&self, pkt: Packet, timeout: Duration) -> RQResult<Packet> {
+        let Some(metrics) = self.metrics.clone() else {
+            return self.send_and_wait_inner(pkt, timeout).await;
+        };
+        let command = pkt.command_name.clone();
+        let start = Instant::now();
+        let result = self.send_and_wait_inner(pkt, timeout).await;
+        metrics.request_latency(&command, start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn send_and_wait_inner(&self, pkt: Packet, timeout: Duration) -> RQResult<Packet> {
         tracing::trace!("send_and_waitting pkt {}-{},", pkt.command_name, pkt.seq_id);
+        if pkt.encrypt_type == EncryptType::D2Key
+            && self.engine.read().await.transport.sig.d2key.is_empty()
+        {
+            return Err(RQError::NeedLogin);
+        }
+        self.rate_limiter.acquire_packet(&pkt.command_name).await?;
+        self.recent.record_packet(&pkt, true);
+        if let Some(metrics) = &self.metrics {
+            metrics.packet_sent(&pkt.command_name);
+        }
         let seq = pkt.seq_id;
         let expect = pkt.command_name.clone();
-        let data = self.engine.read().await.transport.encode_packet(pkt);
+        let pkt = self.sign_packet(pkt).await;
+        self.observe_packet(observer::PacketDirection::Outbound, &pkt);
+        let data = self.engine.read().await.encode_packet(pkt);
         let (sender, receiver) = oneshot::channel();
-        {
-            let mut packet_promises = self.packet_promises.write().await;
-            packet_promises.insert(seq, sender);
-        }
+        self.packet_promises.lock().unwrap().insert(seq, sender);
         if self.out_pkt_sender.send(data).is_err() {
-            let mut packet_promises = self.packet_promises.write().await;
-            packet_promises.remove(&seq);
+            self.packet_promises.lock().unwrap().remove(&seq);
             return Err(RQError::Network);
         }
-        match tokio::time::timeout(std::time::Duration::from_secs(15), receiver).await {
-            Ok(p) => p.unwrap().check_command_name(&expect),
+        match tokio::time::timeout(timeout, receiver).await {
+            // sender 被丢弃说明客户端已关闭
+            Ok(p) => p
+                .map_err(|_| {
+                    if self.get_status() == NetworkStatus::Stop as u8 {
+                        RQError::ClientStopped
+                    } else {
+                        RQError::Network
+                    }
+                })?
+                .check_command_name(&expect),
             Err(_) => {
                 tracing::trace!("waiting pkt {}-{} timeout", expect, seq);
-                self.packet_promises.write().await.remove(&seq);
-                Err(RQError::Timeout)
+                self.packet_promises.lock().unwrap().remove(&seq);
+                Err(RQError::Timeout {
+                    command: expect,
+                    seq,
+                })
             }
         }
     }
@@ -170,10 +449,17 @@ impl super::Client {
     /// 该方法会阻塞当前协程，通常 spawn 使用
     pub async fn do_heartbeat(&self) {
         self.heartbeat_enabled.store(true, Ordering::SeqCst);
+        let mut disconnect_signal = self.disconnect_signal.subscribe();
         let mut times = 0;
+        let mut missed = 0;
         while self.online.load(Ordering::SeqCst) {
-            sleep(Duration::from_secs(30)).await;
+            let config = self.heartbeat_config();
+            tokio::select! {
+                _ = sleep(config.interval) => {}
+                _ = disconnect_signal.recv() => break,
+            }
             if self.heartbeat().await.is_ok() {
+                missed = 0;
                 times += 1;
                 if times >= 7 {
                     if self.register_client().await.is_err() {
@@ -181,11 +467,40 @@ impl super::Client {
                     }
                     times = 0;
                 }
+            } else {
+                missed += 1;
+                if config.max_missed > 0 && missed >= config.max_missed {
+                    // 连接半死不活，断开后 start 返回，按网络掉线处理
+                    tracing::warn!("{} heartbeats missed, disconnecting", missed);
+                    self.disconnect();
+                    break;
+                }
             }
         }
         self.heartbeat_enabled.store(false, Ordering::SeqCst);
     }
 
+    /// 按 `Config::token_refresh_interval` 定时换 token，未配置时直接返回
+    ///
+    /// 该方法会阻塞当前协程，通常 spawn 使用
+    pub async fn do_token_refresh(&self) {
+        let Some(interval) = self.token_refresh_interval else {
+            return;
+        };
+        self.token_refresh_enabled.store(true, Ordering::SeqCst);
+        let mut disconnect_signal = self.disconnect_signal.subscribe();
+        while self.online.load(Ordering::SeqCst) {
+            tokio::select! {
+                _ = sleep(interval) => {}
+                _ = disconnect_signal.recv() => break,
+            }
+            if let Err(err) = self.refresh_token().await {
+                tracing::warn!("failed to refresh token: {}", err);
+            }
+        }
+        self.token_refresh_enabled.store(false, Ordering::SeqCst);
+    }
+
     /// 生成 token
     pub async fn gen_token(&self) -> Token {
         self.engine.read().await.gen_token()
@@ -208,6 +523,30 @@ impl super::Client {
         self.highway_session.read().await.session_key.to_vec()
     }
 
+    /// 今日配额使用情况
+    pub async fn quota_status(&self) -> QuotaStatus {
+        let mut quota = self.quota.lock().await;
+        quota.refresh();
+        quota.clone()
+    }
+
+    /// 订阅所有事件，可以代替 Handler 使用，也可以与 Handler 同时使用
+    ///
+    /// 订阅之前产生的事件不会收到；处理过慢时会收到 `RecvError::Lagged`
+    pub fn event_stream(&self) -> broadcast::Receiver<handler::QEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// 因 `Config::event_mask` 跳过或无法识别的推送包数量
+    pub fn ignored_packets(&self) -> u64 {
+        self.ignored_packets.load(Ordering::Relaxed)
+    }
+
+    /// 没有解析的推送包按 command 统计的数量，可用于发现缺少支持的推送
+    pub fn unhandled_packets(&self) -> HashMap<String, u64> {
+        self.unhandled_packets.lock().unwrap().clone()
+    }
+
     /// 监听指定 command 数据包
     pub async fn listen_command<S: ToString>(&self, command: S) -> broadcast::Receiver<Packet> {
         self.packet_handler
@@ -216,11 +555,21 @@ impl super::Client {
             .cache_get_or_set_with(command.to_string(), || broadcast::channel(10).0)
             .subscribe()
     }
+
+    /// 发送任意命令并等待返回，body 为未加密的请求内容，返回未加密的响应内容
+    ///
+    /// 用于在 crate 外实现尚未支持的协议，使用当前会话的加密、签名、限流和超时设置
+    pub async fn send_raw_command(&self, command_name: &str, body: Bytes) -> RQResult<Bytes> {
+        let req = self.engine.read().await.uni_packet(command_name, body);
+        let resp = self.send_and_wait(req).await?;
+        Ok(resp.body)
+    }
 }
 
 impl Drop for Client {
     fn drop(&mut self) {
         self.stop(NetworkStatus::Drop);
+        self.packet_promises.get_mut().unwrap().clear();
     }
 }
 
@@ -242,3 +591,71 @@ pub enum NetworkStatus {
     // 服务端强制下线
     MsfOffline = 6,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::{DefaultHandler, QEvent};
+
+    #[tokio::test]
+    async fn close_stops_background_tasks() {
+        let client = Arc::new(Client::new(
+            Device::random(),
+            get_version(Protocol::IPad),
+            DefaultHandler,
+        ));
+        client.online.store(true, Ordering::SeqCst);
+        let heartbeat = tokio::spawn({
+            let client = client.clone();
+            async move { client.do_heartbeat().await }
+        });
+        // 保持 out_pkt_sender 有接收者，让请求停在等待响应
+        let _out = client.out_pkt_sender.subscribe();
+        let waiting = tokio::spawn({
+            let client = client.clone();
+            async move {
+                let pkt = client.engine.read().await.build_heartbeat_packet();
+                client.send_and_wait(pkt).await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        client.close().await;
+        let timeout = Duration::from_secs(1);
+        tokio::time::timeout(timeout, heartbeat)
+            .await
+            .expect("heartbeat task leaked")
+            .unwrap();
+        let result = tokio::time::timeout(timeout, waiting)
+            .await
+            .expect("send_and_wait leaked")
+            .unwrap();
+        assert!(matches!(result, Err(RQError::ClientStopped)));
+        assert!(!client.heartbeat_enabled.load(Ordering::SeqCst));
+        assert_eq!(Arc::strong_count(&client), 1);
+    }
+
+    #[tokio::test]
+    async fn handler_panic_is_supervised() {
+        async fn on_event(e: QEvent) {
+            if let QEvent::Login(_) = e {
+                panic!("boom");
+            }
+        }
+        let mut config = crate::Config::new(Device::random(), get_version(Protocol::IPad));
+        config.handler_panic_strategy = crate::SupervisionStrategy::Stop;
+        let client = Client::new_with_config(config, on_event as fn(_) -> _);
+        let mut events = client.event_stream();
+        client.handler.handle(QEvent::Login(10000)).await;
+
+        assert!(matches!(events.recv().await, Ok(QEvent::Login(10000))));
+        match events.recv().await {
+            Ok(QEvent::HandlerError(e)) => {
+                assert_eq!(e.event, "Login");
+                assert_eq!(e.message, "boom");
+            }
+            e => panic!("unexpected event: {:?}", e),
+        }
+        assert_eq!(client.get_status(), NetworkStatus::Stop as u8);
+    }
+}
@@ -5,12 +5,12 @@ use std::time::Duration;
 
 use crate::client::event::{ClientDisconnect, DisconnectReason};
 use async_trait::async_trait;
-use bytes::Bytes;
-use futures_util::{SinkExt, StreamExt};
+use bytes::{Bytes, BytesMut};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use tokio::io::{self, AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
 use tokio::sync::broadcast;
-use tokio_util::codec::LengthDelimitedCodec;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
 
 use crate::client::tcp::tcp_connect_fastest;
 use crate::client::NetworkStatus;
@@ -20,6 +20,25 @@ use super::Client;
 
 pub type OutPktSender = broadcast::Sender<Bytes>;
 
+/// 收发完整数据帧（不含 4 字节长度前缀）的连接
+///
+/// TcpStream、TLS 隧道等字节流用 [`framed`] 分帧即可，WebSocket 中继等基于消息的传输可以直接实现，
+/// 然后通过 `Client::start_connection` 使用
+pub trait Connection: Stream<Item = io::Result<BytesMut>> + Sink<Bytes, Error = io::Error> {}
+
+impl<T> Connection for T where
+    T: Stream<Item = io::Result<BytesMut>> + Sink<Bytes, Error = io::Error>
+{
+}
+
+/// 按 4 字节长度前缀（包含自身）对字节流分帧
+pub fn framed<S: AsyncRead + AsyncWrite>(stream: S) -> Framed<S, LengthDelimitedCodec> {
+    LengthDelimitedCodec::builder()
+        .length_field_length(4)
+        .length_adjustment(-4)
+        .new_framed(stream)
+}
+
 #[async_trait]
 pub trait Connector<T: AsyncRead + AsyncWrite> {
     async fn connect(&self, client: &Client) -> io::Result<T>;
@@ -62,9 +81,15 @@ impl crate::Client {
     ///
     /// **Notice: 该方法仅开始处理包，需要手动登录并开始心跳包**
     pub async fn start(self: &Arc<Self>, stream: impl AsyncRead + AsyncWrite) {
+        self.start_connection(framed(stream)).await
+    }
+
+    /// 与 start 相同，使用已经分帧的连接，见 [`Connection`]
+    pub async fn start_connection(self: &Arc<Self>, conn: impl Connection) {
+        self.weak_self.get_or_init(|| Arc::downgrade(self));
         self.status
             .store(NetworkStatus::Running as u8, Ordering::Relaxed);
-        self.net_loop(stream).await; // 阻塞到断开
+        self.net_loop(conn).await; // 阻塞到断开
         self.disconnect();
         self.online.store(false, Ordering::Relaxed);
 
@@ -111,30 +136,31 @@ impl crate::Client {
         self.online.store(false, Ordering::Relaxed);
     }
 
-    fn disconnect(&self) {
+    pub(crate) fn disconnect(&self) {
         // TODO dispatch disconnect event
         // don't unwrap (Err means there is no receiver.)
         self.disconnect_signal.send(()).ok();
     }
 
-    async fn net_loop(self: &Arc<Client>, stream: impl AsyncRead + AsyncWrite) {
-        let (mut write_half, mut read_half) = LengthDelimitedCodec::builder()
-            .length_field_length(4)
-            .length_adjustment(-4)
-            .new_framed(stream)
-            .split();
+    async fn net_loop(self: &Arc<Client>, conn: impl Connection) {
+        let (mut write_half, mut read_half) = conn.split();
         // 外发包 Channel Receiver
         let mut rx = self.out_pkt_sender.subscribe();
         let mut disconnect_signal = self.disconnect_signal.subscribe();
         loop {
             tokio::select! {
                 input = read_half.next() => {
-                    if let Some(Ok(mut input)) = input
-                        && let Ok(pkt) = self.engine.read().await.transport.decode_packet(&mut input)
-                    {
-                        self.process_income_packet(pkt).await;
-                    } else {
+                    let Some(Ok(input)) = input else {
                         break;
+                    };
+                    let pkt = self.engine.read().await.decode_packet(input);
+                    match pkt {
+                        Ok(pkt) => self.process_income_packet(pkt).await,
+                        Err(err) => {
+                            tracing::error!("failed to decode frame: {}", err);
+                            self.recent.dump_on_error();
+                            break;
+                        }
                     }
                 }
                 output = rx.recv() => {
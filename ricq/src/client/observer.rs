@@ -0,0 +1,25 @@
+use ricq_core::protocol::packet::Packet;
+
+/// 数据包方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketDirection {
+    /// 收到的包，body 已解密、解压
+    Inbound,
+    /// 发出的包，body 为加密前的内容
+    Outbound,
+}
+
+/// 收发包回调，用于调试协议或实现 crate 尚未支持的功能，通过 `Config::packet_observer` 设置
+///
+/// 收到的包在分发前调用，发出的包在加密前调用；调用是同步的，实现时不要阻塞
+pub trait PacketObserver: Send + Sync {
+    fn observe(&self, direction: PacketDirection, pkt: &Packet);
+}
+
+impl super::Client {
+    pub(crate) fn observe_packet(&self, direction: PacketDirection, pkt: &Packet) {
+        if let Some(observer) = &self.packet_observer {
+            observer.observe(direction, pkt);
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use cached::Cached;
 use std::sync::Arc;
 
-use ricq_core::msg::MessageChain;
-use ricq_core::structs::{FriendAudio, FriendAudioMessage, FriendMessage};
+use ricq_core::msg::elem::RedPacket;
+use ricq_core::msg::parse_friend_message;
+use ricq_core::structs::{FriendAudio, FriendAudioMessage, FriendTransfer};
 use ricq_core::{pb, RQResult};
 
-use crate::client::event::{FriendAudioMessageEvent, FriendMessageEvent};
+use crate::client::event::{FriendAudioMessageEvent, FriendMessageEvent, FriendTransferEvent};
+use crate::client::stats::Activity;
 use crate::handler::QEvent;
 use crate::Client;
 
@@ -19,10 +21,12 @@ impl Client {
         }
         if let Some(ptt) = take_ptt(&mut msg) {
             // TODO self friend audio
+            let message = parse_friend_audio_message(msg, ptt)?;
+            self.record_friend_activity(message.from_uin, Activity::Received);
             self.handler
                 .handle(QEvent::FriendAudioMessage(FriendAudioMessageEvent {
                     client: self.clone(),
-                    inner: parse_friend_audio_message(msg, ptt)?,
+                    inner: message,
                 }))
                 .await;
             return Ok(());
@@ -39,36 +43,43 @@ impl Client {
                 let _ = tx.send(message.seqs.first().cloned().unwrap_or_default());
                 return Ok(());
             }
+            self.handler
+                .handle(QEvent::SelfFriendMessage(FriendMessageEvent {
+                    client: self.clone(),
+                    inner: message,
+                }))
+                .await;
+            return Ok(());
         }
+        let transfer = message
+            .elements
+            .red_packet()
+            .filter(RedPacket::is_transfer)
+            .map(|packet| FriendTransfer {
+                from_uin: message.from_uin,
+                seqs: message.seqs.clone(),
+                time: message.time,
+                packet,
+            });
+        self.record_friend_activity(message.from_uin, Activity::Received);
         self.handler
             .handle(QEvent::FriendMessage(FriendMessageEvent {
                 client: self.clone(),
                 inner: message,
             }))
             .await;
+        if let Some(transfer) = transfer {
+            self.handler
+                .handle(QEvent::FriendTransfer(FriendTransferEvent {
+                    client: self.clone(),
+                    inner: transfer,
+                }))
+                .await;
+        }
         Ok(())
     }
 }
 
-pub fn parse_friend_message(msg: pb::msg::Message) -> RQResult<FriendMessage> {
-    let head = msg.head.unwrap();
-    Ok(FriendMessage {
-        seqs: vec![head.msg_seq()],
-        target: head.to_uin.unwrap(),
-        time: head.msg_time.unwrap(),
-        from_uin: head.from_uin.unwrap_or_default(),
-        from_nick: head.from_nick.unwrap_or_default(),
-        rands: vec![
-            if let Some(attr) = &msg.body.as_ref().unwrap().rich_text.as_ref().unwrap().attr {
-                attr.random()
-            } else {
-                0
-            },
-        ],
-        elements: MessageChain::from(msg.body.unwrap().rich_text.unwrap().elems), // todo ptt_store
-    })
-}
-
 pub fn parse_friend_audio_message(
     msg: pb::msg::Message,
     ptt: pb::msg::Ptt,
@@ -1,7 +1,9 @@
 use crate::client::event::NewFriendRequestEvent;
 use crate::handler::QEvent;
 use crate::Client;
-use ricq_core::command::profile_service::FriendSystemMessages;
+use ricq_core::command::profile_service::{
+    FriendSystemMessages, NewFriendRequest, RequestDecision,
+};
 use std::sync::Arc;
 
 impl Client {
@@ -9,7 +11,8 @@ impl Client {
         self: &Arc<Self>,
         msgs: FriendSystemMessages,
     ) {
-        for request in msgs.requests {
+        for mut request in msgs.requests.into_iter().filter(|r| !r.handled) {
+            request.decision = self.apply_friend_request_policy(&request).await;
             self.handler
                 .handle(QEvent::NewFriendRequest(NewFriendRequestEvent {
                     client: self.clone(),
@@ -18,4 +21,27 @@ impl Client {
                 .await;
         }
     }
+
+    async fn apply_friend_request_policy(&self, request: &NewFriendRequest) -> RequestDecision {
+        let policy = &self.friend_request_policy;
+        let accept = policy.accept_all
+            || request
+                .source_group_code
+                .is_some_and(|code| policy.accept_groups.contains(&code));
+        let result = if accept {
+            self.solve_friend_system_message(request.msg_seq, request.req_uin, true)
+                .await
+                .map(|_| RequestDecision::Accepted)
+        } else if let Some(reason) = &policy.reject_reason {
+            self.reject_friend_system_message(request.msg_seq, request.req_uin, reason, false)
+                .await
+                .map(|_| RequestDecision::Rejected)
+        } else {
+            return RequestDecision::Pending;
+        };
+        result.unwrap_or_else(|err| {
+            tracing::warn!("failed to apply friend request policy: {}", err);
+            RequestDecision::Pending
+        })
+    }
 }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use ricq_core::command::profile_service::GroupSystemMessages;
+use ricq_core::command::profile_service::{GroupSystemMessages, RequestDecision, SelfInvited};
 
 use crate::client::event::{JoinGroupRequestEvent, SelfInvitedEvent};
 use crate::handler::QEvent;
@@ -8,13 +8,15 @@ use crate::Client;
 
 impl Client {
     pub(crate) async fn process_group_system_messages(self: &Arc<Self>, msgs: GroupSystemMessages) {
-        for request in msgs.self_invited.clone() {
-            if self
-                .self_invited_exists(request.msg_seq, request.msg_time)
-                .await
+        for mut request in msgs.self_invited.clone() {
+            if request.handled
+                || self
+                    .self_invited_exists(request.msg_seq, request.msg_time)
+                    .await
             {
                 continue;
             }
+            request.decision = self.apply_group_invite_policy(&request).await;
             self.handler
                 .handle(QEvent::SelfInvited(SelfInvitedEvent {
                     client: self.clone(),
@@ -23,9 +25,10 @@ impl Client {
                 .await;
         }
         for request in msgs.join_group_requests.clone() {
-            if self
-                .join_group_request_exists(request.msg_seq, request.msg_time)
-                .await
+            if request.handled
+                || self
+                    .join_group_request_exists(request.msg_seq, request.msg_time)
+                    .await
             {
                 continue;
             }
@@ -40,6 +43,49 @@ impl Client {
         *cache = msgs
     }
 
+    async fn apply_group_invite_policy(&self, request: &SelfInvited) -> RequestDecision {
+        let policy = &self.group_invite_policy;
+        let mut accept = policy.hook.as_ref().and_then(|hook| hook(request));
+        if accept.is_none() && policy.accept_from.contains(&request.invitor_uin) {
+            accept = Some(true);
+        }
+        if accept.is_none()
+            && let Some(min) = policy.min_member_count
+        {
+            match self.get_group_info(request.group_code).await {
+                Ok(Some(info)) if info.member_count >= min => accept = Some(true),
+                Ok(_) => {}
+                Err(err) => tracing::warn!("failed to get invited group info: {}", err),
+            }
+        }
+        if accept.is_none() && policy.reject_others {
+            accept = Some(false);
+        }
+        let Some(accept) = accept else {
+            return RequestDecision::Pending;
+        };
+        match self
+            .solve_group_system_message(
+                request.msg_seq,
+                request.invitor_uin,
+                request.group_code,
+                false,
+                true,
+                accept,
+                false,
+                "".into(),
+            )
+            .await
+        {
+            Ok(_) if accept => RequestDecision::Accepted,
+            Ok(_) => RequestDecision::Rejected,
+            Err(err) => {
+                tracing::warn!("failed to apply group invite policy: {}", err);
+                RequestDecision::Pending
+            }
+        }
+    }
+
     async fn self_invited_exists(&self, msg_seq: i64, msg_time: i64) -> bool {
         if self.start_time > msg_time as i32 {
             return true;
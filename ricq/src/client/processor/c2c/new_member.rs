@@ -16,16 +16,27 @@ impl Client {
         let head = msg.head.ok_or(RQError::EmptyField("msg.head"))?;
         let group_code = group_uin2code(head.from_uin());
         let member_uin = head.auth_uin();
+        self.info_cache
+            .lock()
+            .await
+            .invalidate_membership(group_code, member_uin);
 
-        self.handler
-            .handle(QEvent::NewMember(NewMemberEvent {
-                client: self.clone(),
-                inner: NewMember {
-                    group_code,
-                    member_uin,
-                },
-            }))
-            .await;
+        let event = NewMemberEvent {
+            client: self.clone(),
+            inner: NewMember {
+                group_code,
+                member_uin,
+            },
+        };
+        if member_uin == self.uin().await {
+            if let Err(err) = self.add_cached_group(group_code).await {
+                tracing::warn!("failed to get joined group info: {}", err);
+            }
+            self.handler
+                .handle(QEvent::BotJoinGroup(event.clone()))
+                .await;
+        }
+        self.handler.handle(QEvent::NewMember(event)).await;
 
         Ok(())
     }
@@ -1,10 +1,9 @@
 use std::sync::Arc;
 
-use ricq_core::msg::MessageChain;
-use ricq_core::structs::GroupTempMessage;
-use ricq_core::{pb, RQError, RQResult};
+use ricq_core::msg::{is_group_temp, parse_temp_message, parse_temp_session_message};
+use ricq_core::{pb, RQResult};
 
-use crate::client::event::GroupTempMessageEvent;
+use crate::client::event::{GroupTempMessageEvent, TempSessionEvent};
 use crate::handler::QEvent;
 use crate::Client;
 
@@ -13,6 +12,16 @@ impl Client {
         self: &Arc<Self>,
         msg: pb::msg::Message,
     ) -> RQResult<()> {
+        if !msg.head.as_ref().map(is_group_temp).unwrap_or(true) {
+            let message = parse_temp_session_message(msg)?;
+            self.handler
+                .handle(QEvent::TempSession(TempSessionEvent {
+                    client: self.clone(),
+                    inner: message,
+                }))
+                .await;
+            return Ok(());
+        }
         let message = parse_temp_message(msg)?;
         self.handler
             .handle(QEvent::GroupTempMessage(GroupTempMessageEvent {
@@ -23,26 +32,3 @@ impl Client {
         Ok(())
     }
 }
-
-pub fn parse_temp_message(msg: pb::msg::Message) -> RQResult<GroupTempMessage> {
-    let head = msg.head.unwrap();
-    let tmp_head = head
-        .c2c_tmp_msg_head
-        .ok_or(RQError::EmptyField("c2c_tmp_msg_head"))?;
-
-    Ok(GroupTempMessage {
-        seqs: vec![head.msg_seq.unwrap_or_default()],
-        rands: vec![
-            if let Some(attr) = &msg.body.as_ref().unwrap().rich_text.as_ref().unwrap().attr {
-                attr.random()
-            } else {
-                0
-            },
-        ],
-        time: head.msg_time.unwrap(),
-        from_uin: head.from_uin.unwrap_or_default(),
-        from_nick: head.from_nick.unwrap_or_default(),
-        elements: MessageChain::from(msg.body.unwrap().rich_text.unwrap().elems), // todo ptt_store
-        group_code: tmp_head.group_code.unwrap_or_default(),
-    })
-}
@@ -62,6 +62,8 @@ impl Client {
                         }
                     }
                 }
+                drop(session);
+                self.persist_highway_session().await;
             }
         }
         // TODO process
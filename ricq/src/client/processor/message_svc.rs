@@ -58,7 +58,7 @@ impl Client {
         self.handler
             .handle(QEvent::KickedOffline(KickedOfflineEvent {
                 client: self.clone(),
-                inner: offline,
+                inner: offline.into(),
             }))
             .await;
     }
@@ -1,9 +1,14 @@
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use bytes::Bytes;
 
 use ricq_core::protocol::packet::Packet;
 
+use crate::client::event::UnhandledPush;
+use crate::handler::QEvent;
+use crate::EventMask;
+
 pub mod c2c;
 pub mod config_push_svc;
 pub mod message_svc;
@@ -22,14 +27,20 @@ macro_rules! log_error {
 
 impl super::Client {
     /// 接收到的 Packet 统一分发
+    #[tracing::instrument(skip_all, fields(command = %pkt.command_name, seq = pkt.seq_id))]
     pub async fn process_income_packet(self: &Arc<Self>, pkt: Packet) {
         tracing::trace!("received pkt: {}", &pkt.command_name);
+        self.recent.record_packet(&pkt, false);
+        if let Some(metrics) = &self.metrics {
+            metrics.packet_received(&pkt.command_name);
+        }
+        self.observe_packet(super::observer::PacketDirection::Inbound, &pkt);
         // response, send_and_wait 的包将会在此被截流
-        {
-            if let Some(sender) = self.packet_promises.write().await.remove(&pkt.seq_id) {
-                sender.send(pkt).unwrap();
-                return;
-            }
+        let sender = self.packet_promises.lock().unwrap().remove(&pkt.seq_id);
+        if let Some(sender) = sender {
+            // 等待方可能已被取消，忽略发送失败
+            sender.send(pkt).ok();
+            return;
         }
 
         tracing::trace!("pkt: {} passed packet_promises", &pkt.command_name);
@@ -40,6 +51,19 @@ impl super::Client {
             }
         }
 
+        let mask = match pkt.command_name.as_ref() {
+            "OnlinePush.PbPushGroupMsg" => Some(EventMask::GROUP_MESSAGE),
+            "MessageSvc.PushNotify" | "OnlinePush.PbC2CMsgSync" => Some(EventMask::C2C_MESSAGE),
+            "OnlinePush.PbPushTransMsg" => Some(EventMask::NOTICE),
+            _ => None,
+        };
+        if let Some(mask) = mask
+            && !self.event_mask().contains(mask)
+        {
+            self.ignored_packets.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
         let cli = self.clone();
         tokio::spawn(async move {
             match pkt.command_name.as_ref() {
@@ -123,7 +147,12 @@ impl super::Client {
                                 .await,
                                 "delete_online_push error: {:?}"
                             );
-                            cli.process_push_req(resp.msg_infos).await;
+                            // 即使不处理也需要回复，否则服务端会重复推送
+                            if cli.event_mask().contains(EventMask::NOTICE) {
+                                cli.process_push_req(resp.msg_infos).await;
+                            } else {
+                                cli.ignored_packets.fetch_add(1, Ordering::Relaxed);
+                            }
                         }
                         Err(err) => {
                             tracing::warn!("failed to decode [OnlinePush.ReqPush]: {}", err);
@@ -170,6 +199,20 @@ impl super::Client {
                         }
                     }
                 }
+                "StatSvc.SvcReqMSFLoginNotify" => {
+                    let notify = cli.engine.read().await.decode_msf_login_notify(pkt.body);
+                    match notify {
+                        Ok(notify) => {
+                            cli.process_msf_login_notify(notify).await;
+                        }
+                        Err(err) => {
+                            tracing::warn!(
+                                "failed to decode [StatSvc.SvcReqMSFLoginNotify]: {}",
+                                err
+                            );
+                        }
+                    }
+                }
                 "OnlinePush.PbC2CMsgSync" => {
                     // 其他设备发送消息，同步
                     let push = cli.engine.read().await.decode_c2c_sync_packet(pkt.body);
@@ -199,8 +242,27 @@ impl super::Client {
                 }
                 _ => {
                     tracing::debug!("unhandled pkt: {}", &pkt.command_name);
+                    cli.ignored_packets.fetch_add(1, Ordering::Relaxed);
+                    cli.process_unhandled_packet(pkt).await;
                 }
             }
         });
     }
+
+    async fn process_unhandled_packet(self: &Arc<Self>, pkt: Packet) {
+        *self
+            .unhandled_packets
+            .lock()
+            .unwrap()
+            .entry(pkt.command_name.clone())
+            .or_default() += 1;
+        self.handler
+            .handle(QEvent::Unhandled(UnhandledPush {
+                seq: pkt.seq_id,
+                body_len: pkt.body.len(),
+                body: self.report_unhandled_body.then_some(pkt.body),
+                command: pkt.command_name,
+            }))
+            .await;
+    }
 }
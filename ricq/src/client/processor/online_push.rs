@@ -1,5 +1,6 @@
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 
 use bytes::{Buf, Bytes};
 use cached::Cached;
@@ -7,19 +8,24 @@ use cached::Cached;
 use prost::Message;
 use ricq_core::command::online_push::GroupMessagePart;
 use ricq_core::command::online_push::{OnlinePushTrans, PushTransInfo};
-use ricq_core::msg::MessageChain;
+use ricq_core::msg::{parse_group_message, MessageChain};
 use ricq_core::structs::{
-    DeleteFriend, FriendInfo, FriendMessageRecall, FriendPoke, GroupAudio, GroupAudioMessage,
-    GroupLeave, GroupMessage, GroupMessageRecall, GroupMute, GroupNameUpdate, GroupPoke,
+    BotMuted, BotUnmuted, DeleteFriend, FriendInfo, FriendMessageRecall, FriendOnlineStatus,
+    FriendPoke, FriendStatusChanged, GroupAudio, GroupAudioMessage, GroupGrayTip, GroupLeave,
+    GroupLeaveKind, GroupMessageRecall, GroupMute, GroupNameUpdate, GroupPoke, GroupRedPacket,
+    MemberCardChanged, OnlineStatus,
 };
 use ricq_core::{jce, pb};
 
 use crate::client::event::{
-    DeleteFriendEvent, FriendMessageRecallEvent, FriendPokeEvent, GroupAudioMessageEvent,
-    GroupDisbandEvent, GroupLeaveEvent, GroupMessageEvent, GroupMessageRecallEvent, GroupMuteEvent,
-    GroupNameUpdateEvent, GroupPokeEvent, MemberPermissionChangeEvent, NewFriendEvent,
+    BotMutedEvent, BotUnmutedEvent, DeleteFriendEvent, FriendMessageRecallEvent, FriendPokeEvent,
+    FriendStatusChangedEvent, GroupAudioMessageEvent, GroupDisbandEvent, GroupGrayTipEvent,
+    GroupLeaveEvent, GroupMessageEvent, GroupMessageRecallEvent, GroupMuteEvent,
+    GroupNameUpdateEvent, GroupPokeEvent, GroupRedPacketEvent, MemberCardChangedEvent,
+    MemberPermissionChangeEvent, NewFriendEvent,
 };
 use crate::client::handler::QEvent;
+use crate::client::stats::Activity;
 use crate::client::Client;
 use crate::RQResult;
 
@@ -40,8 +46,12 @@ impl Client {
                 return Ok(());
             }
         }
+        if self.group_message_part_exists(&group_message_part).await {
+            return Ok(());
+        }
 
         if let Some(ptt) = group_message_part.ptt {
+            self.record_group_activity(group_message_part.group_code, Activity::Received);
             self.handler
                 .handle(QEvent::GroupAudioMessage(GroupAudioMessageEvent {
                     client: self.clone(),
@@ -86,58 +96,86 @@ impl Client {
         // handle message
         if let Some(group_msg) = group_msg {
             // message is finish
-            self.handler
-                .handle(QEvent::GroupMessage(GroupMessageEvent {
-                    client: self.clone(),
-                    inner: self.parse_group_message(group_msg).await?,
-                }))
-                .await; //todo
+            let group_msg = parse_group_message(group_msg);
+            self.clock_skew.store(
+                group_msg.time as i64 - UNIX_EPOCH.elapsed().unwrap().as_secs() as i64,
+                Ordering::Relaxed,
+            );
+            self.store_group_message(
+                group_msg.group_code,
+                &group_msg.seqs,
+                group_msg.elements.clone(),
+            )
+            .await;
+            self.check_member_card(
+                group_msg.group_code,
+                group_msg.from_uin,
+                &group_msg.group_card,
+            )
+            .await;
+            let red_packet = group_msg
+                .elements
+                .red_packet()
+                .map(|packet| GroupRedPacket {
+                    group_code: group_msg.group_code,
+                    sender_uin: group_msg.from_uin,
+                    seqs: group_msg.seqs.clone(),
+                    time: group_msg.time,
+                    packet,
+                });
+            let event = GroupMessageEvent {
+                client: self.clone(),
+                inner: group_msg,
+            };
+            if event.inner.from_uin == self.uin().await {
+                self.handler.handle(QEvent::SelfGroupMessage(event)).await;
+            } else {
+                self.record_group_activity(event.inner.group_code, Activity::Received);
+                self.handler.handle(QEvent::GroupMessage(event)).await;
+            }
+            if let Some(red_packet) = red_packet {
+                self.handler
+                    .handle(QEvent::GroupRedPacket(GroupRedPacketEvent {
+                        client: self.clone(),
+                        inner: red_packet,
+                    }))
+                    .await;
+            }
         }
         Ok(())
     }
 
-    pub(crate) async fn parse_group_message(
-        &self,
-        mut parts: Vec<GroupMessagePart>,
-    ) -> RQResult<GroupMessage> {
-        parts.sort_by(|a, b| a.pkg_index.cmp(&b.pkg_index));
-
-        let group_code = parts.first().map(|p| p.group_code).unwrap_or_default();
-        let group_name = parts
-            .first_mut()
-            .map(|p| std::mem::take(&mut p.group_name))
-            .unwrap_or_default();
-        let group_card = parts
-            .first_mut()
-            .map(|p| std::mem::take(&mut p.group_card))
-            .unwrap_or_default();
-        let from_uin = parts.first().map(|p| p.from_uin).unwrap_or_default();
-        let time = parts.first().map(|p| p.time).unwrap_or_default();
-
-        let mut seqs = Vec::with_capacity(parts.len());
-        let mut rands = Vec::with_capacity(parts.len());
-        let mut elements = Vec::with_capacity(6); // number by experience
-        for p in parts {
-            seqs.push(p.seq);
-            rands.push(p.rand);
-            elements.extend(p.elems.into_iter().filter_map(|e| e.elem));
+    /// 服务端偶尔会重复推送同一条群消息，窗口内重复的分片返回 true
+    async fn group_message_part_exists(&self, part: &GroupMessagePart) -> bool {
+        let Some(dedup) = &self.group_message_dedup else {
+            return false;
+        };
+        let key = (part.group_code, part.seq, part.rand, part.time);
+        let mut dedup = dedup.write().await;
+        if dedup.cache_get(&key).is_some() {
+            return true;
         }
-        // dbg!(elements.len()); // most of message will be 4, complex message like share card is 5
-
-        Ok(GroupMessage {
-            seqs,
-            rands,
-            group_code,
-            group_name,
-            group_card,
-            from_uin,
-            time,
-            elements: MessageChain(elements),
-        })
+        dedup.cache_set(key, ());
+        if dedup.cache_misses().unwrap_or_default() > 100 {
+            dedup.flush();
+            dedup.cache_reset_metrics();
+        }
+        false
+    }
 
-        // TODO: extInfo
-        // TODO: group_card_update
-        // TODO: ptt_store
+    /// 记录最近的群消息，撤回时可取回原消息，多分片消息以第一个 seq 为准
+    pub(crate) async fn store_group_message(
+        &self,
+        group_code: i64,
+        seqs: &[i32],
+        chain: MessageChain,
+    ) {
+        if let Some(seq) = seqs.first() {
+            self.group_message_store
+                .write()
+                .await
+                .cache_set((group_code, *seq), chain);
+        }
     }
 
     pub(crate) async fn process_push_req(self: &Arc<Self>, msg_infos: Vec<jce::PushMessageInfo>) {
@@ -160,6 +198,9 @@ impl Client {
                             r.advance(6);
                             let target = r.get_u32() as i64;
                             let duration = Duration::from_secs(r.get_u32() as u64);
+                            if target == self.uin().await {
+                                self.process_bot_mute(group_code, operator, duration).await;
+                            }
                             self.handler
                                 .handle(QEvent::GroupMute(GroupMuteEvent {
                                     client: self.clone(),
@@ -178,24 +219,33 @@ impl Client {
                             let b = pb::notify::NotifyMsgBody::decode(&*r).unwrap();
                             if let Some(opt_msg_recall) = b.opt_msg_recall {
                                 let operator_uin = opt_msg_recall.uin;
+                                let by_self = operator_uin == self.uin().await;
                                 // use map iterator here will produce massive asm code
                                 for rm in opt_msg_recall.recalled_msg_list {
                                     if rm.msg_type == 2 {
                                         continue;
                                     }
+                                    let event = GroupMessageRecallEvent {
+                                        client: self.clone(),
+                                        inner: GroupMessageRecall {
+                                            msg_seq: rm.seq,
+                                            group_code,
+                                            operator_uin,
+                                            author_uin: rm.author_uin,
+                                            time: rm.time,
+                                            message: self
+                                                .group_message_store
+                                                .write()
+                                                .await
+                                                .cache_remove(&(group_code, rm.seq)),
+                                        },
+                                    };
                                     self.handler
-                                        .handle(QEvent::GroupMessageRecall(
-                                            GroupMessageRecallEvent {
-                                                client: self.clone(),
-                                                inner: GroupMessageRecall {
-                                                    msg_seq: rm.seq,
-                                                    group_code,
-                                                    operator_uin,
-                                                    author_uin: rm.author_uin,
-                                                    time: rm.time,
-                                                },
-                                            },
-                                        ))
+                                        .handle(if by_self {
+                                            QEvent::SelfGroupMessageRecall(event)
+                                        } else {
+                                            QEvent::GroupMessageRecall(event)
+                                        })
                                         .await;
                                 }
                             }
@@ -203,7 +253,7 @@ impl Client {
                             if let Some(t) = b.opt_general_gray_tip {
                                 let mut sender: i64 = 0;
                                 let mut receiver: i64 = 0;
-                                for templ in t.msg_templ_param {
+                                for templ in &t.msg_templ_param {
                                     match &*templ.name {
                                         "uin_str1" => {
                                             sender = templ.value.parse().unwrap_or_default()
@@ -221,6 +271,24 @@ impl Client {
                                             inner: GroupPoke { sender, receiver },
                                         }))
                                         .await;
+                                } else {
+                                    self.handler
+                                        .handle(QEvent::GroupGrayTip(GroupGrayTipEvent {
+                                            client: self.clone(),
+                                            inner: GroupGrayTip {
+                                                group_code,
+                                                busi_type: t.busi_type,
+                                                busi_id: t.busi_id,
+                                                templ_id: t.templ_id,
+                                                templ_params: t
+                                                    .msg_templ_param
+                                                    .into_iter()
+                                                    .map(|p| (p.name, p.value))
+                                                    .collect(),
+                                                content: t.content,
+                                            },
+                                        }))
+                                        .await;
                                 }
                             }
                             // TODO 一些没什么用的 event 暂时没写
@@ -250,27 +318,36 @@ impl Client {
                         0xB3 => {
                             let msg_add_frd_notify = pb::SubB3::decode(&*msg.v_protobuf).unwrap();
                             if let Some(f) = msg_add_frd_notify.msg_add_frd_notify {
+                                let friend = FriendInfo {
+                                    uin: f.uin,
+                                    nick: f.nick,
+                                    ..Default::default()
+                                };
+                                self.info_cache
+                                    .lock()
+                                    .await
+                                    .friends
+                                    .cache_set(friend.uin, Some(friend.clone()));
                                 self.handler
                                     .handle(QEvent::NewFriend(NewFriendEvent {
                                         client: self.clone(),
-                                        inner: FriendInfo {
-                                            uin: f.uin,
-                                            nick: f.nick,
-                                            ..Default::default()
-                                        },
+                                        inner: friend,
                                     }))
                                     .await;
                             }
                         }
+                        // 自己退群
                         0xD4 => {
                             let d4 = pb::SubD4::decode(&*msg.v_protobuf).unwrap();
+                            self.remove_cached_group(d4.uin).await;
                             self.handler
-                                .handle(QEvent::GroupLeave(GroupLeaveEvent {
+                                .handle(QEvent::BotLeaveGroup(GroupLeaveEvent {
                                     client: self.clone(),
                                     inner: GroupLeave {
                                         group_code: d4.uin,
                                         member_uin: self.uin().await,
                                         operator_uin: None,
+                                        kind: GroupLeaveKind::Leave,
                                     },
                                 }))
                                 .await;
@@ -305,22 +382,24 @@ impl Client {
                                         if profile_info.field.unwrap_or_default() != 1 {
                                             continue;
                                         }
+                                        let group_code =
+                                            mod_group_profile.group_code.unwrap_or_default() as i64;
+                                        let group_name =
+                                            String::from_utf8_lossy(profile_info.value())
+                                                .into_owned();
+                                        let old_group_name =
+                                            self.rename_cached_group(group_code, &group_name).await;
                                         self.handler
                                             .handle(QEvent::GroupNameUpdate(GroupNameUpdateEvent {
                                                 client: self.clone(),
                                                 inner: GroupNameUpdate {
-                                                    group_code: mod_group_profile
-                                                        .group_code
-                                                        .unwrap_or_default()
-                                                        as i64,
+                                                    group_code,
                                                     operator_uin: mod_group_profile
                                                         .cmd_uin
                                                         .unwrap_or_default()
                                                         as i64,
-                                                    group_name: String::from_utf8_lossy(
-                                                        profile_info.value(),
-                                                    )
-                                                    .into_owned(),
+                                                    group_name,
+                                                    old_group_name,
                                                 },
                                             }))
                                             .await;
@@ -328,6 +407,7 @@ impl Client {
                                 }
                                 if let Some(del_friend) = mod_info.del_friend {
                                     for uin in del_friend.uins {
+                                        self.info_cache.lock().await.invalidate_friend(uin as i64);
                                         self.handler
                                             .handle(QEvent::DeleteFriend(DeleteFriendEvent {
                                                 client: self.clone(),
@@ -336,6 +416,10 @@ impl Client {
                                             .await;
                                     }
                                 }
+                                if let Some(change) = mod_info.frd_custom_online_status_change {
+                                    let uin = change.uin.unwrap_or_default() as i64;
+                                    self.update_friend_status(uin).await;
+                                }
                             }
                         }
                         0x44 => {
@@ -350,6 +434,133 @@ impl Client {
         }
     }
 
+    /// 收到群消息时发现群名片与缓存不一致，使缓存的成员信息失效
+    async fn check_member_card(self: &Arc<Self>, group_code: i64, uin: i64, card: &str) {
+        let old = {
+            let mut cache = self.info_cache.lock().await;
+            match cache.members.cache_get_mut(&(group_code, uin)) {
+                Some(member) if member.card_name != card => {
+                    std::mem::replace(&mut member.card_name, card.to_owned())
+                }
+                _ => return,
+            }
+        };
+        self.handler
+            .handle(QEvent::MemberCardChanged(MemberCardChangedEvent {
+                client: self.clone(),
+                inner: MemberCardChanged {
+                    group_code,
+                    uin,
+                    old,
+                    new: card.to_owned(),
+                },
+            }))
+            .await;
+    }
+
+    /// 好友在线状态变更推送只携带 uin，查询新状态后更新缓存并发出事件，查询失败时忽略
+    async fn update_friend_status(self: &Arc<Self>, uin: i64) {
+        let status = match self.get_friends_status(vec![uin]).await {
+            Ok(mut statuses) => match statuses.pop() {
+                Some(s) if !s.online => OnlineStatus::Offline,
+                Some(FriendOnlineStatus {
+                    status: Some(status),
+                    ..
+                }) => status,
+                _ => return,
+            },
+            Err(err) => {
+                tracing::warn!("failed to get friend status {}: {}", uin, err);
+                return;
+            }
+        };
+        let old_status = self
+            .info_cache
+            .lock()
+            .await
+            .friends
+            .cache_get_mut(&uin)
+            .and_then(|f| f.as_mut())
+            .and_then(|f| f.status.replace(status));
+        self.handler
+            .handle(QEvent::FriendStatusChanged(FriendStatusChangedEvent {
+                client: self.clone(),
+                inner: FriendStatusChanged {
+                    uin,
+                    old_status,
+                    status,
+                },
+            }))
+            .await;
+    }
+
+    /// 退群、被踢、群解散后从缓存和群列表中移除
+    pub(crate) async fn remove_cached_group(&self, group_code: i64) {
+        self.info_cache.lock().await.invalidate_group(group_code);
+        self.group_list.write().await.remove(&group_code);
+    }
+
+    async fn process_bot_mute(
+        self: &Arc<Self>,
+        group_code: i64,
+        operator: i64,
+        duration: Duration,
+    ) {
+        if duration.is_zero() {
+            self.muted_groups.lock().await.remove(&group_code);
+            self.handler
+                .handle(QEvent::BotUnmuted(BotUnmutedEvent {
+                    client: self.clone(),
+                    inner: BotUnmuted {
+                        group_code,
+                        operator_uin: operator,
+                    },
+                }))
+                .await;
+        } else {
+            self.muted_groups.lock().await.insert(group_code);
+            self.handler
+                .handle(QEvent::BotMuted(BotMutedEvent {
+                    client: self.clone(),
+                    inner: BotMuted {
+                        group_code,
+                        operator_uin: Some(operator),
+                        duration: Some(duration),
+                    },
+                }))
+                .await;
+        }
+    }
+
+    /// 拉取群信息并加入缓存和群列表
+    pub(crate) async fn add_cached_group(&self, group_code: i64) -> RQResult<()> {
+        if let Some(group) = self.get_group_info(group_code).await? {
+            self.info_cache
+                .lock()
+                .await
+                .groups
+                .cache_set(group_code, group.clone());
+            self.group_list.write().await.insert(group_code, group);
+        }
+        Ok(())
+    }
+
+    /// 更新缓存和群列表中的群名称，返回修改前的名称
+    pub(crate) async fn rename_cached_group(&self, group_code: i64, name: &str) -> Option<String> {
+        let mut old = self
+            .info_cache
+            .lock()
+            .await
+            .groups
+            .cache_get_mut(&group_code)
+            .map(|g| std::mem::replace(&mut g.name, name.to_owned()));
+        if let Some(group) = self.group_list.write().await.get_mut(&group_code) {
+            let prev = std::mem::replace(&mut group.name, name.to_owned());
+            old.get_or_insert(prev);
+        }
+        old
+    }
+
     async fn push_req_exists(&self, info: &jce::PushMessageInfo) -> bool {
         let msg_time = info.msg_time as i32; // 可能是0，不过滤
         if msg_time != 0 && self.start_time > msg_time {
@@ -374,14 +585,27 @@ impl Client {
         }
         match push_trans.info {
             PushTransInfo::MemberLeave(leave) => {
-                self.handler
-                    .handle(QEvent::GroupLeave(GroupLeaveEvent {
-                        client: self.clone(),
-                        inner: leave,
-                    }))
-                    .await;
+                self.info_cache
+                    .lock()
+                    .await
+                    .invalidate_membership(leave.group_code, leave.member_uin);
+                let event = GroupLeaveEvent {
+                    client: self.clone(),
+                    inner: leave,
+                };
+                if event.inner.member_uin == self.uin().await {
+                    self.remove_cached_group(event.inner.group_code).await;
+                    self.handler.handle(QEvent::BotLeaveGroup(event)).await;
+                } else {
+                    self.handler.handle(QEvent::GroupLeave(event)).await;
+                }
             }
             PushTransInfo::MemberPermissionChange(change) => {
+                self.info_cache.lock().await.set_member_permission(
+                    change.group_code,
+                    change.member_uin,
+                    change.new_permission.clone(),
+                );
                 self.handler
                     .handle(QEvent::MemberPermissionChange(
                         MemberPermissionChangeEvent {
@@ -392,12 +616,25 @@ impl Client {
                     .await;
             }
             PushTransInfo::GroupDisband(disband) => {
+                self.remove_cached_group(disband.group_code).await;
+                let leave = GroupLeave {
+                    group_code: disband.group_code,
+                    member_uin: self.uin().await,
+                    operator_uin: Some(disband.operator_uin),
+                    kind: GroupLeaveKind::Disband,
+                };
                 self.handler
                     .handle(QEvent::GroupDisband(GroupDisbandEvent {
                         client: self.clone(),
                         inner: disband,
                     }))
                     .await;
+                self.handler
+                    .handle(QEvent::BotLeaveGroup(GroupLeaveEvent {
+                        client: self.clone(),
+                        inner: leave,
+                    }))
+                    .await;
             }
         }
     }
@@ -440,9 +677,73 @@ impl Client {
     }
 
     pub(crate) async fn process_sid_ticket_expired(self: &Arc<Self>, seq: i32) -> RQResult<()> {
-        self.request_change_sig(Some(3554528)).await?;
-        self.register_client().await?;
+        self.refresh_token().await?;
         self.send_sid_ticket_expired_response(seq).await?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use jcers::JcePut;
+
+    use ricq_core::command::common::PbToBytes;
+    use ricq_core::protocol::device::Device;
+    use ricq_core::protocol::version::{get_version, Protocol};
+    use ricq_core::structs::GroupInfo;
+
+    use super::*;
+    use crate::handler::DefaultHandler;
+
+    #[tokio::test]
+    async fn test_bot_leave_group_d4() {
+        let client = Arc::new(Client::new(
+            Device::random(),
+            get_version(Protocol::IPad),
+            DefaultHandler,
+        ));
+        client.uin.store(10000, Ordering::Relaxed);
+        let group = GroupInfo {
+            code: 20001,
+            ..Default::default()
+        };
+        client.group_list.write().await.insert(20001, group.clone());
+        client
+            .info_cache
+            .lock()
+            .await
+            .groups
+            .cache_set(20001, group);
+        let mut events = client.event_stream();
+
+        let msg = jce::MsgType0x210 {
+            sub_msg_type: 0xD4,
+            v_protobuf: pb::SubD4 { uin: 20001 }.to_bytes(),
+        };
+        client
+            .process_push_req(vec![jce::PushMessageInfo {
+                msg_type: 528,
+                msg_seq: 1,
+                v_msg: msg.freeze(),
+                ..Default::default()
+            }])
+            .await;
+
+        match events.try_recv() {
+            Ok(QEvent::BotLeaveGroup(e)) => {
+                assert_eq!(e.inner.group_code, 20001);
+                assert_eq!(e.inner.member_uin, 10000);
+                assert_eq!(e.inner.kind, GroupLeaveKind::Leave);
+            }
+            e => panic!("unexpected event: {e:?}"),
+        }
+        assert!(!client.group_list.read().await.contains_key(&20001));
+        assert!(client
+            .info_cache
+            .lock()
+            .await
+            .groups
+            .cache_get(&20001)
+            .is_none());
+    }
+}
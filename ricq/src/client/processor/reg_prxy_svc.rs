@@ -9,7 +9,7 @@ impl Client {
         other_clients: Vec<OtherClientInfo>,
     ) -> Result<(), RQError> {
         tracing::debug!("{:?}", other_clients);
-        // TODO merge part and dispatch to handler
+        *self.online_clients.write().await = other_clients;
         Ok(())
     }
 }
@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
 use ricq_core::jce;
+use ricq_core::structs::{OtherClientInfo, OtherClientStatusChanged};
 
-use crate::client::event::MSFOfflineEvent;
+use crate::client::event::{KickedOfflineEvent, OtherClientStatusChangedEvent};
 use crate::client::{Client, NetworkStatus};
 use crate::handler::QEvent;
 
@@ -17,10 +18,51 @@ impl Client {
             .ok();
         self.stop(NetworkStatus::MsfOffline);
         self.handler
-            .handle(QEvent::MSFOffline(MSFOfflineEvent {
+            .handle(QEvent::KickedOffline(KickedOfflineEvent {
                 client: self.clone(),
-                inner: offline,
+                inner: offline.into(),
             }))
             .await;
     }
+
+    /// 其他客户端上线/下线，status 1 上线 2 下线
+    pub(crate) async fn process_msf_login_notify(
+        self: &Arc<Self>,
+        notify: jce::SvcReqMSFLoginNotify,
+    ) {
+        let info = OtherClientInfo {
+            app_id: notify.app_id,
+            instance_id: 0,
+            sub_platform: notify.title,
+            device_kind: notify.info,
+        };
+        let online = match notify.status {
+            1 => {
+                let mut clients = self.online_clients.write().await;
+                if !clients.iter().any(|c| c.app_id == info.app_id) {
+                    clients.push(info.clone());
+                }
+                true
+            }
+            2 => {
+                self.online_clients
+                    .write()
+                    .await
+                    .retain(|c| c.app_id != info.app_id);
+                false
+            }
+            _ => return,
+        };
+        self.handler
+            .handle(QEvent::OtherClientStatusChanged(
+                OtherClientStatusChangedEvent {
+                    client: self.clone(),
+                    inner: OtherClientStatusChanged {
+                        client: info,
+                        online,
+                    },
+                },
+            ))
+            .await;
+    }
 }
@@ -4,6 +4,7 @@ use ricq_core::command::wtlogin::*;
 
 impl Client {
     pub(crate) async fn process_login_response(&self, login_response: LoginResponse) {
+        let success = matches!(login_response, LoginResponse::Success(_));
         if let LoginResponse::Success(ref success) = login_response {
             if let Some(info) = success.account_info.clone() {
                 let mut account_info = self.account_info.write().await;
@@ -16,6 +17,9 @@ impl Client {
             .write()
             .await
             .process_login_response(login_response);
+        if success {
+            self.persist_token().await;
+        }
         self.handler.handle(QEvent::Login(self.uin().await)).await;
     }
 
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+
+use crate::{RQError, RQResult};
+
+/// 非会员每天给同一个人点赞的次数上限
+pub const DAILY_LIKE_LIMIT: u32 = 10;
+/// 管理员每天在同一个群 @全体成员 的次数上限
+pub const DAILY_AT_ALL_LIMIT: u32 = 10;
+/// 非会员每天可以送出的免费礼物数量
+pub const DAILY_FREE_GIFT_LIMIT: u32 = 10;
+
+/// 本地记录的每日配额使用情况，按北京时间日期重置
+///
+/// 记录点赞、@全体成员 和群礼物，送礼物后以服务端返回的剩余免费礼物数量为准
+#[derive(Debug, Clone, Default)]
+pub struct QuotaStatus {
+    /// 记录所属的日期（北京时间，自 unix 纪元起的天数）
+    pub day: i64,
+    /// <uin, 今日已点赞次数>
+    pub likes: HashMap<i64, u32>,
+    /// <group_code, 今日已 @全体成员 次数>
+    pub at_all: HashMap<i64, u32>,
+    /// 今日已送出的免费礼物数量，不区分群
+    pub gifts: u32,
+}
+
+impl QuotaStatus {
+    fn today() -> i64 {
+        (UNIX_EPOCH.elapsed().unwrap().as_secs() as i64 + 8 * 3600) / 86400
+    }
+
+    pub(crate) fn refresh(&mut self) {
+        let today = Self::today();
+        if self.day != today {
+            *self = Self {
+                day: today,
+                ..Default::default()
+            };
+        }
+    }
+
+    pub(crate) fn check_like(&mut self, uin: i64, count: u32) -> RQResult<()> {
+        self.refresh();
+        if self.likes.get(&uin).cloned().unwrap_or_default() + count > DAILY_LIKE_LIMIT {
+            return Err(RQError::QuotaExceeded("like"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn add_like(&mut self, uin: i64, count: u32) {
+        self.refresh();
+        *self.likes.entry(uin).or_default() += count;
+    }
+
+    pub(crate) fn check_at_all(&mut self, group_code: i64) -> RQResult<()> {
+        self.refresh();
+        if self.at_all.get(&group_code).cloned().unwrap_or_default() >= DAILY_AT_ALL_LIMIT {
+            return Err(RQError::QuotaExceeded("at_all"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn add_at_all(&mut self, group_code: i64) {
+        self.refresh();
+        *self.at_all.entry(group_code).or_default() += 1;
+    }
+
+    pub(crate) fn check_gift(&mut self, count: u32) -> RQResult<()> {
+        self.refresh();
+        if self.gifts + count > DAILY_FREE_GIFT_LIMIT {
+            return Err(RQError::QuotaExceeded("gift"));
+        }
+        Ok(())
+    }
+
+    /// remain 为服务端返回的剩余免费礼物数量
+    pub(crate) fn set_gift_remain(&mut self, remain: u32) {
+        self.refresh();
+        self.gifts = DAILY_FREE_GIFT_LIMIT.saturating_sub(remain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gift_quota() {
+        let mut quota = QuotaStatus::default();
+        quota.check_gift(DAILY_FREE_GIFT_LIMIT).unwrap();
+        quota.set_gift_remain(2);
+        assert_eq!(quota.gifts, DAILY_FREE_GIFT_LIMIT - 2);
+        quota.check_gift(2).unwrap();
+        assert!(matches!(
+            quota.check_gift(3),
+            Err(RQError::QuotaExceeded("gift"))
+        ));
+
+        // 跨天后重置
+        quota.day -= 1;
+        quota.check_gift(DAILY_FREE_GIFT_LIMIT).unwrap();
+        assert_eq!(quota.gifts, 0);
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{RQError, RQResult, RateLimitConfig, RateLimitMode};
+
+/// 不受全局发包限制的命令，避免心跳、登录被限流导致掉线
+const UNLIMITED_COMMANDS: &[&str] = &[
+    "Heartbeat.Alive",
+    "trpc.qq_new_tech.status_svc.StatusService.SsoHeartBeat",
+    "StatSvc.register",
+];
+
+struct TokenBucket {
+    capacity: f64,
+    /// 每秒补充的令牌数
+    rate: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    fn new(count: u32, period: Duration) -> Self {
+        Self {
+            capacity: count as f64,
+            rate: count as f64 / period.as_secs_f64(),
+            tokens: count as f64,
+            last: Instant::now(),
+        }
+    }
+
+    /// 取出一个令牌，不足时返回需要等待的时间
+    fn take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+fn packet_bucket(config: &RateLimitConfig) -> Option<TokenBucket> {
+    config
+        .packets_per_second
+        .filter(|n| *n > 0)
+        .map(|n| TokenBucket::new(n, Duration::from_secs(1)))
+}
+
+/// 发包和发消息的令牌桶限流
+pub(crate) struct RateLimiter {
+    config: Mutex<RateLimitConfig>,
+    packets: Mutex<Option<TokenBucket>>,
+    groups: Mutex<HashMap<i64, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimitConfig) -> Self {
+        Self {
+            packets: Mutex::new(packet_bucket(&config)),
+            groups: Default::default(),
+            config: Mutex::new(config),
+        }
+    }
+
+    pub(crate) fn config(&self) -> RateLimitConfig {
+        *self.config.lock().unwrap()
+    }
+
+    /// 替换配置，已有的令牌桶按新配置重新开始计算
+    pub(crate) fn update(&self, config: RateLimitConfig) {
+        *self.packets.lock().unwrap() = packet_bucket(&config);
+        self.groups.lock().unwrap().clear();
+        *self.config.lock().unwrap() = config;
+    }
+
+    async fn acquire<F>(&self, category: &'static str, mut take: F) -> RQResult<()>
+    where
+        F: FnMut() -> Result<(), Duration>,
+    {
+        loop {
+            match take() {
+                Ok(()) => return Ok(()),
+                Err(_) if self.config().mode == RateLimitMode::Reject => {
+                    return Err(RQError::RateLimited(category));
+                }
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    pub(crate) async fn acquire_packet(&self, command_name: &str) -> RQResult<()> {
+        if UNLIMITED_COMMANDS.contains(&command_name) {
+            return Ok(());
+        }
+        // 未限制或运行中取消限制时直接通过
+        self.acquire("packet", || match &mut *self.packets.lock().unwrap() {
+            Some(bucket) => bucket.take(),
+            None => Ok(()),
+        })
+        .await
+    }
+
+    pub(crate) async fn acquire_group_message(&self, group_code: i64) -> RQResult<()> {
+        let Some(count) = self.config().group_messages_per_minute.filter(|n| *n > 0) else {
+            return Ok(());
+        };
+        self.acquire("group_message", || {
+            self.groups
+                .lock()
+                .unwrap()
+                .entry(group_code)
+                .or_insert_with(|| TokenBucket::new(count, Duration::from_secs(60)))
+                .take()
+        })
+        .await
+    }
+}
@@ -0,0 +1,203 @@
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, UNIX_EPOCH};
+
+use cached::Cached;
+use ricq_core::structs::{MessageId, MessageReceipt, MessageTargetKind};
+
+use crate::{RQError, RQResult};
+
+/// 等待撤回的群消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PendingRecall {
+    /// 撤回时间戳（秒）
+    deadline: i64,
+    group_code: i64,
+    seqs: Vec<i32>,
+    rands: Vec<i32>,
+}
+
+impl PendingRecall {
+    fn encode(&self) -> String {
+        let join = |v: &[i32]| v.iter().map(i32::to_string).collect::<Vec<_>>().join(",");
+        format!(
+            "{} {} {} {}",
+            self.deadline,
+            self.group_code,
+            join(&self.seqs),
+            join(&self.rands)
+        )
+    }
+
+    fn decode(line: &str) -> Option<Self> {
+        let split =
+            |s: &str| -> Option<Vec<i32>> { s.split(',').map(|v| v.parse().ok()).collect() };
+        let mut fields = line.split_whitespace();
+        Some(Self {
+            deadline: fields.next()?.parse().ok()?,
+            group_code: fields.next()?.parse().ok()?,
+            seqs: split(fields.next()?)?,
+            rands: split(fields.next()?)?,
+        })
+    }
+}
+
+fn now() -> i64 {
+    UNIX_EPOCH.elapsed().unwrap().as_secs() as i64
+}
+
+/// 最近发送的消息的接收方，用于 recall_recent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SentTarget {
+    Group(i64),
+    Friend(i64),
+}
+
+/// 撤回失败（如掉线中）时的重试次数
+const RECALL_RETRY: usize = 3;
+
+impl super::Client {
+    /// 延时撤回群消息
+    ///
+    /// 设置了 `Config::pending_recall_path` 时会持久化，重启登录后由 `resume_pending_recalls` 继续
+    pub async fn recall_after(
+        self: &Arc<Self>,
+        group_code: i64,
+        receipt: &MessageReceipt,
+        delay: Duration,
+    ) {
+        let recall = PendingRecall {
+            deadline: now() + delay.as_secs() as i64,
+            group_code,
+            seqs: receipt.seqs.clone(),
+            rands: receipt.rands.clone(),
+        };
+        self.pending_recalls.lock().await.push(recall.clone());
+        self.save_pending_recalls().await;
+        self.schedule_recall(recall);
+    }
+
+    /// 从 `Config::pending_recall_path` 加载未完成的延时撤回，已过期的立即撤回，返回加载数量
+    pub async fn resume_pending_recalls(self: &Arc<Self>) -> io::Result<usize> {
+        let Some(path) = &self.pending_recall_path else {
+            return Ok(0);
+        };
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+        let recalls: Vec<_> = text.lines().filter_map(PendingRecall::decode).collect();
+        let mut pending = self.pending_recalls.lock().await;
+        let mut count = 0;
+        for recall in recalls {
+            if !pending.contains(&recall) {
+                pending.push(recall.clone());
+                self.schedule_recall(recall);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// 通过 MessageId 撤回群消息或好友消息，可用于重启后撤回持久化的消息
+    pub async fn recall_message(&self, id: &MessageId) -> RQResult<()> {
+        match id.kind {
+            MessageTargetKind::Group => {
+                self.recall_group_message(id.target, id.seqs.clone(), id.rands.clone())
+                    .await
+            }
+            MessageTargetKind::Friend => {
+                self.recall_friend_message(id.target, id.time, id.seqs.clone(), id.rands.clone())
+                    .await
+            }
+        }
+    }
+
+    /// 撤回最近发送的群消息或好友消息，自动判断消息类型
+    ///
+    /// 需要设置 `Config::sent_message_capacity`，消息不在记录中时返回错误
+    pub async fn recall_recent(&self, receipt: &MessageReceipt) -> RQResult<()> {
+        let target = match (&self.sent_messages, receipt.rands.first()) {
+            (Some(sent), Some(rand)) => sent.lock().await.cache_get(rand).copied(),
+            _ => None,
+        };
+        match target {
+            Some(SentTarget::Group(group_code)) => {
+                self.recall_group_message(group_code, receipt.seqs.clone(), receipt.rands.clone())
+                    .await
+            }
+            Some(SentTarget::Friend(uin)) => {
+                self.recall_friend_message(
+                    uin,
+                    receipt.time,
+                    receipt.seqs.clone(),
+                    receipt.rands.clone(),
+                )
+                .await
+            }
+            None => Err(RQError::Other("message not found in sent records".into())),
+        }
+    }
+
+    /// 记录发送的消息，key 为第一个 rand
+    pub(crate) async fn record_sent_message(&self, target: SentTarget, receipt: &MessageReceipt) {
+        if let (Some(sent), Some(rand)) = (&self.sent_messages, receipt.rands.first()) {
+            sent.lock().await.cache_set(*rand, target);
+        }
+    }
+
+    fn schedule_recall(self: &Arc<Self>, recall: PendingRecall) {
+        let client = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let wait = (recall.deadline - now()).max(0) as u64;
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+            for _ in 0..RECALL_RETRY {
+                // Client 已被丢弃时保留持久化记录，下次启动继续
+                let Some(client) = Weak::upgrade(&client) else {
+                    return;
+                };
+                match client
+                    .recall_group_message(
+                        recall.group_code,
+                        recall.seqs.clone(),
+                        recall.rands.clone(),
+                    )
+                    .await
+                {
+                    Ok(_) => break,
+                    Err(err) => tracing::warn!("failed to recall group message: {}", err),
+                }
+                drop(client);
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            if let Some(client) = Weak::upgrade(&client) {
+                client.pending_recalls.lock().await.retain(|r| r != &recall);
+                client.save_pending_recalls().await;
+            }
+        });
+    }
+
+    async fn save_pending_recalls(&self) {
+        let Some(path) = &self.pending_recall_path else {
+            return;
+        };
+        let text: String = self
+            .pending_recalls
+            .lock()
+            .await
+            .iter()
+            .map(|r| r.encode() + "\n")
+            .collect();
+        if let Err(err) = write_file(path, &text) {
+            tracing::warn!("failed to save pending recalls: {}", err);
+        }
+    }
+}
+
+fn write_file(path: &Path, text: &str) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(tmp, path)
+}
@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use bytes::Bytes;
+use ricq_core::protocol::packet::Packet;
+
+use crate::handler::QEvent;
+
+enum Record {
+    Event(String),
+    Packet {
+        outgoing: bool,
+        command_name: String,
+        seq_id: i32,
+        body: Bytes,
+    },
+}
+
+/// 最近的事件和收发包，用于反馈难以复现的问题
+pub(crate) struct RecentBuffer {
+    capacity: usize,
+    dump_path: Option<PathBuf>,
+    records: Mutex<VecDeque<(u128, Record)>>,
+}
+
+impl RecentBuffer {
+    pub(crate) fn new(config: &crate::RecentBufferConfig) -> Self {
+        Self {
+            capacity: config.capacity,
+            dump_path: config.dump_on_error.clone(),
+            records: Mutex::new(VecDeque::with_capacity(config.capacity)),
+        }
+    }
+
+    fn push(&self, record: Record) {
+        let time = UNIX_EPOCH.elapsed().unwrap_or_default().as_millis();
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back((time, record));
+    }
+
+    pub(crate) fn record_event(&self, event: &QEvent) {
+        if self.capacity > 0 {
+            self.push(Record::Event(format!("{:?}", event)));
+        }
+    }
+
+    pub(crate) fn record_packet(&self, pkt: &Packet, outgoing: bool) {
+        if self.capacity > 0 {
+            self.push(Record::Packet {
+                outgoing,
+                command_name: pkt.command_name.clone(),
+                seq_id: pkt.seq_id,
+                body: pkt.body.clone(),
+            });
+        }
+    }
+
+    pub(crate) fn dump(&self, path: &Path) -> io::Result<()> {
+        let mut text = String::new();
+        for (time, record) in self.records.lock().unwrap().iter() {
+            match record {
+                Record::Event(event) => {
+                    writeln!(text, "[{}] EVENT {}", time, event).ok();
+                }
+                Record::Packet {
+                    outgoing,
+                    command_name,
+                    seq_id,
+                    body,
+                } => {
+                    let direction = if *outgoing { "SEND" } else { "RECV" };
+                    write!(
+                        text,
+                        "[{}] {} {} seq={} ",
+                        time, direction, command_name, seq_id
+                    )
+                    .ok();
+                    for b in body.iter() {
+                        write!(text, "{:02x}", b).ok();
+                    }
+                    text.push('\n');
+                }
+            }
+        }
+        std::fs::File::create(path)?.write_all(text.as_bytes())
+    }
+
+    /// 出错时写入 dump_on_error 指定的文件
+    pub(crate) fn dump_on_error(&self) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(path) = &self.dump_path {
+            match self.dump(path) {
+                Ok(_) => tracing::info!("recent events dumped to {}", path.display()),
+                Err(err) => tracing::warn!("failed to dump recent events: {}", err),
+            }
+        }
+    }
+}
+
+impl crate::Client {
+    /// 把最近的事件和收发包写入文件，需要在 Config::recent_buffer 中设置容量
+    pub fn dump_recent(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.recent.dump(path.as_ref())
+    }
+}
@@ -0,0 +1,51 @@
+//! 运行中修改部分配置，不需要重新连接
+
+use crate::{EventMask, HeartbeatConfig, RQResult, RateLimitConfig, RequestConfig};
+
+impl super::Client {
+    /// 当前的请求超时和重试配置
+    pub fn request_config(&self) -> RequestConfig {
+        *self.request_config.read().unwrap()
+    }
+
+    /// 修改请求超时和重试配置，之后发出的请求生效
+    pub fn set_request_config(&self, config: RequestConfig) -> RQResult<()> {
+        config.validate()?;
+        *self.request_config.write().unwrap() = config;
+        Ok(())
+    }
+
+    /// 当前的限流配置
+    pub fn rate_limit(&self) -> RateLimitConfig {
+        self.rate_limiter.config()
+    }
+
+    /// 修改限流配置，已经在等待的请求按新配置重新计算
+    pub fn set_rate_limit(&self, config: RateLimitConfig) -> RQResult<()> {
+        config.validate()?;
+        self.rate_limiter.update(config);
+        Ok(())
+    }
+
+    /// 当前需要处理的推送类型
+    pub fn event_mask(&self) -> EventMask {
+        *self.event_mask.read().unwrap()
+    }
+
+    /// 修改需要处理的推送类型，之后收到的推送生效
+    pub fn set_event_mask(&self, mask: EventMask) {
+        *self.event_mask.write().unwrap() = mask;
+    }
+
+    /// 当前的心跳配置
+    pub fn heartbeat_config(&self) -> HeartbeatConfig {
+        *self.heartbeat_config.read().unwrap()
+    }
+
+    /// 修改心跳配置，下一次心跳生效
+    pub fn set_heartbeat_config(&self, config: HeartbeatConfig) -> RQResult<()> {
+        config.validate()?;
+        *self.heartbeat_config.write().unwrap() = config;
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use ricq_core::protocol::packet::{Packet, SignResult};
+
+use crate::RQResult;
+
+/// 默认需要签名的命令
+pub const DEFAULT_SIGN_COMMANDS: &[&str] = &[
+    "wtlogin.login",
+    "wtlogin.exchange_emp",
+    "wtlogin.trans_emp",
+    "MessageSvc.PbSendMsg",
+    "trpc.o3.ecdh_access.EcdhAccess.SsoSecureA2Establish",
+];
+
+/// 外部签名服务，Client 发包前对需要签名的命令调用
+#[async_trait]
+pub trait SignProvider: Send + Sync {
+    /// 该命令是否需要签名
+    fn need_sign(&self, command_name: &str) -> bool {
+        DEFAULT_SIGN_COMMANDS.contains(&command_name)
+    }
+
+    async fn sign(&self, uin: i64, cmd: &str, seq: i32, buffer: &[u8]) -> RQResult<SignResult>;
+}
+
+impl super::Client {
+    /// 有 SignProvider 且命令需要签名时填充 pkt.sign，签名失败时按无签名发送
+    pub(crate) async fn sign_packet(&self, mut pkt: Packet) -> Packet {
+        let Some(provider) = &self.sign_provider else {
+            return pkt;
+        };
+        if !provider.need_sign(&pkt.command_name) {
+            return pkt;
+        }
+        let uin = self.uin().await;
+        match provider
+            .sign(uin, &pkt.command_name, pkt.seq_id, &pkt.body)
+            .await
+        {
+            Ok(sign) => pkt.sign = Some(sign),
+            Err(err) => tracing::warn!("failed to sign {}: {}", pkt.command_name, err),
+        }
+        pkt
+    }
+}
+
+/// qsign 服务的 HTTP 实现，需要开启 `web` feature
+#[cfg(feature = "web")]
+pub struct HttpSignProvider {
+    /// 服务地址，如 `http://127.0.0.1:8080`
+    pub url: String,
+    /// 协议版本标识，如 `V1_AND_SQ_8.9.63_4194_YYB_D`
+    pub qua: String,
+    pub android_id: String,
+    /// 设备 guid 的 hex
+    pub guid: String,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "web")]
+impl HttpSignProvider {
+    pub fn new(url: String, qua: String, android_id: String, guid: String) -> Self {
+        Self {
+            url,
+            qua,
+            android_id,
+            guid,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[cfg(feature = "web")]
+#[async_trait]
+impl SignProvider for HttpSignProvider {
+    async fn sign(&self, uin: i64, cmd: &str, seq: i32, buffer: &[u8]) -> RQResult<SignResult> {
+        use ricq_core::hex::{decode_hex, encode_hex};
+
+        use crate::RQError;
+
+        fn from_hex(s: &str) -> RQResult<bytes::Bytes> {
+            if s.len() % 2 != 0 {
+                return Err(RQError::Decode(format!("invalid hex: {s}")));
+            }
+            decode_hex(s)
+                .map(bytes::Bytes::from)
+                .map_err(|e| RQError::Decode(format!("invalid hex: {s}: {e}")))
+        }
+
+        let resp = self
+            .client
+            .get(format!("{}/sign", self.url.trim_end_matches('/')))
+            .query(&[
+                ("uin", uin.to_string()),
+                ("qua", self.qua.clone()),
+                ("cmd", cmd.to_owned()),
+                ("seq", seq.to_string()),
+                ("buffer", encode_hex(buffer)),
+                ("android_id", self.android_id.clone()),
+                ("guid", self.guid.clone()),
+            ])
+            .send()
+            .await
+            .map_err(|e| RQError::Other(format!("sign request failed: {e}")))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(format!("sign request failed: {e}")))?;
+        let resp: serde_json::Value =
+            serde_json::from_str(&resp).map_err(|e| RQError::Decode(e.to_string()))?;
+        if resp["code"].as_i64() != Some(0) {
+            return Err(RQError::Other(format!("sign failed: {}", resp["msg"])));
+        }
+        let data = &resp["data"];
+        let field = |name: &str| from_hex(data[name].as_str().unwrap_or_default());
+        Ok(SignResult {
+            sign: field("sign")?,
+            token: field("token")?,
+            extra: field("extra")?,
+        })
+    }
+}
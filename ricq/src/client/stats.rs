@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+
+use super::recall::SentTarget;
+
+/// 单个群或好友的消息统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActivityStats {
+    pub sent: u64,
+    pub received: u64,
+    /// 发送失败次数
+    pub send_failed: u64,
+    /// 最后一次收发消息的时间戳（秒）
+    pub last_active: i64,
+}
+
+impl ActivityStats {
+    fn merge(&mut self, other: &ActivityStats) {
+        self.sent += other.sent;
+        self.received += other.received;
+        self.send_failed += other.send_failed;
+        self.last_active = self.last_active.max(other.last_active);
+    }
+}
+
+/// 消息收发统计，需要开启 `Config::enable_stats`
+#[derive(Debug, Clone, Default)]
+pub struct MessageStats {
+    /// <group_code, stats>
+    pub groups: HashMap<i64, ActivityStats>,
+    /// <uin, stats>
+    pub friends: HashMap<i64, ActivityStats>,
+    /// 开始统计的时间戳（秒），reset_stats 后重新计算
+    pub since: i64,
+}
+
+impl MessageStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            since: now(),
+            ..Default::default()
+        }
+    }
+
+    /// 所有群和好友的合计
+    pub fn total(&self) -> ActivityStats {
+        let mut total = ActivityStats::default();
+        for stats in self.groups.values().chain(self.friends.values()) {
+            total.merge(stats);
+        }
+        total
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Activity {
+    Sent,
+    Received,
+    SendFailed,
+}
+
+fn now() -> i64 {
+    UNIX_EPOCH.elapsed().unwrap().as_secs() as i64
+}
+
+fn record(stats: &mut ActivityStats, activity: Activity) {
+    match activity {
+        Activity::Sent => stats.sent += 1,
+        Activity::Received => stats.received += 1,
+        Activity::SendFailed => stats.send_failed += 1,
+    }
+    stats.last_active = now();
+}
+
+impl super::Client {
+    /// 当前的消息统计，未开启 `Config::enable_stats` 时返回 None
+    pub fn stats(&self) -> Option<MessageStats> {
+        self.stats.as_ref().map(|s| s.lock().unwrap().clone())
+    }
+
+    /// 清空消息统计
+    pub fn reset_stats(&self) {
+        if let Some(stats) = &self.stats {
+            *stats.lock().unwrap() = MessageStats::new();
+        }
+    }
+
+    pub(crate) fn record_send_result(&self, target: SentTarget, success: bool) {
+        let activity = match success {
+            true => Activity::Sent,
+            false => Activity::SendFailed,
+        };
+        match target {
+            SentTarget::Group(group_code) => self.record_group_activity(group_code, activity),
+            SentTarget::Friend(uin) => self.record_friend_activity(uin, activity),
+        }
+    }
+
+    pub(crate) fn record_group_activity(&self, group_code: i64, activity: Activity) {
+        if let Some(stats) = &self.stats {
+            let mut stats = stats.lock().unwrap();
+            record(stats.groups.entry(group_code).or_default(), activity);
+        }
+    }
+
+    pub(crate) fn record_friend_activity(&self, uin: i64, activity: Activity) {
+        if let Some(stats) = &self.stats {
+            let mut stats = stats.lock().unwrap();
+            record(stats.friends.entry(uin).or_default(), activity);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! 会话数据持久化
+//!
+//! Client 在登录、换 token、同步消息、收到 highway 配置后把 token、同步 cookie、highway 会话写入 [`Store`]，
+//! 进程重启后通过 `Client::restore_session` 恢复，再使用 token_login 登录。
+//! 设备信息需要在创建 Client 之前确定，使用 [`load_device`] / [`save_device`]。
+//!
+//! 内置 [`FileStore`]（每个 key 一个文件）和 [`MemoryStore`]，也可以自行实现 Store 接入 Redis、sqlite 等。
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use ricq_core::protocol::device::Device;
+use ricq_core::Token;
+
+use crate::{RQError, RQResult};
+
+/// token（JSON）
+pub const TOKEN_KEY: &str = "token";
+/// 设备信息（JSON）
+pub const DEVICE_KEY: &str = "device";
+/// 消息同步 cookie
+pub const SYNC_COOKIE_KEY: &str = "sync_cookie";
+/// 公众号消息同步 cookie
+pub const PUB_ACCOUNT_COOKIE_KEY: &str = "pub_account_cookie";
+/// highway 会话（sig_session 和 session_key）
+pub const HIGHWAY_SESSION_KEY: &str = "highway_session";
+
+/// 键值存储，key 为上面的 `*_KEY` 常量
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn get(&self, key: &str) -> RQResult<Option<Vec<u8>>>;
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> RQResult<()>;
+}
+
+/// 保存在内存中，用于测试或由外部自行导出
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    data: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn get(&self, key: &str) -> RQResult<Option<Vec<u8>>> {
+        Ok(self.data.lock().unwrap().get(key).cloned())
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> RQResult<()> {
+        self.data.lock().unwrap().insert(key.to_string(), value);
+        Ok(())
+    }
+}
+
+/// 每个 key 保存为目录下的一个文件，先写临时文件再重命名，进程崩溃时不会留下写了一半的文件
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+}
+
+#[async_trait]
+impl Store for FileStore {
+    async fn get(&self, key: &str) -> RQResult<Option<Vec<u8>>> {
+        let path = self.path(key);
+        blocking(move || match std::fs::read(path) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        })
+        .await
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> RQResult<()> {
+        let dir = self.dir.clone();
+        let path = self.path(key);
+        blocking(move || {
+            std::fs::create_dir_all(dir)?;
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, value)?;
+            std::fs::rename(tmp, path)
+        })
+        .await
+    }
+}
+
+/// 在阻塞线程池中执行文件操作
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> RQResult<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| RQError::Other(format!("store task failed: {e}")))?
+        .map_err(RQError::from)
+}
+
+fn json_error(e: serde_json::Error) -> RQError {
+    RQError::Decode(e.to_string())
+}
+
+/// 读取保存的设备信息
+pub async fn load_device(store: &dyn Store) -> RQResult<Option<Device>> {
+    store
+        .get(DEVICE_KEY)
+        .await?
+        .map(|data| serde_json::from_slice(&data).map_err(json_error))
+        .transpose()
+}
+
+/// 保存设备信息，之后的登录应使用同一设备
+pub async fn save_device(store: &dyn Store, device: &Device) -> RQResult<()> {
+    let data = serde_json::to_vec(device).map_err(json_error)?;
+    store.put(DEVICE_KEY, data).await
+}
+
+/// highway 会话：sig_session 长度（u32 大端） + sig_session + session_key
+fn encode_highway_session(sig_session: &[u8], session_key: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(4 + sig_session.len() + session_key.len());
+    data.extend_from_slice(&(sig_session.len() as u32).to_be_bytes());
+    data.extend_from_slice(sig_session);
+    data.extend_from_slice(session_key);
+    data
+}
+
+fn decode_highway_session(data: &[u8]) -> RQResult<(Bytes, Bytes)> {
+    let invalid = || RQError::Decode("invalid highway session".into());
+    let len = data.get(..4).ok_or_else(invalid)?;
+    let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+    let sig_session = data.get(4..4 + len).ok_or_else(invalid)?;
+    let session_key = &data[4 + len..];
+    Ok((
+        Bytes::copy_from_slice(sig_session),
+        Bytes::copy_from_slice(session_key),
+    ))
+}
+
+impl super::Client {
+    async fn persist(&self, key: &str, value: Vec<u8>) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if let Err(err) = store.put(key, value).await {
+            tracing::warn!("failed to persist {}: {}", key, err);
+        }
+    }
+
+    /// 保存当前 token
+    pub(crate) async fn persist_token(&self) {
+        if self.store.is_none() {
+            return;
+        }
+        match serde_json::to_vec(&self.gen_token().await) {
+            Ok(data) => self.persist(TOKEN_KEY, data).await,
+            Err(err) => tracing::warn!("failed to encode token: {}", err),
+        }
+    }
+
+    /// 保存消息同步 cookie，和上次保存的相同时跳过
+    pub(crate) async fn persist_sync_cookies(&self) {
+        if self.store.is_none() {
+            return;
+        }
+        let (sync_cookie, pub_account_cookie) = {
+            let engine = self.engine.read().await;
+            (
+                engine.transport.sig.sync_cookie.clone(),
+                engine.transport.sig.pub_account_cookie.clone(),
+            )
+        };
+        let (sync_changed, pub_account_changed) = {
+            let mut persisted = self.persisted_sync_cookies.lock().unwrap();
+            let changed = (
+                persisted.0 != sync_cookie,
+                persisted.1 != pub_account_cookie,
+            );
+            *persisted = (sync_cookie.clone(), pub_account_cookie.clone());
+            changed
+        };
+        if sync_changed {
+            self.persist(SYNC_COOKIE_KEY, sync_cookie.to_vec()).await;
+        }
+        if pub_account_changed {
+            self.persist(PUB_ACCOUNT_COOKIE_KEY, pub_account_cookie.to_vec())
+                .await;
+        }
+    }
+
+    /// 保存 highway 会话
+    pub(crate) async fn persist_highway_session(&self) {
+        if self.store.is_none() {
+            return;
+        }
+        let data = {
+            let session = self.highway_session.read().await;
+            encode_highway_session(&session.sig_session, &session.session_key)
+        };
+        self.persist(HIGHWAY_SESSION_KEY, data).await;
+    }
+
+    /// 立即保存 token、同步 cookie 和 highway 会话，未配置 `Config::store` 时不做任何操作
+    pub async fn save_session(&self) {
+        self.persist_token().await;
+        self.persist_sync_cookies().await;
+        self.persist_highway_session().await;
+    }
+
+    /// 从 `Config::store` 恢复同步 cookie 和 highway 会话，返回保存的 token，之后使用 token_login 登录
+    ///
+    /// 未配置 store 或没有保存过 token 时返回 None
+    pub async fn restore_session(&self) -> RQResult<Option<Token>> {
+        let Some(store) = &self.store else {
+            return Ok(None);
+        };
+        let Some(token) = store.get(TOKEN_KEY).await? else {
+            return Ok(None);
+        };
+        let token: Token = serde_json::from_slice(&token).map_err(json_error)?;
+        let sync_cookie = store.get(SYNC_COOKIE_KEY).await?.map(Bytes::from);
+        let pub_account_cookie = store.get(PUB_ACCOUNT_COOKIE_KEY).await?.map(Bytes::from);
+        {
+            let mut engine = self.engine.write().await;
+            let mut persisted = self.persisted_sync_cookies.lock().unwrap();
+            if let Some(cookie) = sync_cookie {
+                engine.transport.sig.sync_cookie = cookie.clone();
+                persisted.0 = cookie;
+            }
+            if let Some(cookie) = pub_account_cookie {
+                engine.transport.sig.pub_account_cookie = cookie.clone();
+                persisted.1 = cookie;
+            }
+        }
+        if let Some(data) = store.get(HIGHWAY_SESSION_KEY).await? {
+            let (sig_session, session_key) = decode_highway_session(&data)?;
+            let mut session = self.highway_session.write().await;
+            session.sig_session = sig_session;
+            session.session_key = session_key;
+        }
+        Ok(Some(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highway_session() {
+        let data = encode_highway_session(b"sig", b"key");
+        let (sig_session, session_key) = decode_highway_session(&data).unwrap();
+        assert_eq!(&sig_session[..], b"sig");
+        assert_eq!(&session_key[..], b"key");
+        assert!(decode_highway_session(&data[..5]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_device() {
+        let store = MemoryStore::new();
+        assert!(load_device(&store).await.unwrap().is_none());
+        let device = Device::random();
+        save_device(&store, &device).await.unwrap();
+        let loaded = load_device(&store).await.unwrap().unwrap();
+        assert_eq!(loaded.imei, device.imei);
+    }
+
+    #[tokio::test]
+    async fn test_file_store() {
+        let dir = std::env::temp_dir().join(format!("ricq-store-{}", std::process::id()));
+        let store = FileStore::new(&dir);
+        assert!(store.get(TOKEN_KEY).await.unwrap().is_none());
+        store.put(TOKEN_KEY, b"token".to_vec()).await.unwrap();
+        store.put(TOKEN_KEY, b"token2".to_vec()).await.unwrap();
+        assert_eq!(store.get(TOKEN_KEY).await.unwrap().unwrap(), b"token2");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
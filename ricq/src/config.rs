@@ -1,4 +1,17 @@
+use std::collections::HashSet;
 use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ricq_core::command::profile_service::SelfInvited;
+
+use crate::client::metrics::Metrics;
+use crate::client::observer::PacketObserver;
+use crate::client::sign::SignProvider;
+use crate::client::store::Store;
+use crate::i18n::Locale;
+use crate::{RQError, RQResult};
 
 use ricq_core::protocol::{
     device::Device,
@@ -6,10 +19,272 @@ use ricq_core::protocol::{
     version::{get_version, Protocol},
 };
 
-#[derive(Debug)]
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
 pub struct Config {
     pub device: Device,
     pub version: Version,
+    /// 以隐身状态登录，好友不会看到上线
+    pub login_invisible: bool,
+    /// 好友、群、群成员信息缓存配置
+    pub cache: CacheConfig,
+    /// 好友申请自动处理策略
+    pub friend_request_policy: FriendRequestPolicy,
+    /// 群邀请自动处理策略
+    pub group_invite_policy: GroupInvitePolicy,
+    /// handler panic 后的处理方式
+    pub handler_panic_strategy: SupervisionStrategy,
+    /// 最近事件和收发包的记录，用于 Client::dump_recent
+    pub recent_buffer: RecentBufferConfig,
+    /// crate 生成的错误信息、回复模板使用的语言
+    pub locale: Locale,
+    /// 外部签名服务，见 [`crate::client::sign`]
+    #[derivative(Debug = "ignore")]
+    pub sign_provider: Option<Arc<dyn SignProvider>>,
+    /// recall_after 延时撤回的持久化文件，None 表示不持久化
+    pub pending_recall_path: Option<PathBuf>,
+    /// 上传过的图片信息的持久化文件，用于 try_get_group_image 等跳过重复上传，None 表示只在内存中记录
+    pub image_cache_path: Option<PathBuf>,
+    /// 记录最近发送的多少条消息，用于 Client::recall_recent，0 表示不记录
+    pub sent_message_capacity: usize,
+    /// 心跳配置
+    pub heartbeat: HeartbeatConfig,
+    /// 定时换 token（wtlogin.exchange_emp）的间隔，避免长时间运行后 D2 过期，None 表示不自动刷新
+    pub token_refresh_interval: Option<Duration>,
+    /// send_and_wait 的超时和重试配置
+    pub request: RequestConfig,
+    /// 发包、发消息限流，默认不限制
+    pub rate_limit: RateLimitConfig,
+    /// 运行指标回调，见 [`crate::client::metrics`]
+    #[derivative(Debug = "ignore")]
+    pub metrics: Option<Arc<dyn Metrics>>,
+    /// 需要处理的推送类型，默认全部处理
+    pub event_mask: EventMask,
+    /// 群消息去重时间窗口（秒），窗口内 (seq, rand, time) 相同的重复推送只处理一次，0 表示不去重
+    pub message_dedup_window: u64,
+    /// `QEvent::Unhandled` 是否附带包内容
+    pub report_unhandled_body: bool,
+    /// 统计每个群、好友的消息收发数量，见 Client::stats
+    pub enable_stats: bool,
+    /// 收发包回调，见 [`crate::client::observer`]
+    #[derivative(Debug = "ignore")]
+    pub packet_observer: Option<Arc<dyn PacketObserver>>,
+    /// token、同步 cookie 等会话数据的持久化，见 [`crate::client::store`]
+    #[derivative(Debug = "ignore")]
+    pub store: Option<Arc<dyn Store>>,
+}
+
+/// 心跳包类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeartbeatKind {
+    /// Heartbeat.Alive
+    #[default]
+    Alive,
+    /// StatusService.SsoHeartBeat
+    Sso,
+}
+
+/// 心跳配置
+#[derive(Debug, Clone, Copy)]
+pub struct HeartbeatConfig {
+    pub interval: Duration,
+    pub kind: HeartbeatKind,
+    /// 连续多少次心跳无响应后断开连接（按网络掉线处理，可以由 auto_reconnect 重连），0 表示不检测
+    pub max_missed: usize,
+}
+
+impl HeartbeatConfig {
+    pub fn validate(&self) -> RQResult<()> {
+        if self.interval.is_zero() {
+            return Err(invalid("heartbeat.interval must be greater than 0"));
+        }
+        Ok(())
+    }
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            kind: HeartbeatKind::Alive,
+            max_missed: 3,
+        }
+    }
+}
+
+/// 最近事件和收发包的记录配置，默认不记录
+#[derive(Debug, Clone, Default)]
+pub struct RecentBufferConfig {
+    /// 最多保存的记录条数，0 表示不记录
+    pub capacity: usize,
+    /// handler panic 或收到无法解析的包时自动写入该文件
+    pub dump_on_error: Option<PathBuf>,
+}
+
+/// 请求超时和重试配置，也可以通过 Client::send_and_wait_with 单独指定
+///
+/// 重试会重发同一个包，发消息等非幂等请求超时后服务端可能已经处理，开启重试可能导致重复发送
+#[derive(Debug, Clone, Copy)]
+pub struct RequestConfig {
+    /// 等待返回包的超时时间
+    pub timeout: Duration,
+    /// 超时后的重试次数，0 表示不重试
+    pub retries: usize,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub backoff: Duration,
+}
+
+impl RequestConfig {
+    pub fn validate(&self) -> RQResult<()> {
+        if self.timeout.is_zero() {
+            return Err(invalid("request.timeout must be greater than 0"));
+        }
+        Ok(())
+    }
+}
+
+impl Default for RequestConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(15),
+            retries: 0,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// 超出限流时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// 等待到有剩余额度后发送
+    #[default]
+    Wait,
+    /// 立即返回 `Err(RQError::RateLimited)`
+    Reject,
+}
+
+/// 令牌桶限流配置，避免发送过快触发服务端禁言、冻结
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitConfig {
+    /// 全局每秒发包数，心跳和注册包不受限制，None 表示不限制
+    pub packets_per_second: Option<u32>,
+    /// 每个群每分钟发送的消息数，None 表示不限制
+    pub group_messages_per_minute: Option<u32>,
+    pub mode: RateLimitMode,
+}
+
+impl RateLimitConfig {
+    pub fn validate(&self) -> RQResult<()> {
+        if self.packets_per_second == Some(0) {
+            return Err(invalid(
+                "rate_limit.packets_per_second must be greater than 0, use None to disable",
+            ));
+        }
+        if self.group_messages_per_minute == Some(0) {
+            return Err(invalid(
+                "rate_limit.group_messages_per_minute must be greater than 0, use None to disable",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 需要解码处理的推送类型，未包含的推送不解码、不产生事件，只计入 `Client::ignored_packets`
+///
+/// 登录状态、下线等推送总是会处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventMask(u32);
+
+impl EventMask {
+    /// 群消息
+    pub const GROUP_MESSAGE: Self = Self(1);
+    /// 好友消息、临时会话、好友和加群申请，以及其他设备发出的私聊消息
+    pub const C2C_MESSAGE: Self = Self(1 << 1);
+    /// 群通知（撤回、禁言、戳一戳、改名、退群、权限变更等）和好友通知
+    pub const NOTICE: Self = Self(1 << 2);
+    pub const ALL: Self = Self(u32::MAX);
+    pub const NONE: Self = Self(0);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for EventMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl std::ops::BitOr for EventMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// 信息缓存配置
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// 缓存有效期（秒），过期后下次访问时重新拉取
+    pub ttl: u64,
+    /// 每类缓存最多保存的条目数
+    pub capacity: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: 600,
+            capacity: 10000,
+        }
+    }
+}
+
+/// 好友申请自动处理策略，默认全部交给 handler 处理
+///
+/// 自动处理后仍会产生 NewFriendRequest 事件，处理结果见 `NewFriendRequest::decision`
+#[derive(Debug, Clone, Default)]
+pub struct FriendRequestPolicy {
+    /// 自动同意所有申请
+    pub accept_all: bool,
+    /// 自动同意通过这些群发起的申请
+    pub accept_groups: HashSet<i64>,
+    /// 未被自动同意的申请使用此理由自动拒绝，None 表示交给 handler 处理
+    pub reject_reason: Option<String>,
+}
+
+/// 自定义群邀请处理逻辑，返回 Some(true) 同意，Some(false) 拒绝，None 交给其他规则
+pub type GroupInviteHook = Arc<dyn Fn(&SelfInvited) -> Option<bool> + Send + Sync>;
+
+/// 群邀请自动处理策略，默认全部交给 handler 处理
+///
+/// 自动处理后仍会产生 SelfInvited 事件，处理结果见 `SelfInvited::decision`
+#[derive(Clone, Default, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct GroupInvitePolicy {
+    /// 自动同意这些用户（如 bot 管理员）的邀请
+    pub accept_from: HashSet<i64>,
+    /// 群成员数不少于该值时自动同意
+    pub min_member_count: Option<u16>,
+    /// 未被自动同意的邀请全部拒绝
+    pub reject_others: bool,
+    /// 优先于上述规则执行
+    #[derivative(Debug = "ignore")]
+    pub hook: Option<GroupInviteHook>,
+}
+
+/// handler panic 后的处理方式，无论哪种方式都会先产生 HandlerError 事件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SupervisionStrategy {
+    /// 记录日志，继续处理后续事件
+    #[default]
+    Ignore,
+    /// 断开连接，start 返回后按网络掉线处理，可以由 auto_reconnect 重连
+    Restart,
+    /// 停止客户端，不会自动重连
+    Stop,
 }
 
 impl Default for Config {
@@ -17,12 +292,212 @@ impl Default for Config {
         Self {
             device: Device::random(),
             version: get_version(Protocol::IPad),
+            login_invisible: false,
+            cache: Default::default(),
+            friend_request_policy: Default::default(),
+            group_invite_policy: Default::default(),
+            handler_panic_strategy: Default::default(),
+            recent_buffer: Default::default(),
+            locale: Default::default(),
+            sign_provider: None,
+            pending_recall_path: None,
+            image_cache_path: None,
+            sent_message_capacity: 0,
+            heartbeat: Default::default(),
+            token_refresh_interval: None,
+            request: Default::default(),
+            rate_limit: Default::default(),
+            event_mask: Default::default(),
+            message_dedup_window: 60,
+            report_unhandled_body: false,
+            enable_stats: false,
+            metrics: None,
+            packet_observer: None,
+            store: None,
         }
     }
 }
 
 impl Config {
     pub fn new(device: Device, version: Version) -> Self {
-        Self { device, version }
+        Self {
+            device,
+            version,
+            ..Default::default()
+        }
+    }
+}
+
+/// 支持扫码登录的协议
+const QRCODE_PROTOCOLS: [Protocol; 2] = [Protocol::AndroidWatch, Protocol::MacOS];
+
+fn invalid(reason: impl Into<String>) -> RQError {
+    RQError::InvalidConfig(reason.into())
+}
+
+impl Config {
+    /// 使用 builder 构建配置，build 时检查配置是否有效
+    ///
+    /// ```ignore
+    /// let config = Config::builder()
+    ///     .device(device)
+    ///     .protocol(Protocol::AndroidWatch)
+    ///     .qrcode_login()
+    ///     .build()?;
+    /// ```
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// 检查配置是否有效，[`ConfigBuilder::build`] 会自动调用
+    pub fn validate(&self) -> RQResult<()> {
+        self.heartbeat.validate()?;
+        self.request.validate()?;
+        self.rate_limit.validate()?;
+        if self.cache.capacity == 0 {
+            return Err(invalid("cache.capacity must be greater than 0"));
+        }
+        if self.recent_buffer.dump_on_error.is_some() && self.recent_buffer.capacity == 0 {
+            return Err(invalid(
+                "recent_buffer.dump_on_error requires recent_buffer.capacity > 0",
+            ));
+        }
+        if self.token_refresh_interval.is_some_and(|i| i.is_zero()) {
+            return Err(invalid(
+                "token_refresh_interval must be greater than 0, use None to disable",
+            ));
+        }
+        Ok(())
+    }
+}
+
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+/// [`Config`] 的 builder，未设置的字段使用默认值，build 时检查配置
+#[derive(Debug, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+    qrcode_login: bool,
+}
+
+impl ConfigBuilder {
+    setters! {
+        device: Device,
+        version: Version,
+        login_invisible: bool,
+        cache: CacheConfig,
+        friend_request_policy: FriendRequestPolicy,
+        group_invite_policy: GroupInvitePolicy,
+        handler_panic_strategy: SupervisionStrategy,
+        recent_buffer: RecentBufferConfig,
+        locale: Locale,
+        sent_message_capacity: usize,
+        heartbeat: HeartbeatConfig,
+        request: RequestConfig,
+        rate_limit: RateLimitConfig,
+        event_mask: EventMask,
+        message_dedup_window: u64,
+        report_unhandled_body: bool,
+        enable_stats: bool,
+    }
+
+    /// 使用协议的默认版本信息
+    pub fn protocol(self, protocol: Protocol) -> Self {
+        self.version(get_version(protocol))
+    }
+
+    pub fn sign_provider(mut self, provider: Arc<dyn SignProvider>) -> Self {
+        self.config.sign_provider = Some(provider);
+        self
+    }
+
+    pub fn pending_recall_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.pending_recall_path = Some(path.into());
+        self
+    }
+
+    pub fn image_cache_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.image_cache_path = Some(path.into());
+        self
+    }
+
+    pub fn token_refresh_interval(mut self, interval: Duration) -> Self {
+        self.config.token_refresh_interval = Some(interval);
+        self
+    }
+
+    pub fn metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.config.metrics = Some(metrics);
+        self
+    }
+
+    pub fn packet_observer(mut self, observer: Arc<dyn PacketObserver>) -> Self {
+        self.config.packet_observer = Some(observer);
+        self
+    }
+
+    pub fn store(mut self, store: Arc<dyn Store>) -> Self {
+        self.config.store = Some(store);
+        self
+    }
+
+    /// 将使用扫码登录，build 时检查协议是否支持（AndroidWatch、MacOS）
+    pub fn qrcode_login(mut self) -> Self {
+        self.qrcode_login = true;
+        self
+    }
+
+    pub fn build(self) -> RQResult<Config> {
+        let protocol = self.config.version.protocol;
+        if self.qrcode_login && !QRCODE_PROTOCOLS.contains(&protocol) {
+            return Err(invalid(format!(
+                "qrcode login is not supported by {protocol:?}, use AndroidWatch or MacOS"
+            )));
+        }
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_validate() {
+        let config = Config::builder()
+            .protocol(Protocol::AndroidWatch)
+            .qrcode_login()
+            .build()
+            .unwrap();
+        assert_eq!(config.version.protocol, Protocol::AndroidWatch);
+        assert!(Config::builder()
+            .protocol(Protocol::IPad)
+            .qrcode_login()
+            .build()
+            .is_err());
+        assert!(Config::builder()
+            .heartbeat(HeartbeatConfig {
+                interval: Duration::ZERO,
+                ..Default::default()
+            })
+            .build()
+            .is_err());
+        assert!(Config::builder()
+            .rate_limit(RateLimitConfig {
+                packets_per_second: Some(0),
+                ..Default::default()
+            })
+            .build()
+            .is_err());
     }
 }
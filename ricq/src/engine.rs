@@ -0,0 +1,10 @@
+//! 协议编解码，不依赖 Client 和 tokio
+//!
+//! 用于在 ricq-core 之上实现测试服务器、回放工具或其他传输层（QUIC、中继等），见 [`Engine`]
+
+pub use ricq_core::command;
+pub use ricq_core::jce;
+pub use ricq_core::pb;
+pub use ricq_core::protocol::packet::{EncryptType, Packet, PacketType, SignResult};
+pub use ricq_core::protocol::transport::Transport;
+pub use ricq_core::{Engine, Token};
@@ -0,0 +1,198 @@
+//! 语音格式转换，需要开启 `audio-codec` feature
+//!
+//! 上传：wav/mp3 等常见格式 -> silk，下载：silk -> wav
+
+use std::io::Cursor;
+use std::time::Duration;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use ricq_core::structs::{FriendAudio, GroupAudio};
+use ricq_core::{RQError, RQResult};
+
+use crate::Client;
+
+/// silk 编码使用的采样率
+const SILK_SAMPLE_RATE: u32 = 24000;
+/// silk 编码使用的码率
+const SILK_BIT_RATE: i32 = 24000;
+
+fn codec_error(e: impl std::fmt::Display) -> RQError {
+    RQError::Other(format!("audio codec error: {e}"))
+}
+
+/// 解码为单声道 16bit PCM，返回 (samples, sample_rate)
+fn decode_pcm(data: &[u8]) -> RQResult<(Vec<i16>, u32)> {
+    let source = MediaSourceStream::new(Box::new(Cursor::new(data.to_vec())), Default::default());
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &Hint::new(),
+            source,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(codec_error)?
+        .format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| codec_error("no audio track"))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| codec_error("unknown sample rate"))?;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(codec_error)?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(codec_error(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = decoder.decode(&packet).map_err(codec_error)?;
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buf = SampleBuffer::<i16>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+        // 多声道取平均值转为单声道
+        samples.extend(buf.samples().chunks(channels).map(|frame| {
+            (frame.iter().map(|&s| s as i32).sum::<i32>() / frame.len() as i32) as i16
+        }));
+    }
+    Ok((samples, sample_rate))
+}
+
+/// 线性插值重采样
+fn resample(samples: &[i16], from: u32, to: u32) -> Vec<i16> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let len = (samples.len() as u64 * to as u64 / from as u64) as usize;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * from as f64 / to as f64;
+            let index = pos as usize;
+            let a = samples[index.min(samples.len() - 1)] as f64;
+            let b = samples[(index + 1).min(samples.len() - 1)] as f64;
+            (a + (b - a) * pos.fract()) as i16
+        })
+        .collect()
+}
+
+/// 将 wav、mp3 等格式的音频转换为 silk，返回 (silk 数据, 时长)
+pub fn encode_silk(data: &[u8]) -> RQResult<(Vec<u8>, Duration)> {
+    let (samples, sample_rate) = decode_pcm(data)?;
+    let samples = resample(&samples, sample_rate, SILK_SAMPLE_RATE);
+    let duration = Duration::from_secs_f64(samples.len() as f64 / SILK_SAMPLE_RATE as f64);
+    let pcm: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    let silk = silk_rs::encode_silk(pcm, SILK_SAMPLE_RATE as i32, SILK_BIT_RATE, true)
+        .map_err(|e| codec_error(format!("{e:?}")))?;
+    Ok((silk, duration))
+}
+
+/// 将收到的 silk 语音转换为 wav
+pub fn decode_silk_to_wav(data: &[u8]) -> RQResult<Vec<u8>> {
+    let pcm = silk_rs::decode_silk(data, SILK_SAMPLE_RATE as i32)
+        .map_err(|e| codec_error(format!("{e:?}")))?;
+    Ok(wav(&pcm, SILK_SAMPLE_RATE))
+}
+
+/// 单声道 16bit PCM 加上 wav 文件头
+fn wav(pcm: &[u8], sample_rate: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(44 + pcm.len());
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + pcm.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(pcm.len() as u32).to_le_bytes());
+    out.extend_from_slice(pcm);
+    out
+}
+
+/// 在阻塞线程池中转换为 silk，解码、重采样和编码都比较耗时，不能占用异步运行时
+async fn encode_silk_blocking(data: Vec<u8>) -> RQResult<(Vec<u8>, Duration)> {
+    tokio::task::spawn_blocking(move || encode_silk(&data))
+        .await
+        .map_err(|e| codec_error(format!("encode task failed: {e}")))?
+}
+
+/// 转换为 silk 后上传群语音
+pub async fn upload_group_audio_file(
+    cli: &Client,
+    group_code: i64,
+    data: &[u8],
+) -> RQResult<GroupAudio> {
+    let (silk, _) = encode_silk_blocking(data.to_vec()).await?;
+    cli.upload_group_audio(group_code, silk, 1).await
+}
+
+/// 转换为 silk 后上传好友语音
+pub async fn upload_friend_audio_file(
+    cli: &Client,
+    target: i64,
+    data: &[u8],
+) -> RQResult<FriendAudio> {
+    let (silk, duration) = encode_silk_blocking(data.to_vec()).await?;
+    cli.upload_friend_audio(target, silk, duration).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample() {
+        let samples = [0, 100, 200, 300];
+        assert_eq!(resample(&samples, 24000, 24000), samples);
+        assert!(resample(&[], 8000, 24000).is_empty());
+        assert_eq!(
+            resample(&samples, 8000, 16000),
+            [0, 50, 100, 150, 200, 250, 300, 300]
+        );
+        assert_eq!(resample(&samples, 16000, 8000), [0, 200]);
+        assert_eq!(resample(&[i16::MIN, i16::MAX], 1, 1), [i16::MIN, i16::MAX]);
+    }
+
+    #[test]
+    fn test_wav() {
+        let pcm = [1u8, 0, 2, 0];
+        let data = wav(&pcm, SILK_SAMPLE_RATE);
+        assert_eq!(data.len(), 44 + pcm.len());
+        assert_eq!(&data[..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(data[4..8].try_into().unwrap()), 40);
+        assert_eq!(&data[8..16], b"WAVEfmt ");
+        assert_eq!(u16::from_le_bytes(data[22..24].try_into().unwrap()), 1);
+        assert_eq!(
+            u32::from_le_bytes(data[24..28].try_into().unwrap()),
+            SILK_SAMPLE_RATE
+        );
+        assert_eq!(
+            u32::from_le_bytes(data[28..32].try_into().unwrap()),
+            SILK_SAMPLE_RATE * 2
+        );
+        assert_eq!(&data[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(data[40..44].try_into().unwrap()), 4);
+        assert_eq!(&data[44..], pcm);
+    }
+}
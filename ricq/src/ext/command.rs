@@ -0,0 +1,418 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ricq_core::msg::elem::{FriendImage, GroupImage, RQElem, Text};
+use ricq_core::msg::MessageChain;
+use ricq_core::structs::{GroupMemberPermission, MessageReceipt};
+
+use crate::client::event::{FriendMessageEvent, GroupMessageEvent};
+use crate::handler::{ChainHandler, Handler, Propagation, QEvent};
+use crate::i18n::{Locale, Template};
+use crate::{Client, RQResult};
+
+/// 命令参数
+#[derive(Debug, Clone)]
+pub enum Arg {
+    /// 以空白分隔的文本，双引号内的空白不分隔，引号内可以用 `\"` 和 `\\` 转义
+    Text(String),
+    At(i64),
+    GroupImage(GroupImage),
+    FriendImage(FriendImage),
+}
+
+impl Arg {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Arg::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_at(&self) -> Option<i64> {
+        match self {
+            Arg::At(target) => Some(*target),
+            _ => None,
+        }
+    }
+}
+
+/// 触发命令的消息
+#[derive(Debug, Clone)]
+pub enum CommandSource {
+    Group(GroupMessageEvent),
+    Friend(FriendMessageEvent),
+}
+
+impl CommandSource {
+    pub fn sender(&self) -> i64 {
+        match self {
+            CommandSource::Group(e) => e.inner.from_uin,
+            CommandSource::Friend(e) => e.inner.from_uin,
+        }
+    }
+
+    pub fn group_code(&self) -> Option<i64> {
+        match self {
+            CommandSource::Group(e) => Some(e.inner.group_code),
+            CommandSource::Friend(_) => None,
+        }
+    }
+
+    pub fn client(&self) -> &Arc<Client> {
+        match self {
+            CommandSource::Group(e) => &e.client,
+            CommandSource::Friend(e) => &e.client,
+        }
+    }
+
+    pub fn elements(&self) -> &MessageChain {
+        match self {
+            CommandSource::Group(e) => &e.inner.elements,
+            CommandSource::Friend(e) => &e.inner.elements,
+        }
+    }
+
+    /// 引用回复触发命令的消息
+    pub async fn reply(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        match self {
+            CommandSource::Group(e) => e.reply(message_chain).await,
+            CommandSource::Friend(e) => e.reply(message_chain).await,
+        }
+    }
+}
+
+/// 命令执行上下文
+#[derive(Debug, Clone)]
+pub struct CommandContext {
+    /// 命令名（不含前缀）
+    pub name: String,
+    pub args: Vec<Arg>,
+    pub source: CommandSource,
+}
+
+#[async_trait]
+pub trait Command: Send + Sync {
+    async fn execute(&self, ctx: CommandContext) -> RQResult<()>;
+}
+
+#[async_trait]
+impl<F, Fut> Command for F
+where
+    F: Fn(CommandContext) -> Fut + Send + Sync,
+    Fut: Future<Output = RQResult<()>> + Send,
+{
+    async fn execute(&self, ctx: CommandContext) -> RQResult<()> {
+        self(ctx).await
+    }
+}
+
+/// 命令执行权限
+#[derive(Clone)]
+pub enum Permission {
+    Anyone,
+    /// 群管理员或群主，好友消息中不可用
+    GroupAdmin,
+    /// 群主，好友消息中不可用
+    GroupOwner,
+    Whitelist(HashSet<i64>),
+    Custom(Arc<dyn Fn(&CommandSource) -> bool + Send + Sync>),
+}
+
+impl Permission {
+    async fn check(&self, source: &CommandSource) -> bool {
+        match self {
+            Permission::Anyone => true,
+            Permission::Whitelist(uins) => uins.contains(&source.sender()),
+            Permission::Custom(f) => f(source),
+            Permission::GroupAdmin | Permission::GroupOwner => {
+                let CommandSource::Group(e) = source else {
+                    return false;
+                };
+                let member = match e
+                    .client
+                    .get_member(e.inner.group_code, e.inner.from_uin)
+                    .await
+                {
+                    Ok(member) => member,
+                    Err(err) => {
+                        tracing::warn!("failed to check command permission: {}", err);
+                        return false;
+                    }
+                };
+                match member.permission {
+                    GroupMemberPermission::Owner => true,
+                    GroupMemberPermission::Administrator => {
+                        matches!(self, Permission::GroupAdmin)
+                    }
+                    GroupMemberPermission::Member => false,
+                }
+            }
+        }
+    }
+}
+
+struct CommandEntry {
+    name: String,
+    description: String,
+    permission: Permission,
+    command: Box<dyn Command>,
+}
+
+/// 命令分发器，处理群消息和好友消息中以前缀开头的命令
+///
+/// 命中命令时停止事件传递，可以放在 [`crate::handler::HandlerChain`] 前面
+///
+/// ```ignore
+/// let commands = CommandDispatcher::new(["/"])
+///     .command("ping", "检查是否在线", Permission::Anyone, |ctx: CommandContext| async move {
+///         ctx.source.reply(MessageChain::new(Text::new("pong".into()))).await?;
+///         Ok(())
+///     });
+/// ```
+pub struct CommandDispatcher {
+    prefixes: Vec<String>,
+    commands: Vec<CommandEntry>,
+    help: bool,
+}
+
+impl CommandDispatcher {
+    pub fn new<I, S>(prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            prefixes: prefixes.into_iter().map(Into::into).collect(),
+            commands: Vec::new(),
+            help: true,
+        }
+    }
+
+    /// 注册命令
+    pub fn command<C>(
+        mut self,
+        name: &str,
+        description: &str,
+        permission: Permission,
+        command: C,
+    ) -> Self
+    where
+        C: Command + 'static,
+    {
+        self.commands.push(CommandEntry {
+            name: name.into(),
+            description: description.into(),
+            permission,
+            command: Box::new(command),
+        });
+        self
+    }
+
+    /// 是否自动响应 help 命令，默认开启
+    pub fn with_help(mut self, help: bool) -> Self {
+        self.help = help;
+        self
+    }
+
+    /// 生成帮助文本
+    pub fn help_text(&self, locale: Locale) -> String {
+        let prefix = self
+            .prefixes
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default();
+        let mut text = String::from(locale.template(Template::CommandHelpHeader));
+        for entry in &self.commands {
+            text.push_str(&format!(
+                "\n{}{} - {}",
+                prefix, entry.name, entry.description
+            ));
+        }
+        text
+    }
+
+    /// 解析命令，第一个文本参数去掉前缀后作为命令名，引号没有闭合时不作为命令
+    fn parse(&self, elements: &MessageChain) -> Option<(String, Vec<Arg>)> {
+        let mut args = Vec::new();
+        for elem in elements.clone() {
+            match elem {
+                RQElem::Text(t) => {
+                    args.extend(split_text_args(&t.content)?.into_iter().map(Arg::Text))
+                }
+                RQElem::At(at) => args.push(Arg::At(at.target)),
+                RQElem::GroupImage(image) => args.push(Arg::GroupImage(image)),
+                RQElem::FriendImage(image) => args.push(Arg::FriendImage(image)),
+                _ => {}
+            }
+        }
+        if args.is_empty() {
+            return None;
+        }
+        let first = args.remove(0);
+        let first = first.as_text()?;
+        let name = self
+            .prefixes
+            .iter()
+            .find_map(|prefix| first.strip_prefix(prefix.as_str()))?;
+        if name.is_empty() {
+            return None;
+        }
+        Some((name.to_owned(), args))
+    }
+
+    async fn dispatch(&self, source: CommandSource) -> Propagation {
+        let Some((name, args)) = self.parse(source.elements()) else {
+            return Propagation::Continue;
+        };
+        if self.help && name == "help" {
+            if let Err(err) = source
+                .reply(MessageChain::new(Text::new(
+                    self.help_text(source.client().locale()),
+                )))
+                .await
+            {
+                tracing::warn!("failed to send command help: {}", err);
+            }
+            return Propagation::Stop;
+        }
+        let Some(entry) = self.commands.iter().find(|c| c.name == name) else {
+            return Propagation::Continue;
+        };
+        if !entry.permission.check(&source).await {
+            tracing::debug!("command {} denied for {}", name, source.sender());
+            return Propagation::Stop;
+        }
+        let ctx = CommandContext { name, args, source };
+        if let Err(err) = entry.command.execute(ctx).await {
+            tracing::warn!("command {} failed: {}", entry.name, err);
+        }
+        Propagation::Stop
+    }
+}
+
+/// 按空白分割文本参数，双引号内的空白不分割，引号没有闭合时返回 None
+fn split_text_args(text: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            '\\' if quoted => current.push(chars.next()?),
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quoted {
+        return None;
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Some(args)
+}
+
+#[async_trait]
+impl ChainHandler for CommandDispatcher {
+    async fn handle_chained(&self, event: QEvent) -> Propagation {
+        match event {
+            QEvent::GroupMessage(e) => self.dispatch(CommandSource::Group(e)).await,
+            QEvent::FriendMessage(e) => self.dispatch(CommandSource::Friend(e)).await,
+            _ => Propagation::Continue,
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for CommandDispatcher {
+    async fn handle(&self, event: QEvent) {
+        self.handle_chained(event).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ricq_core::msg::elem::At;
+
+    use super::*;
+
+    fn chain(elems: Vec<RQElem>) -> MessageChain {
+        let mut chain = MessageChain::default();
+        for elem in elems {
+            match elem {
+                RQElem::Text(t) => chain.push(t),
+                RQElem::At(at) => chain.push(at),
+                _ => unreachable!(),
+            }
+        }
+        chain
+    }
+
+    fn text(s: &str) -> RQElem {
+        RQElem::Text(Text::new(s.into()))
+    }
+
+    fn parse(dispatcher: &CommandDispatcher, elems: Vec<RQElem>) -> Option<(String, Vec<Arg>)> {
+        dispatcher.parse(&chain(elems))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let dispatcher = CommandDispatcher::new(["/", "!"]);
+        let (name, args) = parse(
+            &dispatcher,
+            vec![text("/echo a  b "), RQElem::At(At::new(12345)), text(" c")],
+        )
+        .unwrap();
+        assert_eq!(name, "echo");
+        assert_eq!(args.len(), 4);
+        assert_eq!(args[0].as_text(), Some("a"));
+        assert_eq!(args[1].as_text(), Some("b"));
+        assert_eq!(args[2].as_at(), Some(12345));
+        assert_eq!(args[3].as_text(), Some("c"));
+
+        let (name, args) = parse(&dispatcher, vec![text("!ping")]).unwrap();
+        assert_eq!(name, "ping");
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_quoted() {
+        let dispatcher = CommandDispatcher::new(["/"]);
+        let (name, args) = parse(
+            &dispatcher,
+            vec![text(r#"/say "hello world" "a \"b\" \\" x"" """#)],
+        )
+        .unwrap();
+        assert_eq!(name, "say");
+        let args: Vec<_> = args.iter().map(|a| a.as_text().unwrap()).collect();
+        assert_eq!(args, vec!["hello world", r#"a "b" \"#, "x", ""]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let dispatcher = CommandDispatcher::new(["/"]);
+        assert!(parse(&dispatcher, vec![]).is_none());
+        assert!(parse(&dispatcher, vec![text("  ")]).is_none());
+        assert!(parse(&dispatcher, vec![text("ping")]).is_none());
+        assert!(parse(&dispatcher, vec![text("/ ping")]).is_none());
+        assert!(parse(&dispatcher, vec![RQElem::At(At::new(1)), text("/ping")]).is_none());
+        assert!(parse(&dispatcher, vec![text(r#"/say "unclosed"#)]).is_none());
+        assert!(parse(&dispatcher, vec![text(r#"/say "a\"#)]).is_none());
+    }
+}
@@ -9,9 +9,13 @@ pub async fn after_login(client: &Arc<Client>) {
         tracing::error!("failed to register client: {}", err)
     }
     start_heartbeat(client.clone()).await;
+    start_token_refresh(client.clone()).await;
     if let Err(err) = client.refresh_status().await {
         tracing::error!("failed to refresh status: {}", err)
     }
+    if let Err(err) = client.resume_pending_recalls().await {
+        tracing::error!("failed to resume pending recalls: {}", err)
+    }
 }
 
 /// 如果当前启动心跳，spawn 开始心跳
@@ -22,3 +26,12 @@ pub async fn start_heartbeat(client: Arc<Client>) {
         });
     }
 }
+
+/// 如果配置了 `Config::token_refresh_interval` 且当前未启动，spawn 开始定时换 token
+pub async fn start_token_refresh(client: Arc<Client>) {
+    if !client.token_refresh_enabled.load(Ordering::Relaxed) {
+        tokio::spawn(async move {
+            client.do_token_refresh().await;
+        });
+    }
+}
@@ -1,18 +1,66 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_trait::async_trait;
 use ricq_core::command::wtlogin::{LoginResponse, QRCodeConfirmed, QRCodeState};
 use ricq_core::{RQError, RQResult};
 
 use crate::Client;
 
+/// 滑块验证码求解，用于 [`Client::submit_ticket_with`]
+#[async_trait]
+pub trait CaptchaSolver: Send + Sync {
+    /// 打开 verify_url 完成滑块，返回 ticket
+    async fn solve(&self, verify_url: &str) -> RQResult<String>;
+}
+
+/// 请求 ticket 服务：GET `{url}?url={verify_url}`，响应 body 即 ticket，需要开启 `web` feature
+#[cfg(feature = "web")]
+pub struct HttpCaptchaSolver {
+    pub url: String,
+}
+
+#[cfg(feature = "web")]
+#[async_trait]
+impl CaptchaSolver for HttpCaptchaSolver {
+    async fn solve(&self, verify_url: &str) -> RQResult<String> {
+        let ticket = reqwest::Client::new()
+            .get(&self.url)
+            .query(&[("url", verify_url)])
+            .send()
+            .await
+            .map_err(|e| RQError::Other(format!("ticket request failed: {e}")))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(format!("ticket request failed: {e}")))?;
+        let ticket = ticket.trim();
+        if ticket.is_empty() {
+            return Err(RQError::EmptyField("ticket"));
+        }
+        Ok(ticket.to_owned())
+    }
+}
+
+/// 密码登录，需要滑块时自动使用 solver 完成
+pub async fn password_login_with_captcha(
+    client: &Arc<Client>,
+    uin: i64,
+    password: &str,
+    solver: &dyn CaptchaSolver,
+) -> RQResult<LoginResponse> {
+    match client.password_login(uin, password).await? {
+        LoginResponse::NeedCaptcha(captcha) => client.submit_ticket_with(&captcha, solver, 3).await,
+        resp => Ok(resp),
+    }
+}
+
 /// 扫码登录：自动查询二维码状态，忽略中间结果，成功或失败返回
 pub async fn auto_query_qrcode(client: &Arc<Client>, sig: &[u8]) -> RQResult<()> {
     loop {
         tokio::time::sleep(Duration::from_secs(1)).await;
         let qrcode_state = client.query_qrcode_result(sig).await?;
         match qrcode_state {
-            QRCodeState::Timeout => return Err(RQError::Timeout),
+            QRCodeState::Timeout => return Err(RQError::Other("qrcode expired".into())),
             QRCodeState::Canceled => return Err(RQError::Other("canceled".into())),
             QRCodeState::Confirmed(QRCodeConfirmed {
                 ref tmp_pwd,
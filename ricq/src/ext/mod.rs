@@ -1,4 +1,8 @@
+#[cfg(feature = "audio-codec")]
+pub mod audio;
+pub mod command;
 pub mod common;
 pub mod image;
 pub mod login;
 pub mod reconnect;
+pub mod send_queue;
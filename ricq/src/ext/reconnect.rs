@@ -58,6 +58,7 @@ pub async fn auto_reconnect<T: AsyncRead + AsyncWrite + 'static + Send>(
             continue;
         }
         tracing::info!("succeed to reconnect");
+        client.record_reconnect();
         after_login(&client).await;
         handle.await.ok();
     }
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use ricq_core::msg::MessageChain;
+use ricq_core::structs::MessageReceipt;
+use tokio::sync::{mpsc, oneshot, Notify};
+
+use crate::{Client, RQResult};
+
+/// 消息发送目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SendTarget {
+    Group(i64),
+    Friend(i64),
+}
+
+type SendFn = Arc<
+    dyn Fn(SendTarget, MessageChain) -> BoxFuture<'static, RQResult<MessageReceipt>> + Send + Sync,
+>;
+
+struct Job {
+    message_chain: MessageChain,
+    result: oneshot::Sender<RQResult<MessageReceipt>>,
+    _pending: PendingGuard,
+}
+
+/// 任务完成或被丢弃（发送时 panic，worker 退出）时减少等待数，避免 flush 一直等待
+struct PendingGuard {
+    pending: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        if self.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+/// 发送队列，同一目标的消息按提交顺序依次发送，每条之间间隔 interval
+///
+/// 不同目标之间互不阻塞
+///
+/// ```ignore
+/// let queue = SendQueue::new(client.clone(), Duration::from_millis(500));
+/// for group_code in groups {
+///     queue.push(SendTarget::Group(group_code), message_chain.clone());
+/// }
+/// queue.flush().await;
+/// ```
+pub struct SendQueue {
+    send: SendFn,
+    interval: Duration,
+    senders: Mutex<HashMap<SendTarget, mpsc::UnboundedSender<Job>>>,
+    pending: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl SendQueue {
+    pub fn new(client: Arc<Client>, interval: Duration) -> Self {
+        Self::with_send_fn(
+            interval,
+            Arc::new(move |target, message_chain| {
+                let client = client.clone();
+                Box::pin(async move {
+                    match target {
+                        SendTarget::Group(code) => {
+                            client.send_group_message(code, message_chain).await
+                        }
+                        SendTarget::Friend(uin) => {
+                            client.send_friend_message(uin, message_chain).await
+                        }
+                    }
+                })
+            }),
+        )
+    }
+
+    fn with_send_fn(interval: Duration, send: SendFn) -> Self {
+        Self {
+            send,
+            interval,
+            senders: Default::default(),
+            pending: Default::default(),
+            idle: Default::default(),
+        }
+    }
+
+    /// 加入队列，返回的 Receiver 可以用于获取发送结果，不需要时可以直接丢弃
+    pub fn push(
+        &self,
+        target: SendTarget,
+        message_chain: MessageChain,
+    ) -> oneshot::Receiver<RQResult<MessageReceipt>> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        let job = Job {
+            message_chain,
+            result: tx,
+            _pending: PendingGuard {
+                pending: self.pending.clone(),
+                idle: self.idle.clone(),
+            },
+        };
+        let mut senders = self.senders.lock().unwrap();
+        let sender = senders
+            .entry(target)
+            .or_insert_with(|| self.spawn_worker(target));
+        if let Err(mpsc::error::SendError(job)) = sender.send(job) {
+            // worker 已退出（发送时 panic），重新启动
+            let sender = self.spawn_worker(target);
+            sender.send(job).ok();
+            senders.insert(target, sender);
+        }
+        rx
+    }
+
+    /// 等待发送的消息数
+    pub fn len(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 等待队列中的消息全部发送完成
+    pub async fn flush(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.is_empty() {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    fn spawn_worker(&self, target: SendTarget) -> mpsc::UnboundedSender<Job> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Job>();
+        let send = self.send.clone();
+        let interval = self.interval;
+        tokio::spawn(async move {
+            while let Some(job) = rx.recv().await {
+                let result = send(target, job.message_chain).await;
+                job.result.send(result).ok();
+                tokio::time::sleep(interval).await;
+            }
+        });
+        tx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_flush_after_panic() {
+        let queue = SendQueue::with_send_fn(
+            Duration::ZERO,
+            Arc::new(|target, _| {
+                Box::pin(async move {
+                    if target == SendTarget::Group(1) {
+                        panic!("send failed");
+                    }
+                    Ok(MessageReceipt::default())
+                })
+            }),
+        );
+        let panicked = queue.push(SendTarget::Group(1), MessageChain::default());
+        let dropped = queue.push(SendTarget::Group(1), MessageChain::default());
+        let sent = queue.push(SendTarget::Friend(2), MessageChain::default());
+        tokio::time::timeout(Duration::from_secs(5), queue.flush())
+            .await
+            .expect("flush should return after a job panics");
+        assert!(queue.is_empty());
+        assert!(panicked.await.is_err());
+        assert!(dropped.await.is_err());
+        assert!(sent.await.unwrap().is_ok());
+    }
+}
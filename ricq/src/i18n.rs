@@ -0,0 +1,75 @@
+//! crate 自身生成的文本（错误信息、内置回复模板）的多语言支持
+
+use crate::RQError;
+
+/// 文本语言，通过 Config::locale 设置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    ZhCN,
+    EnUS,
+}
+
+/// 内置回复模板
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// 命令帮助的标题
+    CommandHelpHeader,
+}
+
+impl Locale {
+    pub fn template(self, template: Template) -> &'static str {
+        match (self, template) {
+            (Locale::ZhCN, Template::CommandHelpHeader) => "可用命令:",
+            (Locale::EnUS, Template::CommandHelpHeader) => "Available commands:",
+        }
+    }
+
+    /// 错误信息，EnUS 与 Display 相同
+    pub fn error_message(self, err: &RQError) -> String {
+        if self == Locale::EnUS {
+            return err.to_string();
+        }
+        match err {
+            RQError::Other(s) => format!("其他错误: {}", s),
+            RQError::Decode(s) => format!("解析失败: {}", s),
+            RQError::PbDecode(e) => format!("protobuf 解析失败: {}", e),
+            RQError::EmptyField(s) => format!("字段为空: {}", s),
+            RQError::Utf8(e) => format!("UTF-8 解码失败: {}", e),
+            RQError::CommandNameMismatch(expected, actual) => {
+                format!("命令不匹配，期望 {} 实际 {}", expected, actual)
+            }
+            RQError::Timeout { command, seq } => format!("请求超时: {} (seq {})", command, seq),
+            RQError::Network => "网络错误".into(),
+            RQError::Jce(e) => format!("jce 解析失败: {}", e),
+            RQError::IO(e) => format!("IO 错误: {}", e),
+            RQError::UnknownFlag(f) => format!("未知标志 {}", f),
+            RQError::UnknownEncryptType => "未知加密类型".into(),
+            RQError::InvalidPacketType => "无效的包类型".into(),
+            RQError::InvalidEncryptType => "无效的加密类型".into(),
+            RQError::PacketDropped => "包被丢弃".into(),
+            RQError::SessionExpired => "会话已过期".into(),
+            RQError::UnsuccessfulRetCode(code) => format!("服务端返回错误码 {}", code),
+            RQError::TokenLoginFailed => "token 登录失败".into(),
+            RQError::GetFileCountFailed => "获取文件数量失败".into(),
+            RQError::GetFileListFailed(s) => format!("获取文件列表失败: {}", s),
+            RQError::QuotaExceeded(s) => format!("超出每日次数限制: {}", s),
+            RQError::CaptchaTicketRejected(n) => format!("滑块 ticket 连续 {} 次无效", n),
+            RQError::SmsTooFrequent => "短信请求过于频繁".into(),
+            RQError::SmsCodeRejected(s) => format!("短信验证码错误: {}", s),
+            RQError::ClientStopped => "客户端已停止".into(),
+            RQError::OwnerCannotQuit => "群主需要先转让或解散群才能退群".into(),
+            RQError::RateLimited(s) => format!("请求过于频繁，已被本地限流: {}", s),
+            RQError::RealNameRequired(s) => format!("账号需要实名认证: {}", s),
+            RQError::AccountLevelTooLow(s) => format!("账号等级不足: {}", s),
+            RQError::ServerError {
+                command,
+                code,
+                message,
+            } => format!("服务端返回错误 {}: {} {}", command, code, message),
+            RQError::NeedLogin => "未登录".into(),
+            RQError::BotMuted(group_code) => format!("在群 {} 被禁言", group_code),
+            RQError::InvalidConfig(reason) => format!("配置错误: {}", reason),
+        }
+    }
+}
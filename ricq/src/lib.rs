@@ -4,18 +4,26 @@
 
 pub mod client;
 mod config;
+pub mod engine;
 pub mod ext;
+pub mod i18n;
+pub mod prelude;
 pub mod structs;
 
 pub use client::handler;
 pub use client::Client;
-pub use config::Config;
+pub use config::{
+    CacheConfig, Config, ConfigBuilder, EventMask, FriendRequestPolicy, GroupInviteHook,
+    GroupInvitePolicy, HeartbeatConfig, HeartbeatKind, RateLimitConfig, RateLimitMode,
+    RecentBufferConfig, RequestConfig, SupervisionStrategy,
+};
 pub use device::Device;
 pub use version::Protocol;
 
 pub use ricq_core::command::wtlogin::{
-    LoginDeviceLockLogin, LoginDeviceLocked, LoginNeedCaptcha, LoginResponse, LoginSuccess,
-    LoginUnknownStatus, QRCodeConfirmed, QRCodeImageFetch, QRCodeState,
+    LoginDeviceLockLogin, LoginDeviceLocked, LoginFailed, LoginFailedKind, LoginNeedCaptcha,
+    LoginResponse, LoginSuccess, LoginUnknownStatus, QRCodeConfirmed, QRCodeImageFetch,
+    QRCodeState, SmsRequestResult,
 };
 pub use ricq_core::error::{RQError, RQResult};
 use ricq_core::jce;
@@ -0,0 +1,16 @@
+//! 常用类型，`use ricq::prelude::*;` 即可开始编写 bot
+
+pub use crate::client::event::*;
+pub use crate::client::{Connector, DefaultConnector, NetworkStatus};
+pub use crate::ext::common::after_login;
+pub use crate::handler::{
+    ChainHandler, DefaultHandler, Handler, HandlerChain, PartlyHandler, Propagation, QEvent,
+};
+pub use crate::msg::elem::{At, Face, FriendImage, GroupImage, RQElem, Reply, Text};
+pub use crate::msg::MessageChain;
+pub use crate::structs::{
+    FriendInfo, FriendMessage, GroupInfo, GroupMemberInfo, GroupMemberPermission, GroupMessage,
+    MessageReceipt,
+};
+pub use crate::version::{get_version, Protocol};
+pub use crate::{Client, Config, Device, LoginResponse, RQError, RQResult};
@@ -54,6 +54,30 @@ impl ImageInfo {
         })
     }
 
+    /// 只知道 md5 和大小时使用，尺寸和格式为默认值
+    pub fn from_md5(md5: Vec<u8>, size: u32) -> Self {
+        ImageInfo {
+            filename: format!("{}.png", encode_hex(&md5)),
+            md5,
+            width: 1280,
+            height: 720,
+            image_type: 1001,
+            size,
+        }
+    }
+
+    /// 已上传的群图片的信息，用于在其他群查询同一张图片
+    pub fn from_group_image(image: &GroupImage) -> Self {
+        ImageInfo {
+            filename: format!("{}.png", encode_hex(&image.md5)),
+            md5: image.md5.clone(),
+            width: image.width,
+            height: image.height,
+            image_type: image.image_type,
+            size: image.size,
+        }
+    }
+
     // download path: "/{to_uin}-{unknown?}-{md5}"
     pub fn into_friend_image(self, res_id: String, download_path: String) -> FriendImage {
         FriendImage {
@@ -1,4 +1,8 @@
 pub use image_info::*;
+pub use ricq_core::command::profile_service::{
+    JoinGroupRequest, NewFriendRequest, RequestDecision, SelfInvited,
+};
 pub use ricq_core::structs::*;
+pub use ricq_core::web::{AvatarSize, GroupHonorInfo, GroupHonorType};
 
 mod image_info;